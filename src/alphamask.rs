@@ -1,76 +1,280 @@
-//! Alphamask Adapator
+//! Alpha Masks and Alphamask Adapator
 
-//use crate::math::blend_pix;
-use crate::color::Rgb8;
-use crate::color::Gray8;
-use crate::pixfmt::Pixfmt;
+use crate::color::Rgba8;
 
+use crate::AlphaMask;
 use crate::Color;
 use crate::Pixel;
+use crate::PixelData;
 use crate::Source;
-use crate::math::lerp_u8;
-use crate::math::multiply_u8;
-use crate::color::Rgba8;
 
-/// Alpha Mask Adaptor
-pub struct AlphaMaskAdaptor<T> where Pixfmt<T>: Pixel + Source {
-    pub rgb: Pixfmt<T>,
-    pub alpha: Pixfmt<Gray8>,
+/// Component of a mask pixel used as the mask value
+#[derive(Debug,Copy,Clone,PartialEq)]
+enum MaskChannel {
+    /// Single component at byte offset
+    Channel(usize),
+    /// Gray value of red, green and blue components at offsets 0, 1 and 2
+    RgbToGray,
 }
 
-
-impl<T> AlphaMaskAdaptor<T> where Pixfmt<T>: Pixel + Source {
-    /// Create a new Alpha Mask Adapator from a two PixelFormats
-    pub fn new(rgb: Pixfmt<T>, alpha: Pixfmt<Gray8>) -> Self {
-        Self { rgb, alpha }
-    }
-    /// Blend a set of colors starting at (x,y) with a length
-    ///
-    /// Background color is from the rgb image and
-    /// alpha form the gray scale
-    ///
-    /// Calls blend_pix
-    //
-    // From https://stackoverflow.com/a/746937 :
-    // out = alpha * new + (1 - alpha) * old
-    //   p[j]  = out
-    //   alpha = alpha
-    //   new   = c
-    //   old   = p[j]
-    pub fn blend_color_hspan(&mut self, x: usize, y: usize, n: usize,
-                             colors: &[Rgb8], _cover: usize) {
-        //for i in 0 .. n {
-        //assert!(1==2);
-        assert_eq!(n, colors.len());
-        for (i, color) in colors.iter().enumerate() {
-            let pix = &mut self.rgb.get((x+i,y));
-            let alpha = u64::from(self.alpha.raw((x+i,y)).value);
-            let pix = blend_pix(pix, color, alpha);
-            self.rgb.set((x+i,y), pix);
+impl MaskChannel {
+    /// Mask value of pixel (`x`,`y`) in `pixf`
+    fn value<T: PixelData>(self, pixf: &T, x: usize, y: usize) -> u64 {
+        let bpp = T::bpp();
+        let p = &pixf.row(y)[x * bpp .. (x + 1) * bpp];
+        match self {
+            MaskChannel::Channel(i) => u64::from(p[i]),
+            MaskChannel::RgbToGray =>
+                (u64::from(p[0]) * 77 + u64::from(p[1]) * 150 + u64::from(p[2]) * 29) >> 8,
         }
     }
 }
-/// Blend foreground and background pixels with an cover value
+
+/// Combine a `cover` with a mask value `m`
+fn combine(cover: u64, m: u64) -> u64 {
+    (cover * m + 255) >> 8
+}
+
+/// Alpha Mask read from a Pixel Format, with clipping
 ///
-/// Color components are computed by:
+/// Locations outside of the mask have a mask value of 0
 ///
-/// out = (alpha * cover) * (c - p)
+///     use agg::{AlphaMask,AlphaMaskU8,Pixfmt,Pixel,Gray8};
 ///
-/// Computations are conducted using fixed point math
+///     let mut pix = Pixfmt::<Gray8>::new(2,2);
+///     pix.copy_pixel(1, 0, Gray8::new(128));
+///     let mask = AlphaMaskU8::new(pix);
+///     assert_eq!(mask.pixel(1, 0), 128);
+///     assert_eq!(mask.pixel(5, 0), 0);
+///     assert_eq!(mask.combine_pixel(1, 0, 255), 128);
 ///
-/// see [Alpha Compositing](https://en.wikipedia.org/wiki/Alpha_compositing)
+#[derive(Debug)]
+pub struct AlphaMaskU8<T> {
+    /// Pixel Format holding the mask
+    pub pixf: T,
+    channel: MaskChannel,
+}
+
+/// Alpha Mask read from a Pixel Format, without clipping
+///
+/// All locations must be inside the mask
+#[derive(Debug)]
+pub struct AlphaMaskNoClipU8<T> {
+    /// Pixel Format holding the mask
+    pub pixf: T,
+    channel: MaskChannel,
+}
+
+impl<T> AlphaMaskU8<T> where T: PixelData {
+    /// Mask value at (`x`,`y`), 0 if outside of the mask
+    fn value(&self, x: i64, y: i64) -> u64 {
+        if x < 0 || y < 0 || x >= self.pixf.width() as i64 || y >= self.pixf.height() as i64 {
+            return 0;
+        }
+        self.channel.value(&self.pixf, x as usize, y as usize)
+    }
+}
 
-fn blend_pix<C1: Color, C2: Color>(p: &C1, c: &C2, cover: u64) -> Rgba8 {
+impl<T> AlphaMaskNoClipU8<T> where T: PixelData {
+    /// Mask value at (`x`,`y`)
+    fn value(&self, x: i64, y: i64) -> u64 {
+        self.channel.value(&self.pixf, x as usize, y as usize)
+    }
+}
 
-    assert!(c.alpha() >= 0.0);
-    assert!(c.alpha() <= 1.0);
+macro_rules! impl_alpha_mask {
+    ($mask:ident) => {
+        impl<T> $mask<T> where T: PixelData {
+            /// Create a mask from the first component of `pixf`
+            ///
+            /// For a Gray Pixel Format this is the gray value
+            pub fn new(pixf: T) -> Self {
+                Self::with_channel(pixf, 0)
+            }
+            /// Create a mask from the component at byte offset `channel`
+            ///   of each pixel
+            ///
+            /// # Panics
+            ///
+            /// If `channel` is not within a pixel
+            pub fn with_channel(pixf: T, channel: usize) -> Self {
+                assert!(channel < T::bpp(), "channel {} >= {} bpp", channel, T::bpp());
+                Self { pixf, channel: MaskChannel::Channel(channel) }
+            }
+            /// Create a mask from the gray value of red, green and blue
+            ///   components, at byte offsets 0, 1 and 2
+            ///
+            /// # Panics
+            ///
+            /// If pixels have less than 3 components
+            pub fn rgb_to_gray(pixf: T) -> Self {
+                assert!(T::bpp() >= 3, "rgb to gray mask requires 3 components");
+                Self { pixf, channel: MaskChannel::RgbToGray }
+            }
+        }
+        impl<T> AlphaMask for $mask<T> where T: PixelData {
+            fn pixel(&self, x: i64, y: i64) -> u64 {
+                self.value(x, y)
+            }
+            fn combine_pixel(&self, x: i64, y: i64, cover: u64) -> u64 {
+                combine(cover, self.value(x, y))
+            }
+            fn fill_hspan(&self, x: i64, y: i64, dst: &mut [u64]) {
+                for (i, d) in dst.iter_mut().enumerate() {
+                    *d = self.value(x + i as i64, y);
+                }
+            }
+            fn combine_hspan(&self, x: i64, y: i64, dst: &mut [u64]) {
+                for (i, d) in dst.iter_mut().enumerate() {
+                    *d = combine(*d, self.value(x + i as i64, y));
+                }
+            }
+            fn fill_vspan(&self, x: i64, y: i64, dst: &mut [u64]) {
+                for (i, d) in dst.iter_mut().enumerate() {
+                    *d = self.value(x, y + i as i64);
+                }
+            }
+            fn combine_vspan(&self, x: i64, y: i64, dst: &mut [u64]) {
+                for (i, d) in dst.iter_mut().enumerate() {
+                    *d = combine(*d, self.value(x, y + i as i64));
+                }
+            }
+        }
+    }
+}
 
-    let alpha = multiply_u8(c.alpha8(), cover as u8);
+impl_alpha_mask!(AlphaMaskU8);
+impl_alpha_mask!(AlphaMaskNoClipU8);
 
-    let red   = lerp_u8(p.red8(),   c.red8(),   alpha);
-    let green = lerp_u8(p.green8(), c.green8(), alpha);
-    let blue  = lerp_u8(p.blue8(),  c.blue8(),  alpha);
-    let alpha = lerp_u8(p.alpha8(), c.alpha8(), alpha);
+/// Alpha Mask Adaptor
+///
+/// Wraps a Pixel Format so all drawing is modulated by an Alpha Mask
+///
+/// The adaptor is itself a Pixel Format and can be used in a
+///   [`RenderingBase`](../base/struct.RenderingBase.html)
+#[derive(Debug)]
+pub struct AlphaMaskAdaptor<P, M> {
+    /// Pixel Format being drawn onto
+    pub pixf: P,
+    /// Alpha Mask applied to drawing
+    pub mask: M,
+}
 
-    Rgba8::new(red, green, blue, alpha)
+impl<P, M> AlphaMaskAdaptor<P, M> where P: Pixel, M: AlphaMask {
+    /// Create a new Alpha Mask Adapator from a Pixel Format and a Mask
+    pub fn new(pixf: P, mask: M) -> Self {
+        Self { pixf, mask }
+    }
+}
+
+impl<P, M> Source for AlphaMaskAdaptor<P, M> where P: Source {
+    fn get(&self, id: (usize, usize)) -> Rgba8 {
+        self.pixf.get(id)
+    }
+}
+
+impl<P, M> Pixel for AlphaMaskAdaptor<P, M> where P: Pixel, M: AlphaMask {
+    fn cover_mask() -> u64 { P::cover_mask() }
+    fn bpp() -> usize { P::bpp() }
+    fn as_bytes(&self) -> &[u8] {
+        self.pixf.as_bytes()
+    }
+    fn to_file<Q: AsRef<std::path::Path>>(&self, filename: Q) -> Result<(),std::io::Error> {
+        self.pixf.to_file(filename)
+    }
+    fn width(&self) -> usize {
+        self.pixf.width()
+    }
+    fn height(&self) -> usize {
+        self.pixf.height()
+    }
+    /// Copy `c` to `id` through the mask
+    fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
+        let m = self.mask.pixel(id.0 as i64, id.1 as i64);
+        self.pixf.copy_or_blend_pix_with_cover(id, c, m);
+    }
+    fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
+        let (x, y) = (id.0 as i64, id.1 as i64);
+        let mut span = vec![0; n];
+        self.mask.fill_hspan(x, y, &mut span);
+        self.pixf.blend_solid_hspan(x, y, n as i64, c, &span);
+    }
+    fn blend_pix<C: Color>(&mut self, id: (usize, usize), c: C, cover: u64) {
+        let cover = self.mask.combine_pixel(id.0 as i64, id.1 as i64, cover);
+        self.pixf.blend_pix(id, c, cover);
+    }
+    fn fill<C: Color>(&mut self, color: C) {
+        let (w, h) = (self.width(), self.height());
+        for y in 0 .. h {
+            self.setn((0, y), w, color);
+        }
+    }
+    fn blend_hline<C: Color>(&mut self, x: i64, y: i64, len: i64, color: C, cover: u64) {
+        let mut span = vec![cover; len as usize];
+        self.mask.combine_hspan(x, y, &mut span);
+        self.pixf.blend_solid_hspan(x, y, len, color, &span);
+    }
+    fn blend_solid_hspan<C: Color>(&mut self, x: i64, y: i64, len: i64, color: C, covers: &[u64]) {
+        let mut span = covers.to_vec();
+        self.mask.combine_hspan(x, y, &mut span);
+        self.pixf.blend_solid_hspan(x, y, len, color, &span);
+    }
+    fn blend_vline<C: Color>(&mut self, x: i64, y: i64, len: i64, c: C, cover: u64) {
+        let mut span = vec![cover; len as usize];
+        self.mask.combine_vspan(x, y, &mut span);
+        self.pixf.blend_solid_vspan(x, y, len, c, &span);
+    }
+    fn blend_solid_vspan<C: Color>(&mut self, x: i64, y: i64, len: i64, c: C, covers: &[u64]) {
+        let mut span = covers.to_vec();
+        self.mask.combine_vspan(x, y, &mut span);
+        self.pixf.blend_solid_vspan(x, y, len, c, &span);
+    }
+    fn blend_color_hspan<C: Color>(&mut self, x: i64, y: i64, len: i64, colors: &[C], covers: &[u64], cover: u64) {
+        let mut span = if covers.is_empty() { vec![cover; len as usize] } else { covers.to_vec() };
+        self.mask.combine_hspan(x, y, &mut span);
+        self.pixf.blend_color_hspan(x, y, len, colors, &span, cover);
+    }
+    fn blend_color_vspan<C: Color>(&mut self, x: i64, y: i64, len: i64, colors: &[C], covers: &[u64], cover: u64) {
+        let mut span = if covers.is_empty() { vec![cover; len as usize] } else { covers.to_vec() };
+        self.mask.combine_vspan(x, y, &mut span);
+        self.pixf.blend_color_vspan(x, y, len, colors, &span, cover);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixfmt::Pixfmt;
+    use crate::color::{Gray8,Rgb8};
+    #[test]
+    fn alpha_mask_test() {
+        let mut pix = Pixfmt::<Rgb8>::new(2,1);
+        pix.copy_pixel(0,0,Rgb8::new(10,20,255));
+        pix.copy_pixel(1,0,Rgb8::new(255,255,255));
+        let mask = AlphaMaskNoClipU8::with_channel(pix, 2);
+        assert_eq!(mask.pixel(0,0), 255);
+        let mut span = [255, 128];
+        mask.combine_hspan(0, 0, &mut span);
+        assert_eq!(span, [255, 128]);
+
+        let mask = AlphaMaskU8::rgb_to_gray(mask.pixf);
+        assert_eq!(mask.pixel(0,0), (10*77 + 20*150 + 255*29) >> 8);
+        assert_eq!(mask.pixel(1,0), 255);
+        let mut span = [7; 4];
+        mask.fill_vspan(1, -1, &mut span);
+        assert_eq!(span, [0, 255, 0, 0]);
+
+        // Drawing through the mask
+        let mut alpha = Pixfmt::<Gray8>::new(3,1);
+        alpha.copy_hline(0, 0, 3, Gray8::new(0));
+        alpha.copy_pixel(1, 0, Gray8::new(255));
+        alpha.copy_pixel(2, 0, Gray8::new(128));
+        let mut pix = Pixfmt::<Rgb8>::new(3,1);
+        pix.fill(Rgb8::black());
+        let mut mix = AlphaMaskAdaptor::new(pix, AlphaMaskU8::new(alpha));
+        mix.fill(Rgb8::white());
+        assert_eq!(mix.get((0,0)), Rgba8::black());
+        assert_eq!(mix.get((1,0)), Rgba8::white());
+        assert_eq!(mix.get((2,0)), Rgba8::new(128,128,128,255));
+    }
 }
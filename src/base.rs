@@ -2,9 +2,13 @@
 
 use crate::color::*;
 use crate::Pixel;
+use crate::PixelData;
+use crate::pixfmt::Pixels;
 use crate::Color;
+use crate::clip::Rectangle;
 use std::cmp::min;
 use std::cmp::max;
+use std::any::TypeId;
 
 
 /// Rendering Base
@@ -13,12 +17,18 @@ use std::cmp::max;
 pub struct RenderingBase<T> {
     /// Pixel Format
     pub pixf: T,
+    /// Region drawing is limited to, inclusive, `None` if nothing is visible
+    clip_box: Option<Rectangle<i64>>,
 }
 
 impl<T> RenderingBase<T> where T: Pixel {
     /// Create new Rendering Base from Pixel Format
+    ///
+    /// The clip box is the entire image
     pub fn new(pixf: T) -> RenderingBase<T> {
-        RenderingBase { pixf }
+        let mut ren = RenderingBase { pixf, clip_box: None };
+        ren.reset_clipping(true);
+        ren
     }
     pub fn as_bytes(&self) -> &[u8] {
         self.pixf.as_bytes()
@@ -30,11 +40,125 @@ impl<T> RenderingBase<T> where T: Pixel {
     pub fn clear(&mut self, color: Rgba8) {
         self.pixf.fill(color);
     }
-    /// Get Image size
-    pub fn limits(&self) -> (i64,i64,i64,i64) {
+    /// Set the clip box to (`x1`,`y1`) - (`x2`,`y2`), inclusive
+    ///
+    /// The clip box is limited to the image.  If the box does not overlap
+    ///   the image nothing is visible and `false` is returned
+    ///
+    ///     use agg::{Pixfmt,Pixel,Source,RenderingBase,Rgb8,Rgba8};
+    ///
+    ///     let mut ren_base = RenderingBase::new(Pixfmt::<Rgb8>::new(10,10));
+    ///     ren_base.clear(Rgba8::white());
+    ///     assert!(ren_base.clip_box(2, 2, 20, 5));
+    ///     assert!(ren_base.inbox(9, 5));
+    ///     assert!(! ren_base.inbox(9, 6));
+    ///     ren_base.blend_hline(-5, 3, 15, Rgba8::black(), 255);
+    ///     assert_eq!(ren_base.pixf.get((1,3)), Rgba8::white());
+    ///     assert_eq!(ren_base.pixf.get((2,3)), Rgba8::black());
+    ///     assert_eq!(ren_base.pixf.get((9,3)), Rgba8::black());
+    ///
+    ///     assert!(! ren_base.clip_box(-5, -5, -1, -1));
+    ///     let (xmin, xmax, ymin, ymax) = ren_base.limits();
+    ///     assert!(xmin > xmax && ymin > ymax);
+    ///
+    pub fn clip_box(&mut self, x1: i64, y1: i64, x2: i64, y2: i64) -> bool {
+        let (x1, x2) = if x1 > x2 { (x2, x1) } else { (x1, x2) };
+        let (y1, y2) = if y1 > y2 { (y2, y1) } else { (y1, y2) };
         let w = self.pixf.width() as i64;
         let h = self.pixf.height() as i64;
-        (0, w-1, 0, h-1)
+        let (x1, y1) = (max(x1, 0), max(y1, 0));
+        let (x2, y2) = (min(x2, w - 1), min(y2, h - 1));
+        if x1 > x2 || y1 > y2 {
+            self.clip_box = None;
+            return false;
+        }
+        self.clip_box = Some(Rectangle::new(x1, y1, x2, y2));
+        true
+    }
+    /// Reset the clip box to the entire image, or to nothing if not `visibility`
+    pub fn reset_clipping(&mut self, visibility: bool) {
+        if visibility {
+            let w = self.pixf.width() as i64;
+            let h = self.pixf.height() as i64;
+            self.clip_box(0, 0, w - 1, h - 1);
+        } else {
+            self.clip_box = None;
+        }
+    }
+    /// Check if (`x`,`y`) is inside the clip box
+    pub fn inbox(&self, x: i64, y: i64) -> bool {
+        let (xmin,xmax,ymin,ymax) = self.limits();
+        x >= xmin && x <= xmax && y >= ymin && y <= ymax
+    }
+    /// Get clip box limits as (xmin, xmax, ymin, ymax)
+    ///
+    /// If nothing is visible, the minimum values are greater than the maximum
+    pub fn limits(&self) -> (i64,i64,i64,i64) {
+        match self.clip_box {
+            Some(r) => (r.x1(), r.x2(), r.y1(), r.y2()),
+            None => (0, -1, 0, -1),
+        }
+    }
+    /// Copy a color to pixel (`x`,`y`)
+    pub fn copy_pixel<C: Color>(&mut self, x: i64, y: i64, c: C) {
+        if self.inbox(x, y) {
+            self.pixf.set((x as usize, y as usize), c);
+        }
+    }
+    /// Blend a color with pixel (`x`,`y`)
+    pub fn blend_pixel<C: Color>(&mut self, x: i64, y: i64, c: C, cover: u64) {
+        if self.inbox(x, y) {
+            self.pixf.copy_or_blend_pix_with_cover((x as usize, y as usize), c, cover);
+        }
+    }
+    /// Copy a color along y-row from x1 to x2
+    pub fn copy_hline<C: Color>(&mut self, x1: i64, y: i64, x2: i64, c: C) {
+        let (xmin,xmax,ymin,ymax) = self.limits();
+        let (x1,x2) = if x2 > x1 { (x1,x2) } else { (x2,x1) };
+        if y > ymax || y < ymin || x1 > xmax || x2 < xmin {
+            return;
+        }
+        let x1 = max(x1, xmin);
+        let x2 = min(x2, xmax);
+        self.pixf.setn((x1 as usize, y as usize), (x2 - x1 + 1) as usize, c);
+    }
+    /// Copy a color along x-column from y1 to y2
+    pub fn copy_vline<C: Color>(&mut self, x: i64, y1: i64, y2: i64, c: C) {
+        let (xmin,xmax,ymin,ymax) = self.limits();
+        let (y1,y2) = if y2 > y1 { (y1,y2) } else { (y2,y1) };
+        if x > xmax || x < xmin || y1 > ymax || y2 < ymin {
+            return;
+        }
+        for y in max(y1, ymin) ..= min(y2, ymax) {
+            self.pixf.set((x as usize, y as usize), c);
+        }
+    }
+    /// Blend a color along x-column from y1 to y2
+    pub fn blend_vline<C: Color>(&mut self, x: i64, y1: i64, y2: i64, c: C, cover: u64) {
+        let (xmin,xmax,ymin,ymax) = self.limits();
+        let (y1,y2) = if y2 > y1 { (y1,y2) } else { (y2,y1) };
+        if x > xmax || x < xmin || y1 > ymax || y2 < ymin {
+            return;
+        }
+        let y1 = max(y1, ymin);
+        let y2 = min(y2, ymax);
+        self.pixf.blend_vline(x, y1, y2 - y1 + 1, c, cover);
+    }
+    /// Copy a color to the rectangle (`x1`,`y1`) - (`x2`,`y2`), inclusive
+    pub fn copy_bar<C: Color>(&mut self, x1: i64, y1: i64, x2: i64, y2: i64, c: C) {
+        let (y1,y2) = if y2 > y1 { (y1,y2) } else { (y2,y1) };
+        let (_,_,ymin,ymax) = self.limits();
+        for y in max(y1, ymin) ..= min(y2, ymax) {
+            self.copy_hline(x1, y, x2, c);
+        }
+    }
+    /// Blend a color with the rectangle (`x1`,`y1`) - (`x2`,`y2`), inclusive
+    pub fn blend_bar<C: Color>(&mut self, x1: i64, y1: i64, x2: i64, y2: i64, c: C, cover: u64) {
+        let (y1,y2) = if y2 > y1 { (y1,y2) } else { (y2,y1) };
+        let (_,_,ymin,ymax) = self.limits();
+        for y in max(y1, ymin) ..= min(y2, ymax) {
+            self.blend_hline(x1, y, x2, c, cover);
+        }
     }
     /// Blend a color along y-row from x1 to x2
     pub fn blend_hline<C: Color>(&mut self, x1: i64, y: i64, x2: i64, c: C, cover: u64) {
@@ -156,3 +280,128 @@ impl<T> RenderingBase<T> where T: Pixel {
     }
 }
 
+impl<T> RenderingBase<T> where T: PixelData {
+    /// Source rectangle to transfer from `src` to (`dx`,`dy`) offset,
+    ///   clipped to the source and the image
+    fn clip_rect_area<S: Pixel>(&self, src: &S, rect: Option<Rectangle<i64>>, dx: i64, dy: i64)
+                                -> Option<(i64,i64,i64,i64)> {
+        let (sw, sh) = (src.width() as i64, src.height() as i64);
+        let (x1, y1, x2, y2) = match rect {
+            Some(r) => (r.x1(), r.y1(), r.x2(), r.y2()),
+            None => (0, 0, sw - 1, sh - 1),
+        };
+        let (xmin,xmax,ymin,ymax) = self.limits();
+        let x1 = max(max(x1, 0), xmin - dx);
+        let y1 = max(max(y1, 0), ymin - dy);
+        let x2 = min(min(x2, sw - 1), xmax - dx);
+        let y2 = min(min(y2, sh - 1), ymax - dy);
+        if x1 > x2 || y1 > y2 {
+            None
+        } else {
+            Some((x1, y1, x2, y2))
+        }
+    }
+    /// Copy pixels from `src` into the image
+    ///
+    /// Pixels within `rect` of `src`, or all of `src` if `None`, are copied
+    ///   to the same location offset by (`dx`,`dy`).  The area is clipped
+    ///   to the image; `src` may have a different Pixel Format, with colors
+    ///   premultiplied or demultiplied as needed
+    ///
+    ///     use agg::{Pixfmt,Pixel,Source,RenderingBase,Rectangle,Rgb8,Rgba8};
+    ///
+    ///     let mut sprite = Pixfmt::<Rgba8>::new(4,4);
+    ///     sprite.fill(Rgba8::new(255,0,0,255));
+    ///     let mut ren_base = RenderingBase::new(Pixfmt::<Rgb8>::new(10,10));
+    ///     ren_base.clear(Rgba8::white());
+    ///     ren_base.copy_from(&sprite, Some(Rectangle::new(1,1,2,2)), 7, 7);
+    ///     assert_eq!(ren_base.pixf.get((8,8)), Rgba8::new(255,0,0,255));
+    ///     assert_eq!(ren_base.pixf.get((9,9)), Rgba8::new(255,0,0,255));
+    ///     assert_eq!(ren_base.pixf.get((7,7)), Rgba8::white());
+    ///
+    pub fn copy_from<S>(&mut self, src: &S, rect: Option<Rectangle<i64>>, dx: i64, dy: i64)
+        where S: PixelData, S::Color: 'static, T::Color: 'static
+    {
+        self.transfer_from(src, rect, dx, dy, None);
+    }
+    /// Blend pixels from `src` into the image with a global alpha `cover`
+    ///
+    /// Pixels within `rect` of `src`, or all of `src` if `None`, are blended
+    ///   using their alpha combined with `cover` to the same location offset
+    ///   by (`dx`,`dy`).  The area is clipped to the image; `src` may have a
+    ///   different Pixel Format, with colors premultiplied or demultiplied
+    ///   as needed
+    pub fn blend_from<S>(&mut self, src: &S, rect: Option<Rectangle<i64>>, dx: i64, dy: i64, cover: u64)
+        where S: PixelData, S::Color: 'static, T::Color: 'static
+    {
+        self.transfer_from(src, rect, dx, dy, Some(cover));
+    }
+    /// Copy, or blend with `cover`, pixels from `src` into the image
+    ///
+    /// Copies between images of the same Pixel Format are done byte for
+    ///   byte, otherwise colors are converted from the stored color type of
+    ///   `src` and premultiplied or demultiplied to match the image
+    fn transfer_from<S>(&mut self, src: &S, rect: Option<Rectangle<i64>>, dx: i64, dy: i64, cover: Option<u64>)
+        where S: PixelData, S::Color: 'static, T::Color: 'static
+    {
+        let (x1, y1, x2, y2) = match self.clip_rect_area(src, rect, dx, dy) {
+            Some(area) => area,
+            None => return,
+        };
+        let same = TypeId::of::<S::Color>() == TypeId::of::<T::Color>()
+            && S::bpp() == T::bpp();
+        if same && cover.is_none() {
+            let n = S::bpp();
+            let (a, b) = (x1 as usize * n, (x2 + 1) as usize * n);
+            let off = dx as usize * n;
+            for y in y1 ..= y2 {
+                let row = &src.row(y as usize)[a .. b];
+                self.pixf.row_mut((y + dy) as usize)[a + off .. b + off].copy_from_slice(row);
+            }
+            return;
+        }
+        for y in y1 ..= y2 {
+            for x in x1 ..= x2 {
+                let c = src.pixel(x as usize, y as usize);
+                let id = ((x + dx) as usize, (y + dy) as usize);
+                match (S::PREMULTIPLIED, T::PREMULTIPLIED) {
+                    (false, true) => put_pix(&mut self.pixf, id, Rgba8::from_trait(c).premultiply(), cover),
+                    (true, false) => put_pix(&mut self.pixf, id, Rgba8pre::from_trait(c).demultiply(), cover),
+                    _ => put_pix(&mut self.pixf, id, c, cover),
+                }
+            }
+        }
+    }
+}
+
+/// Copy `c` to the pixel at `id`, or blend it with `cover`
+fn put_pix<T: Pixel, C: Color>(pixf: &mut T, id: (usize, usize), c: C, cover: Option<u64>) {
+    match cover {
+        None => pixf.set(id, c),
+        Some(cover) => pixf.copy_or_blend_pix_with_cover(id, c, cover),
+    }
+}
+
+impl<T> RenderingBase<T> where T: PixelData {
+    /// Bytes of row `y`, without padding
+    pub fn row(&self, y: usize) -> &[u8] {
+        self.pixf.row(y)
+    }
+    /// Mutable bytes of row `y`, without padding
+    pub fn row_mut(&mut self, y: usize) -> &mut [u8] {
+        self.pixf.row_mut(y)
+    }
+    /// Pixel at (`x`,`y`) in its stored color type
+    pub fn pixel(&self, x: usize, y: usize) -> T::Color {
+        self.pixf.pixel(x, y)
+    }
+    /// Iterator over the pixels of row `y`
+    pub fn row_pixels(&self, y: usize) -> Pixels<'_, T> {
+        self.pixf.row_pixels(y)
+    }
+    /// Iterator over all pixels, top row first
+    pub fn pixels(&self) -> Pixels<'_, T> {
+        self.pixf.pixels()
+    }
+}
+
@@ -0,0 +1,384 @@
+//! Stack and Recursive Blur
+//!
+//! Blurs operate independently on each component of a Pixel Format,
+//!   first along rows with the x radius, then along columns with the y
+//!   radius.  Colors with alpha should be premultiplied, i.e. [`Rgba8pre`],
+//!   to avoid dark fringes around transparent areas.
+//!
+//! 16-bit and floating point formats are blurred at their own precision,
+//!   packed formats on their components expanded to 8 bits and [`Srgba8`]
+//!   on linear components, so blurred edges do not darken.
+//!
+//! [`Rgba8pre`]: ../color/struct.Rgba8pre.html
+//! [`Srgba8`]: ../color/struct.Srgba8.html
+
+use crate::buffer::RenderingBuffer;
+use crate::color::*;
+use crate::pixfmt::Pixfmt;
+
+use crate::Color;
+use crate::Pixel;
+
+use std::cmp::min;
+
+/// Conversion between stored pixels and the components that are blurred
+trait BlurComponents {
+    /// Components per pixel
+    const N: usize;
+    /// Components are whole numbers, stack blur truncates its averages
+    const INTEGER: bool = true;
+    /// Decode the pixel stored in `p` into `v`
+    fn decode(p: &[u8], v: &mut [f64]);
+    /// Encode `v` into the pixel stored in `p`, rounding to the nearest value
+    fn encode(v: &[f64], p: &mut [u8]);
+}
+
+macro_rules! impl_blur_components8 {
+    ($($color:ty : $n:expr),*) => { $(
+        impl BlurComponents for $color {
+            const N: usize = $n;
+            fn decode(p: &[u8], v: &mut [f64]) {
+                for (v, &p) in v.iter_mut().zip(p) {
+                    *v = f64::from(p);
+                }
+            }
+            fn encode(v: &[f64], p: &mut [u8]) {
+                for (p, v) in p.iter_mut().zip(v) {
+                    *p = v.round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+    )* }
+}
+
+impl_blur_components8!(Rgb8: 3, Rgba8: 4, Rgba8pre: 4, Gray8: 2,
+                       Bgr8: 3, Bgra8: 4, Argb8: 4, Abgr8: 4);
+
+macro_rules! impl_blur_components16 {
+    ($($color:ty : $n:expr),*) => { $(
+        impl BlurComponents for $color {
+            const N: usize = $n;
+            fn decode(p: &[u8], v: &mut [f64]) {
+                for (v, p) in v.iter_mut().zip(p.chunks_exact(2)) {
+                    *v = f64::from(u16::from_ne_bytes([p[0], p[1]]));
+                }
+            }
+            fn encode(v: &[f64], p: &mut [u8]) {
+                for (p, v) in p.chunks_exact_mut(2).zip(v) {
+                    let x = v.round().clamp(0.0, 65535.0) as u16;
+                    p.copy_from_slice(&x.to_ne_bytes());
+                }
+            }
+        }
+    )* }
+}
+
+impl_blur_components16!(Rgb16: 3, Rgba16: 4, Gray16: 2);
+
+impl BlurComponents for Rgba32 {
+    const N: usize = 4;
+    const INTEGER: bool = false;
+    fn decode(p: &[u8], v: &mut [f64]) {
+        for (v, p) in v.iter_mut().zip(p.chunks_exact(4)) {
+            *v = f64::from(f32::from_ne_bytes([p[0], p[1], p[2], p[3]]));
+        }
+    }
+    fn encode(v: &[f64], p: &mut [u8]) {
+        for (p, &v) in p.chunks_exact_mut(4).zip(v) {
+            p.copy_from_slice(&(v as f32).to_ne_bytes());
+        }
+    }
+}
+
+/// Color components are linear 16-bit values, alpha stays 8-bit
+impl BlurComponents for Srgba8 {
+    const N: usize = 4;
+    fn decode(p: &[u8], v: &mut [f64]) {
+        let lut = SrgbLut::get();
+        for i in 0 .. 3 {
+            v[i] = f64::from(lut.dir(p[i]));
+        }
+        v[3] = f64::from(p[3]);
+    }
+    fn encode(v: &[f64], p: &mut [u8]) {
+        let lut = SrgbLut::get();
+        for i in 0 .. 3 {
+            p[i] = lut.inv(v[i].round().clamp(0.0, 65535.0) as u16);
+        }
+        p[3] = v[3].round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+macro_rules! impl_blur_components_packed {
+    ($($color:ident),*) => { $(
+        impl BlurComponents for $color {
+            const N: usize = 3;
+            fn decode(p: &[u8], v: &mut [f64]) {
+                let c = $color { value: u16::from_ne_bytes([p[0], p[1]]) };
+                v[0] = f64::from(c.red8());
+                v[1] = f64::from(c.green8());
+                v[2] = f64::from(c.blue8());
+            }
+            fn encode(v: &[f64], p: &mut [u8]) {
+                let c = |i: usize| v[i].round().clamp(0.0, 255.0) as u8;
+                p[..2].copy_from_slice(&$color::new(c(0), c(1), c(2)).value.to_ne_bytes());
+            }
+        }
+    )* }
+}
+
+impl_blur_components_packed!(Rgb565, Rgb555);
+
+/// Stack Blur a single line of `n` components per pixel with radius `r`
+///
+/// Pixels beyond the ends of the line repeat the end pixels.  Averages are
+///   truncated if `integer` is set
+fn stack_blur_line(line: &mut [f64], n: usize, r: usize, integer: bool) {
+    let len = line.len() / n;
+    if r == 0 || len < 2 {
+        return;
+    }
+    let src = line.to_vec();
+    let wm = len - 1;
+    let div = 2 * r + 1;
+    let sum_div = ((r + 1) * (r + 1)) as f64;
+    let mut stack = vec![0.0; div];
+    for c in 0 .. n {
+        let pix = |i: usize| src[i * n + c];
+        let (mut sum, mut sum_in, mut sum_out) = (0.0, 0.0, 0.0);
+        let p = pix(0);
+        for (i, s) in stack.iter_mut().take(r + 1).enumerate() {
+            *s = p;
+            sum += p * (i + 1) as f64;
+            sum_out += p;
+        }
+        for i in 1 ..= r {
+            let p = pix(min(i, wm));
+            stack[i + r] = p;
+            sum += p * (r + 1 - i) as f64;
+            sum_in += p;
+        }
+        let mut stack_ptr = r;
+        for x in 0 .. len {
+            let v = sum / sum_div;
+            line[x * n + c] = if integer { v.floor() } else { v };
+            sum -= sum_out;
+
+            let mut stack_start = stack_ptr + div - r;
+            if stack_start >= div {
+                stack_start -= div;
+            }
+            sum_out -= stack[stack_start];
+
+            let p = pix(min(x + r + 1, wm));
+            stack[stack_start] = p;
+            sum_in += p;
+            sum += sum_in;
+
+            stack_ptr += 1;
+            if stack_ptr >= div {
+                stack_ptr = 0;
+            }
+            let p = stack[stack_ptr];
+            sum_out += p;
+            sum_in -= p;
+        }
+    }
+}
+
+/// Recursive filter coefficients (b, b1, b2, b3) for a Gaussian of `radius`
+///
+/// See Young and van Vliet, "Recursive implementation of the Gaussian filter"
+fn recursive_coefficients(radius: f64) -> (f64, f64, f64, f64) {
+    let s = radius * 0.5;
+    let q = if s < 2.5 {
+        3.97156 - 4.14554 * (1.0 - 0.26891 * s).sqrt()
+    } else {
+        0.98711 * s - 0.96330
+    };
+    let q2 = q * q;
+    let q3 = q2 * q;
+    let b0 = 1.0 / (1.578250 + 2.44413 * q + 1.42810 * q2 + 0.422205 * q3);
+    let b1 = ( 2.44413 * q + 2.85619 * q2 + 1.26661 * q3) * b0;
+    let b2 = (-1.42810 * q2 - 1.26661 * q3) * b0;
+    let b3 = 0.422205 * q3 * b0;
+    let b = 1.0 - (b1 + b2 + b3);
+    (b, b1, b2, b3)
+}
+
+/// Recursive Blur a single line of `n` components per pixel with `radius`
+fn recursive_blur_line(line: &mut [f64], n: usize, radius: f64) {
+    let len = line.len() / n;
+    if radius < 0.62 || len < 3 {
+        return;
+    }
+    let (b, b1, b2, b3) = recursive_coefficients(radius);
+    let calc = |c: f64, c1: f64, c2: f64, c3: f64| b * c + b1 * c1 + b2 * c2 + b3 * c3;
+    let wm = len - 1;
+    let mut sum1 = vec![0.0; len];
+    let mut sum2 = vec![0.0; len];
+    for c in 0 .. n {
+        let pix = |i: usize| line[i * n + c];
+        // Forward
+        sum1[0] = calc(pix(0), pix(0), pix(0), pix(0));
+        sum1[1] = calc(pix(1), sum1[0], sum1[0], sum1[0]);
+        sum1[2] = calc(pix(2), sum1[1], sum1[0], sum1[0]);
+        for i in 3 .. len {
+            sum1[i] = calc(pix(i), sum1[i-1], sum1[i-2], sum1[i-3]);
+        }
+        // Backward
+        sum2[wm]   = calc(sum1[wm],   sum1[wm],   sum1[wm],   sum1[wm]);
+        sum2[wm-1] = calc(sum1[wm-1], sum2[wm],   sum2[wm],   sum2[wm]);
+        sum2[wm-2] = calc(sum1[wm-2], sum2[wm-1], sum2[wm],   sum2[wm]);
+        for i in (0 .. wm - 2).rev() {
+            sum2[i] = calc(sum1[i], sum2[i+1], sum2[i+2], sum2[i+3]);
+        }
+        for (i, &v) in sum2.iter().enumerate() {
+            line[i * n + c] = v;
+        }
+    }
+}
+
+/// Apply `f` to each row and then each column of the region
+///   (`x1`,`y1`) - (`x2`,`y2`), inclusive and clipped to the buffer
+///
+/// `f` receives the decoded components of a line of pixels, the number of
+///   components per pixel and whether the line is a row
+fn blur_region<C, B, F>(rbuf: &mut RenderingBuffer<B>, (x1,y1): (usize,usize), (x2,y2): (usize,usize), f: F)
+    where C: BlurComponents,
+          B: AsRef<[u8]> + AsMut<[u8]>,
+          F: Fn(&mut [f64], usize, bool)
+{
+    let (w, h, n) = (rbuf.width(), rbuf.height(), rbuf.bpp());
+    if w == 0 || h == 0 || x1 >= w || y1 >= h || x2 < x1 || y2 < y1 {
+        return;
+    }
+    let (x2, y2) = (min(x2, w - 1), min(y2, h - 1));
+    let nc = C::N;
+    let mut row = vec![0.0; (x2 - x1 + 1) * nc];
+    for y in y1 ..= y2 {
+        let pixels = &mut rbuf.row_mut(y)[x1 * n .. (x2 + 1) * n];
+        for (p, v) in pixels.chunks_exact(n).zip(row.chunks_exact_mut(nc)) {
+            C::decode(p, v);
+        }
+        f(&mut row, nc, true);
+        for (p, v) in pixels.chunks_exact_mut(n).zip(row.chunks_exact(nc)) {
+            C::encode(v, p);
+        }
+    }
+    let mut col = vec![0.0; (y2 - y1 + 1) * nc];
+    for x in x1 ..= x2 {
+        for (v, y) in col.chunks_exact_mut(nc).zip(y1 ..= y2) {
+            C::decode(&rbuf.row(y)[x * n .. (x + 1) * n], v);
+        }
+        f(&mut col, nc, false);
+        for (v, y) in col.chunks_exact(nc).zip(y1 ..= y2) {
+            C::encode(v, &mut rbuf.row_mut(y)[x * n .. (x + 1) * n]);
+        }
+    }
+}
+
+macro_rules! impl_blur {
+    ($($color:ty),*) => { $(
+        impl<B> Pixfmt<$color,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+            /// Stack Blur the image with radii `rx` and `ry`
+            ///
+            /// A fast approximation of a Gaussian blur, see [`stack_blur_region`]
+            ///
+            /// [`stack_blur_region`]: #method.stack_blur_region
+            pub fn stack_blur(&mut self, rx: usize, ry: usize) {
+                let (w, h) = (self.width(), self.height());
+                if w == 0 || h == 0 {
+                    return;
+                }
+                self.stack_blur_region(rx, ry, 0, 0, w - 1, h - 1);
+            }
+            /// Stack Blur the region (`x1`,`y1`) - (`x2`,`y2`), inclusive,
+            ///   with radii `rx` and `ry`
+            ///
+            /// The region is clipped to the image, pixels outside the region
+            ///   are unchanged and do not contribute
+            ///
+            ///     use agg::{Pixfmt,Pixel,PixelData,Gray8};
+            ///
+            ///     let mut pix = Pixfmt::<Gray8>::new(5,1);
+            ///     pix.fill(Gray8::new(0));
+            ///     pix.copy_pixel(2, 0, Gray8::new(90));
+            ///     pix.stack_blur_region(1, 0, 0, 0, 4, 0);
+            ///     let v : Vec<_> = pix.row_pixels(0).map(|c| c.value).collect();
+            ///     assert_eq!(v, vec![0, 22, 45, 22, 0]);
+            ///
+            pub fn stack_blur_region(&mut self, rx: usize, ry: usize,
+                                     x1: usize, y1: usize, x2: usize, y2: usize) {
+                blur_region::<$color, _, _>(self.rendering_buffer_mut(), (x1,y1), (x2,y2), |line, n, horizontal| {
+                    let r = if horizontal { rx } else { ry };
+                    stack_blur_line(line, n, r, <$color as BlurComponents>::INTEGER);
+                });
+            }
+            /// Recursive Blur the image with radii `rx` and `ry`
+            ///
+            /// A Gaussian blur computed with a recursive filter, the cost is
+            ///   independent of the radius, see [`recursive_blur_region`]
+            ///
+            /// [`recursive_blur_region`]: #method.recursive_blur_region
+            pub fn recursive_blur(&mut self, rx: f64, ry: f64) {
+                let (w, h) = (self.width(), self.height());
+                if w == 0 || h == 0 {
+                    return;
+                }
+                self.recursive_blur_region(rx, ry, 0, 0, w - 1, h - 1);
+            }
+            /// Recursive Blur the region (`x1`,`y1`) - (`x2`,`y2`), inclusive,
+            ///   with radii `rx` and `ry`
+            ///
+            /// The region is clipped to the image, pixels outside the region
+            ///   are unchanged and do not contribute.  Radii less than 0.62
+            ///   leave the image unchanged
+            pub fn recursive_blur_region(&mut self, rx: f64, ry: f64,
+                                         x1: usize, y1: usize, x2: usize, y2: usize) {
+                blur_region::<$color, _, _>(self.rendering_buffer_mut(), (x1,y1), (x2,y2), |line, n, horizontal| {
+                    recursive_blur_line(line, n, if horizontal { rx } else { ry });
+                });
+            }
+        }
+    )* }
+}
+
+impl_blur!(Rgb8, Rgba8, Rgba8pre, Srgba8, Gray8, Bgr8, Bgra8, Argb8, Abgr8,
+           Rgb16, Rgba16, Gray16, Rgba32, Rgb565, Rgb555);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn blur_line_test() {
+        // Constant lines are unchanged
+        let mut line = vec![77.0; 30];
+        stack_blur_line(&mut line, 3, 4, true);
+        assert!(line.iter().all(|&v| v == 77.0));
+        recursive_blur_line(&mut line, 3, 4.0);
+        assert!(line.iter().all(|&v| (v - 77.0).abs() < 1e-9));
+
+        // Impulse spreads symmetrically as a triangle
+        let mut line = vec![0.0; 9];
+        line[4] = 160.0;
+        stack_blur_line(&mut line, 1, 3, true);
+        assert_eq!(line, vec![0., 10., 20., 30., 40., 30., 20., 10., 0.]);
+
+        // Averages are only truncated for integer components
+        let mut line = vec![0.0, 1.0, 0.0];
+        stack_blur_line(&mut line, 1, 1, false);
+        assert_eq!(line, vec![0.25, 0.5, 0.25]);
+
+        // Recursive blur is approximately symmetric and preserves the sum
+        let mut line = vec![0.0; 41];
+        line[20] = 255.0;
+        recursive_blur_line(&mut line, 1, 6.0);
+        assert!(line[20] < 255.0 && line[20] > 0.0);
+        for i in 1 .. 20 {
+            assert!((line[20-i] - line[20+i]).abs() <= 2.0);
+        }
+        let sum : f64 = line.iter().sum();
+        assert!((245.0 ..= 265.0).contains(&sum), "{}", sum);
+    }
+}
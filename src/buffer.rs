@@ -2,62 +2,167 @@
 
 /// Rendering Buffer
 ///
-/// Data is stored as row-major order (C-format)
+/// Data is stored as rows of pixels.  By default the buffer owns a tightly
+///   packed `Vec<u8>` in row-major order (C-format), but any storage that
+///   can be viewed as bytes, e.g. a borrowed `&mut [u8]`, can be attached
+///   with an explicit row stride
+///
+/// A negative stride places row 0 at the end of the data, rows are then
+///   stored bottom-up, as in many window surfaces and BMP images
+///
+///     use agg::RenderingBuffer;
+///
+///     let mut data = vec![0u8; 2 * 8];
+///     {
+///         // 2x2 pixels of 3 bytes with 2 bytes of padding per row, bottom-up
+///         let mut rbuf = RenderingBuffer::attach(&mut data[..], 2, 2, 3, -8);
+///         rbuf.row_mut(0).copy_from_slice(&[1,2,3,4,5,6]);
+///         assert_eq!(rbuf.row(0), &[1,2,3,4,5,6]);
+///     }
+///     assert_eq!(&data[8..14], &[1,2,3,4,5,6]);
+///
 #[derive(Debug,Default)]
-pub(crate) struct RenderingBuffer {
+pub struct RenderingBuffer<B = Vec<u8>> {
     /// Pixel / Component level data of Image
-    pub data: Vec<u8>,
+    pub(crate) data: B,
     /// Image Width in pixels
-    pub width: usize,
+    pub(crate) width: usize,
     /// Image Height in pixels
-    pub height: usize,
-    /// Bytes per pixel or number of color components 
-    pub bpp: usize,
+    pub(crate) height: usize,
+    /// Bytes per pixel or number of color components
+    pub(crate) bpp: usize,
+    /// Bytes from the start of one row to the next, negative for bottom-up
+    stride: isize,
 }
 
 
 impl RenderingBuffer {
     /// Create a new buffer of width, height, and bpp
     ///
-    /// Data for the Image is allocated 
+    /// Data for the Image is allocated
     pub fn new(width: usize, height: usize, bpp: usize) -> Self {
         RenderingBuffer {
-            width, height, bpp, data: vec![0u8; width * height * bpp]
+            width, height, bpp, data: vec![0u8; width * height * bpp],
+            stride: (width * bpp) as isize,
         }
     }
-    /// Size of underlying Rendering Buffer
+    pub fn from_buf(data: Vec<u8>, width: usize, height: usize, bpp: usize) -> Self {
+        assert_eq!(data.len(), width * height * bpp);
+        RenderingBuffer { width, height, bpp, data, stride: (width * bpp) as isize }
+    }
+}
+
+impl<B> RenderingBuffer<B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    /// Attach to existing `data` of `width` x `height` pixels of `bpp` bytes
+    ///
+    /// `stride` is the number of bytes from the start of one row to the
+    ///   start of the next and must be at least `width * bpp` in magnitude.
+    ///   If negative, row 0 is the last row in `data`
+    ///
+    /// # Panics
+    ///
+    /// If the stride is too small or `data` is too short
+    pub fn attach(data: B, width: usize, height: usize, bpp: usize, stride: isize) -> Self {
+        let row = width * bpp;
+        assert!(stride.unsigned_abs() >= row,
+                "stride {} is less than the row size {}", stride, row);
+        let needed = if height == 0 { 0 } else { (height - 1) * stride.unsigned_abs() + row };
+        assert!(data.as_ref().len() >= needed,
+                "buffer of {} bytes is too small, {} bytes required", data.as_ref().len(), needed);
+        RenderingBuffer { data, width, height, bpp, stride }
+    }
+    /// Image Width in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Image Height in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// Bytes per pixel
+    pub fn bpp(&self) -> usize {
+        self.bpp
+    }
+    /// Bytes from the start of one row to the next, negative for bottom-up
+    pub fn stride(&self) -> isize {
+        self.stride
+    }
+    /// Size of underlying data in bytes
     pub fn len(&self) -> usize {
-        self.data.len()
+        self.data.as_ref().len()
+    }
+    /// Check if the underlying data is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Underlying data, including any padding between rows
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+    /// Offset of the start of row `y` in the data
+    fn row_start(&self, y: usize) -> usize {
+        if self.stride < 0 {
+            (self.height - 1 - y) * self.stride.unsigned_abs()
+        } else {
+            y * self.stride as usize
+        }
+    }
+    /// Pixels of row `y`, `width * bpp` bytes
+    ///
+    /// # Panics
+    ///
+    /// If `y` is outside of the buffer
+    pub fn row(&self, y: usize) -> &[u8] {
+        assert!(y < self.height, "row {} >= {} height", y, self.height);
+        let i = self.row_start(y);
+        &self.data.as_ref()[i .. i + self.width * self.bpp]
+    }
+    /// Mutable pixels of row `y`, `width * bpp` bytes
+    ///
+    /// # Panics
+    ///
+    /// If `y` is outside of the buffer
+    pub fn row_mut(&mut self, y: usize) -> &mut [u8] {
+        assert!(y < self.height, "row {} >= {} height", y, self.height);
+        let i = self.row_start(y);
+        let n = self.width * self.bpp;
+        &mut self.data.as_mut()[i .. i + n]
+    }
+    /// Pixels, without padding, top row first
+    pub fn to_packed(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.width * self.height * self.bpp);
+        for y in 0 .. self.height {
+            out.extend_from_slice(self.row(y));
+        }
+        out
     }
     /// Clear an image
     pub fn clear(&mut self) {
-        self.data.iter_mut().for_each(|v| *v = 255);
-    }
-    pub fn from_buf(data: Vec<u8>, width: usize, height: usize, bpp: usize) -> Self {
-        assert_eq!(data.len(), width * height * bpp);
-        RenderingBuffer { width, height, bpp, data }
+        for y in 0 .. self.height {
+            self.row_mut(y).iter_mut().for_each(|v| *v = 255);
+        }
     }
 }
 
 use std::ops::Index;
 use std::ops::IndexMut;
 
-impl Index<(usize,usize)> for RenderingBuffer {
+impl<B> Index<(usize,usize)> for RenderingBuffer<B> where B: AsRef<[u8]> + AsMut<[u8]> {
     type Output = [u8];
     fn index(&self, index: (usize, usize)) -> &[u8] {
         debug_assert!(index.0 < self.width, "request {} >= {} width :: index", index.0, self.width);
         debug_assert!(index.1 < self.height, "request {} >= {} height :: index", index.1, self.height);
-        let i = ((index.1 * self.width) + index.0) * self.bpp;
-        debug_assert!(i < self.data.len());
-        &self.data[i..]
+        let i = self.row_start(index.1) + index.0 * self.bpp;
+        debug_assert!(i < self.len());
+        &self.data.as_ref()[i..]
     }
 }
-impl IndexMut<(usize,usize)> for RenderingBuffer {
+impl<B> IndexMut<(usize,usize)> for RenderingBuffer<B> where B: AsRef<[u8]> + AsMut<[u8]> {
     fn index_mut(&mut self, index: (usize, usize)) -> &mut [u8] {
         debug_assert!(index.0 < self.width, "request {} >= {} width :: index_mut", index.0, self.width);
         debug_assert!(index.1 < self.height, "request {} >= {} height :: index_mut", index.1, self.height);
-        let i = ((index.1 * self.width) + index.0) * self.bpp;
-        debug_assert!(i < self.data.len());
-        &mut self.data[i..]
+        let i = self.row_start(index.1) + index.0 * self.bpp;
+        debug_assert!(i < self.len());
+        &mut self.data.as_mut()[i..]
     }
 }
@@ -21,6 +21,10 @@ pub(crate) struct Cell { // cell_aa
     pub cover: i64,
     /// Cell area 
     pub area: i64,
+    /// Style on the left of the edge, negative if none
+    pub left: i64,
+    /// Style on the right of the edge, negative if none
+    pub right: i64,
 }
 
 impl Cell {
@@ -28,7 +32,8 @@ impl Cell {
     ///
     /// Cover and Area are both 0
     fn new() -> Self {
-        Cell { x: std::i64::MAX, y: std::i64::MAX, cover: 0, area: 0 }
+        Cell { x: std::i64::MAX, y: std::i64::MAX, cover: 0, area: 0,
+               left: -1, right: -1 }
     }
     /// Create new cell at position (x,y)
     pub fn at(x: i64, y: i64) -> Self {
@@ -63,6 +68,10 @@ pub(crate) struct RasterizerCell {
     pub max_y: i64,
     /// Cells sorted by y position, then x position
     pub sorted_y: Vec<Vec<Cell>>,
+    /// Left style of new cells
+    left: i64,
+    /// Right style of new cells
+    right: i64,
 }
 
 
@@ -75,6 +84,8 @@ impl RasterizerCell {
                max_x: std::i64::MIN,
                max_y: std::i64::MIN,
                sorted_y: vec![],
+               left: -1,
+               right: -1,
         }
     }
     /// Clear cells
@@ -85,8 +96,25 @@ impl RasterizerCell {
         self.min_y = std::i64::MAX;
         self.sorted_y.clear(); // Not sure if this should be cleared
         self.cells.clear();    // Not sure if this should be cleared
+        self.left = -1;
+        self.right = -1;
     }
 
+    /// Set the left and right styles of new cells
+    ///
+    /// Negative values are no style
+    pub fn style(&mut self, left: i64, right: i64) {
+        self.left = left;
+        self.right = right;
+    }
+    /// Return the left and right styles of new cells
+    pub fn current_style(&self) -> (i64, i64) {
+        (self.left, self.right)
+    }
+    /// Determine if cells have been sorted
+    pub fn sorted(&self) -> bool {
+        ! self.sorted_y.is_empty()
+    }
     /// Return total number of cells
     pub fn total_cells(&self) -> usize {
         self.cells.len()
@@ -133,11 +161,11 @@ impl RasterizerCell {
     //         }
     //     }
     // }
-    /// Determine if the current cell is located at (x,y)
+    /// Determine if the current cell is located at (x,y) with the current styles
     fn curr_cell_not_equal(&self, x: i64, y: i64) -> bool {
         match self.cells.last() {
             None      => true,
-            Some(cur) => ! cur.equal(x,y),
+            Some(cur) => ! cur.equal(x,y) || cur.left != self.left || cur.right != self.right,
         }
     }
     /// Remove last cell is cover and area are equal to 0
@@ -165,7 +193,10 @@ impl RasterizerCell {
     fn set_curr_cell(&mut self, x: i64, y: i64)  {
         if self.curr_cell_not_equal(x, y) {
             self.pop_last_cell_if_empty();
-            self.cells.push( Cell::at(x,y) );
+            let mut cell = Cell::at(x,y);
+            cell.left = self.left;
+            cell.right = self.right;
+            self.cells.push( cell );
         }
     }
 
@@ -21,7 +21,7 @@ impl<T> Rectangle<T> where T: std::cmp::PartialOrd + Copy {
     /// Values are sorted before storing
     pub fn new(x1: T, y1: T, x2: T, y2: T) -> Self {
         let (x1, x2) = if x1 > x2 { (x2,x1) } else { (x1,x2) };
-        let (y1, y2) = if y1 > x2 { (y2,y1) } else { (y1,y2) };
+        let (y1, y2) = if y1 > y2 { (y2,y1) } else { (y1,y2) };
         Self { x1,y1,x2,y2 }
     }
     /// Get location of point relative to rectangle
@@ -259,3 +259,18 @@ impl Clip {
         self.clip_box = Some( Rectangle::new(x1, y1, x2, y2) );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Rectangle;
+
+    #[test]
+    fn rectangle_new_test() {
+        let corners = |r: Rectangle<i64>| (r.x1(), r.y1(), r.x2(), r.y2());
+        assert_eq!(corners(Rectangle::new(0, 2, 10, 5)), (0, 2, 10, 5));
+        assert_eq!(corners(Rectangle::new(10, 5, 0, 2)), (0, 2, 10, 5));
+        // y values are sorted by themselves, not against x
+        assert_eq!(corners(Rectangle::new(0, 5, 10, 2)), (0, 2, 10, 5));
+        assert_eq!(corners(Rectangle::new(0, 20, 10, 30)), (0, 20, 10, 30));
+    }
+}
@@ -8,6 +8,11 @@ fn cu8(v: f64) -> u8 {
     (v * 255.0).round() as u8
 }
 
+/// Convert an f64 [0,1] component to a u16 [0,65535] component
+fn cu16(v: f64) -> u16 {
+    (v * 65535.0).round() as u16
+}
+
 /// Convert from sRGB to RGB for a single component
 fn srgb_to_rgb(x: f64) -> f64 {
     if x <= 0.04045 {
@@ -25,6 +30,126 @@ fn rgb_to_srgb(x: f64) -> f64 {
     }
 }
 
+/// Lookup tables between 8-bit sRGB and 16-bit linear components
+///
+/// See agg_gamma_lut.h:sRGB_lut<int16u> of agg version 2.6
+///
+#[derive(Debug)]
+pub struct SrgbLut {
+    /// sRGB to linear
+    dir: [u16; 256],
+    /// Linear value at the midpoint below each sRGB value
+    inv: [u16; 256],
+}
+
+/// Lookup tables computed by [`SrgbLut::new`], stored as constants so
+///   they need no initialization
+///
+/// [`SrgbLut::new`]: struct.SrgbLut.html#method.new
+static SRGB_LUT: SrgbLut = SrgbLut {
+    dir: [
+        0, 20, 40, 60, 80, 99, 119, 139, 159, 179, 199, 219,
+        241, 264, 288, 313, 340, 367, 396, 427, 458, 491, 526, 562,
+        599, 637, 677, 718, 761, 805, 851, 898, 947, 997, 1048, 1101,
+        1156, 1212, 1270, 1330, 1391, 1453, 1517, 1583, 1651, 1720, 1790, 1863,
+        1937, 2013, 2090, 2170, 2250, 2333, 2418, 2504, 2592, 2681, 2773, 2866,
+        2961, 3058, 3157, 3258, 3360, 3464, 3570, 3678, 3788, 3900, 4014, 4129,
+        4247, 4366, 4488, 4611, 4736, 4864, 4993, 5124, 5257, 5392, 5530, 5669,
+        5810, 5953, 6099, 6246, 6395, 6547, 6700, 6856, 7014, 7174, 7335, 7500,
+        7666, 7834, 8004, 8177, 8352, 8528, 8708, 8889, 9072, 9258, 9445, 9635,
+        9828, 10022, 10219, 10417, 10619, 10822, 11028, 11235, 11446, 11658, 11873, 12090,
+        12309, 12530, 12754, 12980, 13209, 13440, 13673, 13909, 14146, 14387, 14629, 14874,
+        15122, 15371, 15623, 15878, 16135, 16394, 16656, 16920, 17187, 17456, 17727, 18001,
+        18277, 18556, 18837, 19121, 19407, 19696, 19987, 20281, 20577, 20876, 21177, 21481,
+        21787, 22096, 22407, 22721, 23038, 23357, 23678, 24002, 24329, 24658, 24990, 25325,
+        25662, 26001, 26344, 26688, 27036, 27386, 27739, 28094, 28452, 28813, 29176, 29542,
+        29911, 30282, 30656, 31033, 31412, 31794, 32179, 32567, 32957, 33350, 33745, 34143,
+        34544, 34948, 35355, 35764, 36176, 36591, 37008, 37429, 37852, 38278, 38706, 39138,
+        39572, 40009, 40449, 40891, 41337, 41785, 42236, 42690, 43147, 43606, 44069, 44534,
+        45002, 45473, 45947, 46423, 46903, 47385, 47871, 48359, 48850, 49344, 49841, 50341,
+        50844, 51349, 51858, 52369, 52884, 53401, 53921, 54445, 54971, 55500, 56032, 56567,
+        57105, 57646, 58190, 58737, 59287, 59840, 60396, 60955, 61517, 62082, 62650, 63221,
+        63795, 64372, 64952, 65535,
+    ],
+    inv: [
+        0, 10, 30, 50, 70, 90, 109, 129, 149, 169, 189, 209,
+        230, 252, 276, 300, 326, 353, 382, 411, 442, 475, 508, 543,
+        580, 618, 657, 697, 739, 783, 828, 874, 922, 971, 1022, 1075,
+        1129, 1184, 1241, 1300, 1360, 1422, 1485, 1550, 1617, 1685, 1755, 1826,
+        1900, 1975, 2051, 2130, 2210, 2292, 2375, 2460, 2547, 2636, 2727, 2819,
+        2914, 3010, 3107, 3207, 3309, 3412, 3517, 3624, 3733, 3844, 3957, 4071,
+        4188, 4306, 4427, 4549, 4673, 4800, 4928, 5058, 5190, 5325, 5461, 5599,
+        5739, 5881, 6026, 6172, 6320, 6471, 6623, 6778, 6935, 7093, 7254, 7417,
+        7582, 7750, 7919, 8090, 8264, 8440, 8618, 8798, 8980, 9165, 9351, 9540,
+        9731, 9925, 10120, 10318, 10518, 10720, 10924, 11131, 11340, 11551, 11765, 11981,
+        12199, 12419, 12642, 12867, 13094, 13324, 13556, 13790, 14027, 14266, 14508, 14751,
+        14998, 15246, 15497, 15750, 16006, 16264, 16525, 16788, 17053, 17321, 17591, 17864,
+        18139, 18416, 18696, 18979, 19264, 19551, 19841, 20134, 20429, 20726, 21026, 21329,
+        21634, 21941, 22251, 22564, 22879, 23197, 23517, 23840, 24165, 24493, 24824, 25157,
+        25493, 25831, 26172, 26516, 26862, 27211, 27562, 27916, 28273, 28632, 28994, 29359,
+        29726, 30096, 30469, 30844, 31222, 31603, 31986, 32372, 32761, 33153, 33547, 33944,
+        34344, 34746, 35151, 35559, 35970, 36383, 36799, 37218, 37640, 38064, 38492, 38922,
+        39354, 39790, 40228, 40670, 41114, 41560, 42010, 42463, 42918, 43376, 43837, 44301,
+        44768, 45237, 45709, 46185, 46663, 47144, 47628, 48114, 48604, 49097, 49592, 50091,
+        50592, 51096, 51603, 52113, 52626, 53142, 53661, 54183, 54707, 55235, 55766, 56299,
+        56836, 57375, 57918, 58463, 59012, 59563, 60118, 60675, 61235, 61799, 62365, 62935,
+        63507, 64083, 64661, 65243,
+    ],
+};
+
+impl SrgbLut {
+    /// Create the lookup tables
+    pub fn new() -> Self {
+        let mut dir = [0u16; 256];
+        let mut inv = [0u16; 256];
+        for i in 1 ..= 255 {
+            dir[i] = (65535.0 * srgb_to_rgb(i as f64 / 255.0)).round() as u16;
+            inv[i] = (65535.0 * srgb_to_rgb((i as f64 - 0.5) / 255.0)).round() as u16;
+        }
+        Self { dir, inv }
+    }
+    /// Shared lookup tables
+    pub fn get() -> &'static Self {
+        &SRGB_LUT
+    }
+    /// Convert an sRGB component to linear
+    ///
+    ///     let lut = agg::SrgbLut::get();
+    ///     assert_eq!(lut.dir(0), 0);
+    ///     assert_eq!(lut.dir(128), 14146);
+    ///     assert_eq!(lut.dir(255), 65535);
+    ///
+    pub fn dir(&self, v: u8) -> u16 {
+        self.dir[v as usize]
+    }
+    /// Convert a linear component to sRGB, the inverse of [`dir`]
+    ///
+    ///     let lut = agg::SrgbLut::get();
+    ///     for i in 0 ..= 255 {
+    ///         assert_eq!(lut.inv(lut.dir(i)), i);
+    ///     }
+    ///
+    /// [`dir`]: #method.dir
+    pub fn inv(&self, v: u16) -> u8 {
+        // Binary search for the largest midpoint below v
+        let mut x = 0usize;
+        let mut step = 128;
+        while step > 0 {
+            if v > self.inv[x + step] {
+                x += step;
+            }
+            step /= 2;
+        }
+        x as u8
+    }
+}
+
+impl Default for SrgbLut {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 
 /// Color as Red, Green, Blue, and Alpha
 #[derive(Debug,Default,Copy,Clone,PartialEq)]
@@ -58,6 +183,16 @@ impl Rgba8 {
     pub fn into_slice(&self) -> [u8;4] {
         [self.r, self.g, self.b, self.a]
     }
+    /// Linear interpolation to color `c`, including alpha
+    ///
+    /// `k` is in [0,1], where 0 is self and 1 is `c`
+    pub fn gradient(&self, c: Rgba8, k: f64) -> Self {
+        let lerp = |p: u8, q: u8| {
+            (f64::from(p) + (f64::from(q) - f64::from(p)) * k).round() as u8
+        };
+        Self::new(lerp(self.r, c.r), lerp(self.g, c.g),
+                  lerp(self.b, c.b), lerp(self.a, c.a))
+    }
     /// Crate new color from a wavelength and gamma 
     pub fn from_wavelength_gamma(w: f64, gamma: f64) -> Self {
         let c = Rgb8::from_wavelength_gamma(w, gamma);
@@ -224,6 +359,22 @@ impl Rgba8pre {
     pub fn into_slice(&self) -> [u8;4] {
         [self.r, self.g, self.b, self.a]
     }
+    /// Color with components divided by alpha
+    pub fn demultiply(self) -> Rgba8 {
+        match self.a {
+            255 => {
+                Rgba8::new(self.r, self.g, self.b, self.a)
+            },
+            0   => {
+                Rgba8::new(0, 0, 0, self.a)
+            },
+            _   => {
+                let a = u32::from(self.a);
+                let div = |v: u8| std::cmp::min(255, (u32::from(v) * 255 + a / 2) / a) as u8;
+                Rgba8::new(div(self.r), div(self.g), div(self.b), self.a)
+            }
+        }
+    }
 }
 
 /// Color as standard Red, Green, Blue, Alpha
@@ -253,6 +404,257 @@ impl Srgba8 {
     pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
         Self { r, g, b, a }
     }
+    /// Encode a linear color using the sRGB lookup tables
+    pub fn from_rgba16(c: Rgba16) -> Self {
+        let lut = SrgbLut::get();
+        Self::new(lut.inv(c.r), lut.inv(c.g), lut.inv(c.b), (c.a >> 8) as u8)
+    }
+    pub fn from_slice(v: &[u8]) -> Self {
+        Self::new(v[0], v[1], v[2], v[3])
+    }
+    pub fn into_slice(&self) -> [u8;4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+/// Color as linear Red, Green, Blue, Alpha with 16-bit components
+///
+/// Working format for blending in linear light
+///
+///     use agg::{Rgba16,Srgba8};
+///
+///     let c = Srgba8::new(255, 128, 0, 255);
+///     let lin = Rgba16::from_srgba8(c);
+///     assert_eq!(lin, Rgba16::new(65535, 14146, 0, 65535));
+///     assert_eq!(Srgba8::from_rgba16(lin), c);
+///
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Rgba16 {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+    pub a: u16,
+}
+
+impl Rgba16 {
+    pub fn new(r: u16, g: u16, b: u16, a: u16) -> Self {
+        Self { r, g, b, a }
+    }
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        Self::new(cu16(c.red()), cu16(c.green()), cu16(c.blue()), cu16(c.alpha()))
+    }
+    /// Decode an sRGB color using the sRGB lookup tables
+    pub fn from_srgba8(c: Srgba8) -> Self {
+        let lut = SrgbLut::get();
+        Self::new(lut.dir(c.r), lut.dir(c.g), lut.dir(c.b), u16::from(c.a) * 257)
+    }
+    pub fn white() -> Self {
+        Self::new(65535,65535,65535,65535)
+    }
+    pub fn black() -> Self {
+        Self::new(0,0,0,65535)
+    }
+    pub fn into_slice(&self) -> [u16;4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+/// Color as Red, Green, Blue with 16-bit components
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Rgb16 {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+}
+
+impl Rgb16 {
+    pub fn new(r: u16, g: u16, b: u16) -> Self {
+        Self { r, g, b }
+    }
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        Self::new(cu16(c.red()), cu16(c.green()), cu16(c.blue()))
+    }
+    pub fn white() -> Self {
+        Self::new(65535,65535,65535)
+    }
+    pub fn black() -> Self {
+        Self::new(0,0,0)
+    }
+    pub fn into_slice(&self) -> [u16;3] {
+        [self.r, self.g, self.b]
+    }
+}
+
+/// Color as Gray scale value and alpha with 16-bit components
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Gray16 {
+    pub value: u16,
+    pub alpha: u16,
+}
+
+impl Gray16 {
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        let lum = luminance(c.red(), c.green(), c.blue());
+        Self::new_with_alpha( cu16(lum), cu16(c.alpha()) )
+    }
+    /// Create a new gray scale value
+    pub fn new(value: u16) -> Self {
+        Self { value, alpha: 65535 }
+    }
+    pub fn new_with_alpha(value: u16, alpha: u16) -> Self {
+        Self { value, alpha }
+    }
+    pub fn into_slice(&self) -> [u16;2] {
+        [self.value, self.alpha]
+    }
+}
+
+/// Color as Red, Green, Blue, stored in Blue, Green, Red order
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Bgr8 {
+    /// Blue
+    pub b: u8,
+    /// Green
+    pub g: u8,
+    /// Red
+    pub r: u8,
+}
+
+impl Bgr8 {
+    /// Create a new color, components given as r, g, b
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        Self::new(c.red8(), c.green8(), c.blue8())
+    }
+}
+
+/// Color as Red, Green, Blue, and Alpha, stored in Blue, Green, Red, Alpha order
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Bgra8 {
+    /// Blue
+    pub b: u8,
+    /// Green
+    pub g: u8,
+    /// Red
+    pub r: u8,
+    /// Alpha
+    pub a: u8,
+}
+
+impl Bgra8 {
+    /// Create a new color, components given as r, g, b, a
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        Self::new(c.red8(), c.green8(), c.blue8(), c.alpha8())
+    }
+}
+
+/// Color as Red, Green, Blue, and Alpha, stored in Alpha, Red, Green, Blue order
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Argb8 {
+    /// Alpha
+    pub a: u8,
+    /// Red
+    pub r: u8,
+    /// Green
+    pub g: u8,
+    /// Blue
+    pub b: u8,
+}
+
+impl Argb8 {
+    /// Create a new color, components given as r, g, b, a
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        Self::new(c.red8(), c.green8(), c.blue8(), c.alpha8())
+    }
+}
+
+/// Color as Red, Green, Blue, and Alpha, stored in Alpha, Blue, Green, Red order
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Abgr8 {
+    /// Alpha
+    pub a: u8,
+    /// Blue
+    pub b: u8,
+    /// Green
+    pub g: u8,
+    /// Red
+    pub r: u8,
+}
+
+impl Abgr8 {
+    /// Create a new color, components given as r, g, b, a
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        Self::new(c.red8(), c.green8(), c.blue8(), c.alpha8())
+    }
+}
+
+/// Color as Red, Green, Blue packed into 16 bits, 5-6-5 bits from high to low
+///
+///     use agg::{Color,Rgb565};
+///
+///     let c = Rgb565::new(255, 128, 0);
+///     assert_eq!(c.value, 0b11111_100000_00000);
+///     assert_eq!((c.red8(), c.green8(), c.blue8()), (255, 130, 0));
+///
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Rgb565 {
+    pub value: u16,
+}
+
+/// Color as Red, Green, Blue packed into 16 bits, 1-5-5-5 bits from high
+///   to low, the high bit is always set
+///
+///     use agg::{Color,Rgb555};
+///
+///     let c = Rgb555::new(255, 128, 0);
+///     assert_eq!(c.value, 0b1_11111_10000_00000);
+///     assert_eq!((c.red8(), c.green8(), c.blue8()), (255, 132, 0));
+///
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Rgb555 {
+    pub value: u16,
+}
+
+/// Reduce an 8-bit component to `bits`, rounding to nearest
+fn pack_bits(v: u8, bits: u32) -> u16 {
+    let max = (1u32 << bits) - 1;
+    ((u32::from(v) * max + 127) / 255) as u16
+}
+/// Expand a component of `bits` to 8 bits by replicating the high bits
+fn unpack_bits(v: u16, bits: u32) -> u8 {
+    let v = u32::from(v);
+    ((v << (8 - bits)) | (v >> (2 * bits - 8))) as u8
+}
+
+impl Rgb565 {
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        let value = (pack_bits(r, 5) << 11) | (pack_bits(g, 6) << 5) | pack_bits(b, 5);
+        Self { value }
+    }
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        Self::new(c.red8(), c.green8(), c.blue8())
+    }
+}
+
+impl Rgb555 {
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        let value = 0x8000 | (pack_bits(r, 5) << 10) | (pack_bits(g, 5) << 5) | pack_bits(b, 5);
+        Self { value }
+    }
+    pub fn from_trait<C: Color>(c: C) -> Self {
+        Self::new(c.red8(), c.green8(), c.blue8())
+    }
 }
 
 #[derive(Debug,Default,Copy,Clone,PartialEq)]
@@ -345,6 +747,105 @@ impl Color for Rgba32 {
     fn blue8(&self)  -> u8  { cu8(self.blue()) }
     fn is_premultiplied(&self) -> bool { false }
 }
+impl Color for Rgba16 {
+    fn   red(&self)  -> f64 { f64::from(self.r) / 65535.0 }
+    fn green(&self)  -> f64 { f64::from(self.g) / 65535.0 }
+    fn  blue(&self)  -> f64 { f64::from(self.b) / 65535.0 }
+    fn alpha(&self)  -> f64 { f64::from(self.a) / 65535.0 }
+    fn alpha8(&self) -> u8  { cu8(self.alpha()) }
+    fn red8(&self)   -> u8  { cu8(self.red()) }
+    fn green8(&self) -> u8  { cu8(self.green()) }
+    fn blue8(&self)  -> u8  { cu8(self.blue()) }
+    fn is_premultiplied(&self) -> bool { false }
+}
+impl Color for Bgr8 {
+    fn   red(&self)  -> f64 { color_u8_to_f64(self.r) }
+    fn green(&self)  -> f64 { color_u8_to_f64(self.g) }
+    fn  blue(&self)  -> f64 { color_u8_to_f64(self.b) }
+    fn alpha(&self)  -> f64 { 1.0 }
+    fn alpha8(&self) -> u8  { 255 }
+    fn red8(&self)   -> u8  { self.r }
+    fn green8(&self) -> u8  { self.g }
+    fn blue8(&self)  -> u8  { self.b }
+    fn is_premultiplied(&self) -> bool { false }
+}
+impl Color for Bgra8 {
+    fn   red(&self)  -> f64 { color_u8_to_f64(self.r) }
+    fn green(&self)  -> f64 { color_u8_to_f64(self.g) }
+    fn  blue(&self)  -> f64 { color_u8_to_f64(self.b) }
+    fn alpha(&self)  -> f64 { color_u8_to_f64(self.a) }
+    fn alpha8(&self) -> u8  { self.a }
+    fn red8(&self)   -> u8  { self.r }
+    fn green8(&self) -> u8  { self.g }
+    fn blue8(&self)  -> u8  { self.b }
+    fn is_premultiplied(&self) -> bool { false }
+}
+impl Color for Argb8 {
+    fn   red(&self)  -> f64 { color_u8_to_f64(self.r) }
+    fn green(&self)  -> f64 { color_u8_to_f64(self.g) }
+    fn  blue(&self)  -> f64 { color_u8_to_f64(self.b) }
+    fn alpha(&self)  -> f64 { color_u8_to_f64(self.a) }
+    fn alpha8(&self) -> u8  { self.a }
+    fn red8(&self)   -> u8  { self.r }
+    fn green8(&self) -> u8  { self.g }
+    fn blue8(&self)  -> u8  { self.b }
+    fn is_premultiplied(&self) -> bool { false }
+}
+impl Color for Abgr8 {
+    fn   red(&self)  -> f64 { color_u8_to_f64(self.r) }
+    fn green(&self)  -> f64 { color_u8_to_f64(self.g) }
+    fn  blue(&self)  -> f64 { color_u8_to_f64(self.b) }
+    fn alpha(&self)  -> f64 { color_u8_to_f64(self.a) }
+    fn alpha8(&self) -> u8  { self.a }
+    fn red8(&self)   -> u8  { self.r }
+    fn green8(&self) -> u8  { self.g }
+    fn blue8(&self)  -> u8  { self.b }
+    fn is_premultiplied(&self) -> bool { false }
+}
+impl Color for Rgb565 {
+    fn   red(&self)  -> f64 { color_u8_to_f64(self.red8()) }
+    fn green(&self)  -> f64 { color_u8_to_f64(self.green8()) }
+    fn  blue(&self)  -> f64 { color_u8_to_f64(self.blue8()) }
+    fn alpha(&self)  -> f64 { 1.0 }
+    fn alpha8(&self) -> u8  { 255 }
+    fn red8(&self)   -> u8  { unpack_bits((self.value >> 11) & 0x1F, 5) }
+    fn green8(&self) -> u8  { unpack_bits((self.value >> 5) & 0x3F, 6) }
+    fn blue8(&self)  -> u8  { unpack_bits(self.value & 0x1F, 5) }
+    fn is_premultiplied(&self) -> bool { false }
+}
+impl Color for Rgb555 {
+    fn   red(&self)  -> f64 { color_u8_to_f64(self.red8()) }
+    fn green(&self)  -> f64 { color_u8_to_f64(self.green8()) }
+    fn  blue(&self)  -> f64 { color_u8_to_f64(self.blue8()) }
+    fn alpha(&self)  -> f64 { 1.0 }
+    fn alpha8(&self) -> u8  { 255 }
+    fn red8(&self)   -> u8  { unpack_bits((self.value >> 10) & 0x1F, 5) }
+    fn green8(&self) -> u8  { unpack_bits((self.value >> 5) & 0x1F, 5) }
+    fn blue8(&self)  -> u8  { unpack_bits(self.value & 0x1F, 5) }
+    fn is_premultiplied(&self) -> bool { false }
+}
+impl Color for Rgb16 {
+    fn   red(&self)  -> f64 { f64::from(self.r) / 65535.0 }
+    fn green(&self)  -> f64 { f64::from(self.g) / 65535.0 }
+    fn  blue(&self)  -> f64 { f64::from(self.b) / 65535.0 }
+    fn alpha(&self)  -> f64 { 1.0 }
+    fn alpha8(&self) -> u8  { 255 }
+    fn red8(&self)   -> u8  { cu8(self.red()) }
+    fn green8(&self) -> u8  { cu8(self.green()) }
+    fn blue8(&self)  -> u8  { cu8(self.blue()) }
+    fn is_premultiplied(&self) -> bool { false }
+}
+impl Color for Gray16 {
+    fn   red(&self)  -> f64 { f64::from(self.value) / 65535.0 }
+    fn green(&self)  -> f64 { f64::from(self.value) / 65535.0 }
+    fn  blue(&self)  -> f64 { f64::from(self.value) / 65535.0 }
+    fn alpha(&self)  -> f64 { f64::from(self.alpha) / 65535.0 }
+    fn alpha8(&self) -> u8  { cu8(self.alpha()) }
+    fn red8(&self)   -> u8  { cu8(self.red()) }
+    fn green8(&self) -> u8  { cu8(self.green()) }
+    fn blue8(&self)  -> u8  { cu8(self.blue()) }
+    fn is_premultiplied(&self) -> bool { false }
+}
 impl Color for Gray8 {
     fn   red(&self)  -> f64 { color_u8_to_f64(self.value) }
     fn green(&self)  -> f64 { color_u8_to_f64(self.value) }
@@ -364,6 +865,9 @@ mod tests {
     use super::Rgba8;
     use super::Rgba8pre;
     use super::Srgba8;
+    use super::Rgb16;
+    use super::Rgba16;
+    use super::Gray16;
 
     #[test]
     fn rgb8_to_gray8_test() {
@@ -412,6 +916,16 @@ mod tests {
         assert_eq!(g, Gray8{ value: 10, alpha: 20 });
     }
     #[test]
+    fn color16_test() {
+        let c = Rgba8::new(255, 128, 1, 0);
+        assert_eq!(Rgb16::from_trait(c), Rgb16::new(65535, 32896, 257));
+        assert_eq!(Rgba16::from_trait(c), Rgba16::new(65535, 32896, 257, 0));
+        assert_eq!(Gray16::from_trait(Rgb8::white()), Gray16::new(65535));
+        assert_eq!(Gray16::from_trait(Rgba8::new(0,0,0,128)), Gray16::new_with_alpha(0, 32896));
+        let c = Rgba16::new(65535, 32896, 257, 65535);
+        assert_eq!(Rgba8::from_trait(c), Rgba8::new(255, 128, 1, 255));
+    }
+    #[test]
     fn rgba8_test() {
         let c = Rgba8::white();
         assert_eq!(c, Rgba8{r:255,g:255,b:255,a:255});
@@ -424,7 +938,12 @@ mod tests {
         assert_eq!(c, Rgba8{r:0,g:0,b:0,a:0});
         let c = Rgba8::new(255,255,255,128);
         let p = c.premultiply();
-        assert_eq!(p, Rgba8pre { r: 128, g: 128, b: 128, a: 128 } )
+        assert_eq!(p, Rgba8pre { r: 128, g: 128, b: 128, a: 128 } );
+        assert_eq!(p.demultiply(), c);
+        let p = Rgba8pre::new(128, 64, 0, 128);
+        assert_eq!(p.demultiply(), Rgba8::new(255, 128, 0, 128));
+        let p = Rgba8pre::new(200, 0, 0, 100);
+        assert_eq!(p.demultiply(), Rgba8::new(255, 0, 0, 100));
     }
     #[test]
     fn srgb_test() {
@@ -433,4 +952,10 @@ mod tests {
         let t = Rgba8::from_trait(s);
         assert_eq!(t, Rgba8{r:8,g:78,b:244,a:128});
     }
+    #[test]
+    fn srgb_lut_test() {
+        let lut = super::SrgbLut::new();
+        assert_eq!(lut.dir, super::SrgbLut::get().dir);
+        assert_eq!(lut.inv, super::SrgbLut::get().inv);
+    }
 }
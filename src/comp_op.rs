@@ -0,0 +1,378 @@
+//! Compositing Operations
+//!
+//! Porter-Duff compositing and SVG blend modes selectable at runtime
+//!
+//!   - [`CompOp`] - Compositing operation
+//!   - [`PixfmtCustomBlendRgba`] - Premultiplied RGBA pixel format blending
+//!     with a [`CompOp`]
+//!
+//! Operations follow "Compositing Digital Images" by Porter and Duff
+//!   (doc/p253-porter.pdf) and the blend modes of the W3C
+//!   [Compositing and Blending](https://www.w3.org/TR/compositing-1/)
+//!   specification, where `S` is the source color, `D` the destination and
+//!   all colors are premultiplied
+//!
+//! [`CompOp`]: enum.CompOp.html
+//! [`PixfmtCustomBlendRgba`]: struct.PixfmtCustomBlendRgba.html
+
+use crate::color::*;
+use crate::pixfmt::Pixfmt;
+
+use crate::Color;
+use crate::Source;
+use crate::Pixel;
+use crate::PixelData;
+
+/// Compositing Operation
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub enum CompOp {
+    /// Nothing remains: 0
+    Clear,
+    /// Source only: S
+    Src,
+    /// Destination only: D
+    Dst,
+    /// Source over destination: S + D(1-Sa)
+    #[default]
+    SrcOver,
+    /// Destination over source: S(1-Da) + D
+    DstOver,
+    /// Source inside destination: S Da
+    SrcIn,
+    /// Destination inside source: D Sa
+    DstIn,
+    /// Source outside destination: S(1-Da)
+    SrcOut,
+    /// Destination outside source: D(1-Sa)
+    DstOut,
+    /// Source atop destination: S Da + D(1-Sa)
+    SrcAtop,
+    /// Destination atop source: S(1-Da) + D Sa
+    DstAtop,
+    /// Source and destination where they do not overlap: S(1-Da) + D(1-Sa)
+    Xor,
+    /// Sum of source and destination: min(S + D, 1)
+    Plus,
+    /// Product of source and destination, always darker
+    Multiply,
+    /// Complement of the product of the complements, always lighter
+    Screen,
+    /// Multiply or Screen depending on the destination
+    Overlay,
+    /// Darker of source and destination
+    Darken,
+    /// Lighter of source and destination
+    Lighten,
+    /// Destination brightened to reflect the source
+    ColorDodge,
+    /// Destination darkened to reflect the source
+    ColorBurn,
+    /// Multiply or Screen depending on the source
+    HardLight,
+    /// Darken or lighten depending on the source, a softer HardLight
+    SoftLight,
+    /// Absolute difference of source and destination
+    Difference,
+    /// Similar to Difference with lower contrast
+    Exclusion,
+}
+
+impl CompOp {
+    /// Composite source `s` onto destination `d` with `cover`
+    ///
+    /// Colors are premultiplied.  A `cover` less than 255 interpolates
+    ///   between the destination and the composited color
+    ///
+    ///     use agg::{CompOp,Rgba8pre};
+    ///
+    ///     let s = Rgba8pre::new(255,0,0,255);
+    ///     let d = Rgba8pre::new(0,0,255,255);
+    ///     assert_eq!(CompOp::SrcOver.blend(s, d, 255), s);
+    ///     assert_eq!(CompOp::DstOver.blend(s, d, 255), d);
+    ///     assert_eq!(CompOp::Clear.blend(s, d, 255), Rgba8pre::new(0,0,0,0));
+    ///     assert_eq!(CompOp::Screen.blend(s, d, 255), Rgba8pre::new(255,0,255,255));
+    ///     assert_eq!(CompOp::Multiply.blend(s, d, 255), Rgba8pre::new(0,0,0,255));
+    ///
+    pub fn blend(self, s: Rgba8pre, d: Rgba8pre, cover: u8) -> Rgba8pre {
+        let to_f64 = |c: Rgba8pre| [f64::from(c.r) / 255.0, f64::from(c.g) / 255.0,
+                                    f64::from(c.b) / 255.0, f64::from(c.a) / 255.0];
+        let (s, d) = (to_f64(s), to_f64(d));
+        let r = self.composite(s, d);
+        let cover = f64::from(cover) / 255.0;
+        let v = |i: usize| {
+            let x = d[i] + (r[i] - d[i]) * cover;
+            (x * 255.0).round().clamp(0.0, 255.0) as u8
+        };
+        Rgba8pre::new(v(0), v(1), v(2), v(3))
+    }
+    /// Composite `s` onto `d`, components in [0,1]
+    fn composite(self, s: [f64;4], d: [f64;4]) -> [f64;4] {
+        let (sa, da) = (s[3], d[3]);
+        // Porter-Duff: S Fa + D Fb
+        let pd = |fa: f64, fb: f64| {
+            [s[0] * fa + d[0] * fb, s[1] * fa + d[1] * fb,
+             s[2] * fa + d[2] * fb, s[3] * fa + d[3] * fb]
+        };
+        match self {
+            CompOp::Clear   => [0.0; 4],
+            CompOp::Src     => s,
+            CompOp::Dst     => d,
+            CompOp::SrcOver => pd(1.0, 1.0 - sa),
+            CompOp::DstOver => pd(1.0 - da, 1.0),
+            CompOp::SrcIn   => pd(da, 0.0),
+            CompOp::DstIn   => pd(0.0, sa),
+            CompOp::SrcOut  => pd(1.0 - da, 0.0),
+            CompOp::DstOut  => pd(0.0, 1.0 - sa),
+            CompOp::SrcAtop => pd(da, 1.0 - sa),
+            CompOp::DstAtop => pd(1.0 - da, sa),
+            CompOp::Xor     => pd(1.0 - da, 1.0 - sa),
+            CompOp::Plus    => {
+                [(s[0] + d[0]).min(1.0), (s[1] + d[1]).min(1.0),
+                 (s[2] + d[2]).min(1.0), (s[3] + d[3]).min(1.0)]
+            },
+            _ => {
+                // Separable blend mode B(Cb,Cs) on non-premultiplied colors
+                //   S(1-Da) + D(1-Sa) + Sa Da B(D/Da, S/Sa)
+                let unmul = |c: f64, a: f64| if a > 0.0 { (c / a).min(1.0) } else { 0.0 };
+                let mut r = [0.0; 4];
+                for i in 0 .. 3 {
+                    let b = self.mix(unmul(d[i], da), unmul(s[i], sa));
+                    r[i] = s[i] * (1.0 - da) + d[i] * (1.0 - sa) + sa * da * b;
+                }
+                r[3] = sa + da - sa * da;
+                r
+            }
+        }
+    }
+    /// Separable blend function B(`cb`,`cs`) of the backdrop (destination)
+    ///   and source, non-premultiplied
+    fn mix(self, cb: f64, cs: f64) -> f64 {
+        match self {
+            CompOp::Multiply   => cb * cs,
+            CompOp::Screen     => cb + cs - cb * cs,
+            CompOp::Overlay    => CompOp::HardLight.mix(cs, cb),
+            CompOp::Darken     => cb.min(cs),
+            CompOp::Lighten    => cb.max(cs),
+            CompOp::ColorDodge => {
+                if cb <= 0.0 {
+                    0.0
+                } else if cs >= 1.0 {
+                    1.0
+                } else {
+                    (cb / (1.0 - cs)).min(1.0)
+                }
+            },
+            CompOp::ColorBurn => {
+                if cb >= 1.0 {
+                    1.0
+                } else if cs <= 0.0 {
+                    0.0
+                } else {
+                    1.0 - ((1.0 - cb) / cs).min(1.0)
+                }
+            },
+            CompOp::HardLight => {
+                if cs <= 0.5 {
+                    CompOp::Multiply.mix(cb, 2.0 * cs)
+                } else {
+                    CompOp::Screen.mix(cb, 2.0 * cs - 1.0)
+                }
+            },
+            CompOp::SoftLight => {
+                if cs <= 0.5 {
+                    cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb)
+                } else {
+                    let d = if cb <= 0.25 {
+                        ((16.0 * cb - 12.0) * cb + 4.0) * cb
+                    } else {
+                        cb.sqrt()
+                    };
+                    cb + (2.0 * cs - 1.0) * (d - cb)
+                }
+            },
+            CompOp::Difference => (cb - cs).abs(),
+            CompOp::Exclusion  => cb + cs - 2.0 * cb * cs,
+            _ => unreachable!("{:?} is not a separable blend mode", self),
+        }
+    }
+}
+
+/// Premultiplied version of `c`
+fn premultiplied<C: Color>(c: C) -> Rgba8pre {
+    if c.is_premultiplied() {
+        Rgba8pre::from_trait(c)
+    } else {
+        Rgba8::from_trait(c).premultiply()
+    }
+}
+
+/// Premultiplied RGBA Pixel Format with a selectable compositing operation
+///
+/// Every pixel drawn, including opaque and transparent colors, is
+///   composited onto the image with the current [`CompOp`].  Colors
+///   that are not premultiplied are premultiplied before being stored
+///
+///     use agg::{Pixel,Source,CompOp,Rgba8};
+///
+///     let mut pix = agg::PixfmtCustomBlendRgba::new(1,1);
+///     pix.fill(Rgba8::new(100,200,50,255));
+///     pix.set_comp_op(CompOp::Multiply);
+///     pix.copy_or_blend_pix((0,0), Rgba8::new(255,128,0,255));
+///     assert_eq!(pix.get((0,0)), Rgba8::new(100,100,0,255));
+///
+///     // Colors are read back demultiplied
+///     pix.fill(Rgba8::new(255,0,0,128));
+///     assert_eq!(pix.get((0,0)), Rgba8::new(255,0,0,128));
+///
+/// [`CompOp`]: enum.CompOp.html
+#[derive(Debug)]
+pub struct PixfmtCustomBlendRgba {
+    pixf: Pixfmt<Rgba8pre>,
+    comp_op: CompOp,
+}
+
+impl PixfmtCustomBlendRgba {
+    /// Create a new Pixel Format of `width` * `height` using
+    ///   [`CompOp::SrcOver`]
+    ///
+    /// [`CompOp::SrcOver`]: enum.CompOp.html#variant.SrcOver
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_pixfmt(Pixfmt::new(width, height))
+    }
+    /// Create a new Pixel Format from existing premultiplied data
+    pub fn with_pixfmt(pixf: Pixfmt<Rgba8pre>) -> Self {
+        Self { pixf, comp_op: CompOp::default() }
+    }
+    /// Set the compositing operation
+    pub fn set_comp_op(&mut self, op: CompOp) {
+        self.comp_op = op;
+    }
+    /// Return the compositing operation
+    pub fn comp_op(&self) -> CompOp {
+        self.comp_op
+    }
+    /// Underlying premultiplied Pixel Format
+    pub fn pixfmt(&self) -> &Pixfmt<Rgba8pre> {
+        &self.pixf
+    }
+    /// Copy the color components, without alpha, to a new Rgb8 Pixel Format
+    pub fn drop_alpha(&self) -> Pixfmt<Rgb8> {
+        self.pixf.drop_alpha()
+    }
+}
+
+impl Source for PixfmtCustomBlendRgba {
+    /// Color at `id`, demultiplied from the stored premultiplied color
+    fn get(&self, id: (usize, usize)) -> Rgba8 {
+        self.pixf.raw(id).demultiply()
+    }
+}
+
+impl Pixel for PixfmtCustomBlendRgba {
+    fn cover_mask() -> u64 { Pixfmt::<Rgba8pre>::cover_mask() }
+    fn bpp() -> usize { Pixfmt::<Rgba8pre>::bpp() }
+    fn as_bytes(&self) -> &[u8] {
+        self.pixf.as_bytes()
+    }
+    fn to_file<P: AsRef<std::path::Path>>(&self, filename: P) -> Result<(),std::io::Error> {
+        self.pixf.to_file(filename)
+    }
+    fn width(&self) -> usize {
+        self.pixf.width()
+    }
+    fn height(&self) -> usize {
+        self.pixf.height()
+    }
+    fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
+        self.pixf.set(id, premultiplied(c));
+    }
+    fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
+        self.pixf.setn(id, n, premultiplied(c));
+    }
+    fn fill<C: Color>(&mut self, color: C) {
+        self.pixf.fill(premultiplied(color));
+    }
+    fn blend_pix<C: Color>(&mut self, id: (usize, usize), c: C, cover: u64) {
+        if cover == 0 {
+            return;
+        }
+        let p = self.pixf.get(id);
+        let d = Rgba8pre::new(p.r, p.g, p.b, p.a);
+        let p = self.comp_op.blend(premultiplied(c), d, cover.min(255) as u8);
+        self.pixf.set(id, p);
+    }
+    fn copy_or_blend_pix<C: Color>(&mut self, id: (usize,usize), color: C) {
+        self.blend_pix(id, color, Self::cover_mask());
+    }
+    fn copy_or_blend_pix_with_cover<C: Color>(&mut self, id: (usize,usize), color: C, cover: u64) {
+        self.blend_pix(id, color, cover);
+    }
+    fn blend_hline<C: Color>(&mut self, x: i64, y: i64, len: i64, color: C, cover: u64) {
+        for i in 0 .. len {
+            self.blend_pix(((x+i) as usize, y as usize), color, cover);
+        }
+    }
+    fn blend_vline<C: Color>(&mut self, x: i64, y: i64, len: i64, color: C, cover: u64) {
+        for i in 0 .. len {
+            self.blend_pix((x as usize, (y+i) as usize), color, cover);
+        }
+    }
+}
+
+impl PixelData for PixfmtCustomBlendRgba {
+    type Color = Rgba8pre;
+    const PREMULTIPLIED: bool = true;
+    fn pixel(&self, x: usize, y: usize) -> Rgba8pre {
+        self.pixf.pixel(x, y)
+    }
+    fn row(&self, y: usize) -> &[u8] {
+        self.pixf.row(y)
+    }
+    fn row_mut(&mut self, y: usize) -> &mut [u8] {
+        self.pixf.row_mut(y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn porter_duff() {
+        let s = Rgba8pre::new(200, 0, 0, 200);
+        let d = Rgba8pre::new(0, 0, 100, 100);
+        assert_eq!(CompOp::Src.blend(s, d, 255), s);
+        assert_eq!(CompOp::Dst.blend(s, d, 255), d);
+        assert_eq!(CompOp::SrcIn.blend(s, d, 255), Rgba8pre::new(78, 0, 0, 78));
+        assert_eq!(CompOp::DstIn.blend(s, d, 255), Rgba8pre::new(0, 0, 78, 78));
+        assert_eq!(CompOp::SrcOut.blend(s, d, 255), Rgba8pre::new(122, 0, 0, 122));
+        assert_eq!(CompOp::DstOut.blend(s, d, 255), Rgba8pre::new(0, 0, 22, 22));
+        assert_eq!(CompOp::SrcAtop.blend(s, d, 255), Rgba8pre::new(78, 0, 22, 100));
+        assert_eq!(CompOp::DstAtop.blend(s, d, 255), Rgba8pre::new(122, 0, 78, 200));
+        assert_eq!(CompOp::Xor.blend(s, d, 255), Rgba8pre::new(122, 0, 22, 143));
+        assert_eq!(CompOp::Plus.blend(s, d, 255), Rgba8pre::new(200, 0, 100, 255));
+        // Partial cover interpolates toward the destination
+        assert_eq!(CompOp::Clear.blend(s, d, 0), d);
+        assert_eq!(CompOp::Clear.blend(s, d, 128), Rgba8pre::new(0, 0, 50, 50));
+    }
+    #[test]
+    fn blend_modes() {
+        let d = Rgba8pre::new(64, 128, 192, 255);
+        let s = Rgba8pre::new(128, 128, 128, 255);
+        assert_eq!(CompOp::Darken.blend(s, d, 255), Rgba8pre::new(64, 128, 128, 255));
+        assert_eq!(CompOp::Lighten.blend(s, d, 255), Rgba8pre::new(128, 128, 192, 255));
+        assert_eq!(CompOp::Difference.blend(s, d, 255), Rgba8pre::new(64, 0, 64, 255));
+        assert_eq!(CompOp::Overlay.blend(s, d, 255), Rgba8pre::new(64, 128, 192, 255));
+        // Black and white are unchanged by soft light with gray
+        let s = Rgba8pre::new(128, 128, 128, 255);
+        let d = Rgba8pre::new(0, 255, 0, 255);
+        assert_eq!(CompOp::SoftLight.blend(s, d, 255), d);
+        // Transparent source leaves the destination
+        let s = Rgba8pre::new(0, 0, 0, 0);
+        let d = Rgba8pre::new(10, 20, 30, 40);
+        for op in [CompOp::Multiply, CompOp::Screen, CompOp::ColorDodge,
+                   CompOp::ColorBurn, CompOp::HardLight, CompOp::Exclusion] {
+            assert_eq!(op.blend(s, d, 255), d);
+        }
+    }
+}
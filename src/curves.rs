@@ -0,0 +1,938 @@
+//! Bezier Curves
+//!
+//! Quadratic ([`Curve3`]) and cubic ([`Curve4`]) Bezier curves approximated
+//!   by line segments, and a path converter ([`ConvCurve`]) that flattens
+//!   the curve commands of a [`VertexSource`]
+//!
+//! # Example
+//!
+//!     // Path with a quadratic and a cubic curve
+//!     let mut path = agg::Path::new();
+//!     path.move_to(10.0, 10.0);
+//!     path.curve3(50.0, 90.0, 90.0, 10.0);
+//!     path.curve4(120.0, 40.0, 140.0, 80.0, 190.0, 10.0);
+//!
+//!     // Flatten the curves
+//!     let curve = agg::ConvCurve::new(path);
+//!
+//!     // Draw
+//!     let mut ras = agg::RasterizerScanline::new();
+//!     ras.add_path(&curve);
+//!
+//! [`Curve3`]: struct.Curve3.html
+//! [`Curve4`]: struct.Curve4.html
+//! [`ConvCurve`]: struct.ConvCurve.html
+//! [`VertexSource`]: ../trait.VertexSource.html
+
+use crate::paths::PathCommand;
+use crate::paths::Vertex;
+use crate::VertexSource;
+
+use std::f64::consts::PI;
+
+const CURVE_COLLINEARITY_EPSILON    : f64 = 1e-30;
+const CURVE_ANGLE_TOLERANCE_EPSILON : f64 = 0.01;
+const CURVE_RECURSION_LIMIT         : usize = 32;
+
+/// Curve Approximation Method
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub enum CurveApproximationMethod {
+    /// Incremental, evenly spaced steps along the curve
+    Inc,
+    /// Adaptive subdivision, more points at sharper bends
+    #[default]
+    Div,
+}
+
+/// Squared distance between two points
+fn calc_sq_distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    let dx = x2 - x1;
+    let dy = y2 - y1;
+    dx * dx + dy * dy
+}
+
+/// Absolute difference between two angles, wrapped to [0, PI]
+fn angle_diff(a1: f64, a2: f64) -> f64 {
+    let da = (a1 - a2).abs();
+    if da >= PI {
+        2.0 * PI - da
+    } else {
+        da
+    }
+}
+
+/// Convert a set of points into a MoveTo followed by LineTo's
+fn to_vertices(pts: &[(f64,f64)]) -> Vec<Vertex<f64>> {
+    pts.iter().enumerate()
+        .map(|(i, &(x,y))| if i == 0 {
+            Vertex::move_to(x,y)
+        } else {
+            Vertex::line_to(x,y)
+        })
+        .collect()
+}
+
+/// Quadratic Bezier Curve
+///
+/// Defined by a start point, a single control point and an end point
+///
+#[derive(Debug,Clone)]
+pub struct Curve3 {
+    /// Approximation method
+    method: CurveApproximationMethod,
+    /// Approximation scale, 1.0
+    approx_scale: f64,
+    /// Angle tolerance in radians, 0.0 (disabled)
+    angle_tolerance: f64,
+    /// Approximated curve
+    vertices: Vec<Vertex<f64>>,
+}
+
+impl VertexSource for Curve3 {
+    fn xconvert(&self) -> Vec<Vertex<f64>> {
+        self.vertices.clone()
+    }
+}
+
+impl Default for Curve3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Curve3 {
+    /// Create a new, empty, Quadratic Curve
+    pub fn new() -> Self {
+        Self { method: CurveApproximationMethod::Div,
+               approx_scale: 1.0,
+               angle_tolerance: 0.0,
+               vertices: vec![],
+        }
+    }
+    /// Create a Quadratic Curve from (`x1`,`y1`) to (`x3`,`y3`) with a
+    ///   control point at (`x2`,`y2`)
+    pub fn with_points(x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64) -> Self {
+        let mut c = Self::new();
+        c.init(x1, y1, x2, y2, x3, y3);
+        c
+    }
+    /// Set the approximation method
+    ///
+    /// Parameters are applied on the next call to [`init`](#method.init)
+    pub fn approximation_method(&mut self, method: CurveApproximationMethod) {
+        self.method = method;
+    }
+    /// Set the approximation scale
+    ///
+    /// Larger values produce more points, use when the curve is scaled up
+    pub fn approximation_scale(&mut self, scale: f64) {
+        self.approx_scale = scale;
+    }
+    /// Set the angle tolerance in radians, only used by `Div`
+    ///
+    /// A value of 0.0 disables the angle check
+    pub fn angle_tolerance(&mut self, angle: f64) {
+        self.angle_tolerance = angle;
+    }
+    /// Remove all points
+    pub fn reset(&mut self) {
+        self.vertices.clear();
+    }
+    /// Approximate the curve
+    pub fn init(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64) {
+        let pts = match self.method {
+            CurveApproximationMethod::Inc => self.inc(x1, y1, x2, y2, x3, y3),
+            CurveApproximationMethod::Div => self.div(x1, y1, x2, y2, x3, y3),
+        };
+        self.vertices = to_vertices(&pts);
+    }
+    /// Incremental approximation using forward differences
+    fn inc(&self, x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64) -> Vec<(f64,f64)> {
+        let len = calc_sq_distance(x1, y1, x2, y2).sqrt() +
+            calc_sq_distance(x2, y2, x3, y3).sqrt();
+        let mut num_steps = (len * 0.25 * self.approx_scale).round() as usize;
+        if num_steps < 4 {
+            num_steps = 4;
+        }
+        let step  = 1.0 / num_steps as f64;
+        let step2 = step * step;
+
+        let tmpx = (x1 - x2 * 2.0 + x3) * step2;
+        let tmpy = (y1 - y2 * 2.0 + y3) * step2;
+
+        let (mut fx, mut fy) = (x1, y1);
+        let mut dfx = tmpx + (x2 - x1) * (2.0 * step);
+        let mut dfy = tmpy + (y2 - y1) * (2.0 * step);
+        let ddfx = tmpx * 2.0;
+        let ddfy = tmpy * 2.0;
+
+        let mut out = vec![(x1, y1)];
+        for _ in 1 .. num_steps {
+            fx  += dfx;
+            fy  += dfy;
+            dfx += ddfx;
+            dfy += ddfy;
+            out.push((fx, fy));
+        }
+        out.push((x3, y3));
+        out
+    }
+    /// Adaptive subdivision
+    fn div(&self, x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64) -> Vec<(f64,f64)> {
+        let dts = 0.5 / self.approx_scale;
+        let dts = dts * dts;
+        let mut out = vec![(x1, y1)];
+        self.recursive_bezier(&mut out, dts, x1, y1, x2, y2, x3, y3, 0);
+        out.push((x3, y3));
+        out
+    }
+    #[allow(clippy::too_many_arguments)]
+    fn recursive_bezier(&self, out: &mut Vec<(f64,f64)>, dts: f64,
+                        x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64,
+                        level: usize) {
+        if level > CURVE_RECURSION_LIMIT {
+            return;
+        }
+        // Mid-points of the line segments
+        let x12  = (x1 + x2) / 2.0;
+        let y12  = (y1 + y2) / 2.0;
+        let x23  = (x2 + x3) / 2.0;
+        let y23  = (y2 + y3) / 2.0;
+        let x123 = (x12 + x23) / 2.0;
+        let y123 = (y12 + y23) / 2.0;
+
+        let dx = x3 - x1;
+        let dy = y3 - y1;
+        let d = ((x2 - x3) * dy - (y2 - y3) * dx).abs();
+
+        if d > CURVE_COLLINEARITY_EPSILON {
+            // Regular case
+            if d * d <= dts * (dx*dx + dy*dy) {
+                // Curvature is within the distance tolerance
+                if self.angle_tolerance < CURVE_ANGLE_TOLERANCE_EPSILON {
+                    out.push((x123, y123));
+                    return;
+                }
+                // Angle & Cusp Condition
+                let da = angle_diff((y3 - y2).atan2(x3 - x2),
+                                    (y2 - y1).atan2(x2 - x1));
+                if da < self.angle_tolerance {
+                    // Curvature is within the angle tolerance
+                    out.push((x123, y123));
+                    return;
+                }
+            }
+        } else {
+            // Collinear case
+            let da = dx*dx + dy*dy;
+            let d = if da == 0.0 {
+                calc_sq_distance(x1, y1, x2, y2)
+            } else {
+                let d = ((x2 - x1)*dx + (y2 - y1)*dy) / da;
+                if d > 0.0 && d < 1.0 {
+                    // Simple collinear case, 1---2---3
+                    // We can leave just two endpoints
+                    return;
+                }
+                if d <= 0.0 {
+                    calc_sq_distance(x2, y2, x1, y1)
+                } else if d >= 1.0 {
+                    calc_sq_distance(x2, y2, x3, y3)
+                } else {
+                    calc_sq_distance(x2, y2, x1 + d*dx, y1 + d*dy)
+                }
+            };
+            if d < dts {
+                out.push((x2, y2));
+                return;
+            }
+        }
+        // Continue subdivision
+        self.recursive_bezier(out, dts, x1, y1, x12, y12, x123, y123, level + 1);
+        self.recursive_bezier(out, dts, x123, y123, x23, y23, x3, y3, level + 1);
+    }
+}
+
+/// Cubic Bezier Curve
+///
+/// Defined by a start point, two control points and an end point
+///
+#[derive(Debug,Clone)]
+pub struct Curve4 {
+    /// Approximation method
+    method: CurveApproximationMethod,
+    /// Approximation scale, 1.0
+    approx_scale: f64,
+    /// Angle tolerance in radians, 0.0 (disabled)
+    angle_tolerance: f64,
+    /// Cusp limit in radians, 0.0 (disabled)
+    cusp_limit: f64,
+    /// Approximated curve
+    vertices: Vec<Vertex<f64>>,
+}
+
+impl VertexSource for Curve4 {
+    fn xconvert(&self) -> Vec<Vertex<f64>> {
+        self.vertices.clone()
+    }
+}
+
+impl Default for Curve4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Curve4 {
+    /// Create a new, empty, Cubic Curve
+    pub fn new() -> Self {
+        Self { method: CurveApproximationMethod::Div,
+               approx_scale: 1.0,
+               angle_tolerance: 0.0,
+               cusp_limit: 0.0,
+               vertices: vec![],
+        }
+    }
+    /// Create a Cubic Curve from (`x1`,`y1`) to (`x4`,`y4`) with control
+    ///   points at (`x2`,`y2`) and (`x3`,`y3`)
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_points(x1: f64, y1: f64, x2: f64, y2: f64,
+                       x3: f64, y3: f64, x4: f64, y4: f64) -> Self {
+        let mut c = Self::new();
+        c.init(x1, y1, x2, y2, x3, y3, x4, y4);
+        c
+    }
+    /// Set the approximation method
+    ///
+    /// Parameters are applied on the next call to [`init`](#method.init)
+    pub fn approximation_method(&mut self, method: CurveApproximationMethod) {
+        self.method = method;
+    }
+    /// Set the approximation scale
+    ///
+    /// Larger values produce more points, use when the curve is scaled up
+    pub fn approximation_scale(&mut self, scale: f64) {
+        self.approx_scale = scale;
+    }
+    /// Set the angle tolerance in radians, only used by `Div`
+    ///
+    /// A value of 0.0 disables the angle check
+    pub fn angle_tolerance(&mut self, angle: f64) {
+        self.angle_tolerance = angle;
+    }
+    /// Set the cusp limit in radians, only used by `Div`
+    ///
+    /// A value of 0.0 disables the cusp check
+    pub fn cusp_limit(&mut self, limit: f64) {
+        self.cusp_limit = if limit == 0.0 { 0.0 } else { PI - limit };
+    }
+    /// Remove all points
+    pub fn reset(&mut self) {
+        self.vertices.clear();
+    }
+    /// Approximate the curve
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(&mut self, x1: f64, y1: f64, x2: f64, y2: f64,
+                x3: f64, y3: f64, x4: f64, y4: f64) {
+        let pts = match self.method {
+            CurveApproximationMethod::Inc => self.inc(x1, y1, x2, y2, x3, y3, x4, y4),
+            CurveApproximationMethod::Div => self.div(x1, y1, x2, y2, x3, y3, x4, y4),
+        };
+        self.vertices = to_vertices(&pts);
+    }
+    /// Incremental approximation using forward differences
+    #[allow(clippy::too_many_arguments)]
+    fn inc(&self, x1: f64, y1: f64, x2: f64, y2: f64,
+           x3: f64, y3: f64, x4: f64, y4: f64) -> Vec<(f64,f64)> {
+        let len = (calc_sq_distance(x1, y1, x2, y2).sqrt() +
+                   calc_sq_distance(x2, y2, x3, y3).sqrt() +
+                   calc_sq_distance(x3, y3, x4, y4).sqrt()) * 0.25 * self.approx_scale;
+        let mut num_steps = len.round() as usize;
+        if num_steps < 4 {
+            num_steps = 4;
+        }
+        let step  = 1.0 / num_steps as f64;
+        let step2 = step * step;
+        let step3 = step * step2;
+
+        let pre1 = 3.0 * step;
+        let pre2 = 3.0 * step2;
+        let pre4 = 6.0 * step2;
+        let pre5 = 6.0 * step3;
+
+        let tmp1x = x1 - x2 * 2.0 + x3;
+        let tmp1y = y1 - y2 * 2.0 + y3;
+        let tmp2x = (x2 - x3) * 3.0 - x1 + x4;
+        let tmp2y = (y2 - y3) * 3.0 - y1 + y4;
+
+        let (mut fx, mut fy) = (x1, y1);
+        let mut dfx  = (x2 - x1) * pre1 + tmp1x * pre2 + tmp2x * step3;
+        let mut dfy  = (y2 - y1) * pre1 + tmp1y * pre2 + tmp2y * step3;
+        let mut ddfx = tmp1x * pre4 + tmp2x * pre5;
+        let mut ddfy = tmp1y * pre4 + tmp2y * pre5;
+        let dddfx = tmp2x * pre5;
+        let dddfy = tmp2y * pre5;
+
+        let mut out = vec![(x1, y1)];
+        for _ in 1 .. num_steps {
+            fx   += dfx;
+            fy   += dfy;
+            dfx  += ddfx;
+            dfy  += ddfy;
+            ddfx += dddfx;
+            ddfy += dddfy;
+            out.push((fx, fy));
+        }
+        out.push((x4, y4));
+        out
+    }
+    /// Adaptive subdivision
+    #[allow(clippy::too_many_arguments)]
+    fn div(&self, x1: f64, y1: f64, x2: f64, y2: f64,
+           x3: f64, y3: f64, x4: f64, y4: f64) -> Vec<(f64,f64)> {
+        let dts = 0.5 / self.approx_scale;
+        let dts = dts * dts;
+        let mut out = vec![(x1, y1)];
+        self.recursive_bezier(&mut out, dts, x1, y1, x2, y2, x3, y3, x4, y4, 0);
+        out.push((x4, y4));
+        out
+    }
+    #[allow(clippy::too_many_arguments)]
+    fn recursive_bezier(&self, out: &mut Vec<(f64,f64)>, dts: f64,
+                        x1: f64, y1: f64, x2: f64, y2: f64,
+                        x3: f64, y3: f64, x4: f64, y4: f64,
+                        level: usize) {
+        if level > CURVE_RECURSION_LIMIT {
+            return;
+        }
+        // Mid-points of the line segments
+        let x12   = (x1 + x2) / 2.0;
+        let y12   = (y1 + y2) / 2.0;
+        let x23   = (x2 + x3) / 2.0;
+        let y23   = (y2 + y3) / 2.0;
+        let x34   = (x3 + x4) / 2.0;
+        let y34   = (y3 + y4) / 2.0;
+        let x123  = (x12 + x23) / 2.0;
+        let y123  = (y12 + y23) / 2.0;
+        let x234  = (x23 + x34) / 2.0;
+        let y234  = (y23 + y34) / 2.0;
+        let x1234 = (x123 + x234) / 2.0;
+        let y1234 = (y123 + y234) / 2.0;
+
+        // Try to approximate the full cubic curve by a single straight line
+        let dx = x4 - x1;
+        let dy = y4 - y1;
+
+        let d2 = ((x2 - x4) * dy - (y2 - y4) * dx).abs();
+        let d3 = ((x3 - x4) * dy - (y3 - y4) * dx).abs();
+
+        let sig2 = d2 > CURVE_COLLINEARITY_EPSILON;
+        let sig3 = d3 > CURVE_COLLINEARITY_EPSILON;
+        match (sig2, sig3) {
+            (false, false) => {
+                // All collinear OR p1 == p4
+                let k = dx*dx + dy*dy;
+                let (d2, d3) = if k == 0.0 {
+                    (calc_sq_distance(x1, y1, x2, y2),
+                     calc_sq_distance(x4, y4, x3, y3))
+                } else {
+                    let k = 1.0 / k;
+                    let d2 = k * ((x2 - x1)*dx + (y2 - y1)*dy);
+                    let d3 = k * ((x3 - x1)*dx + (y3 - y1)*dy);
+                    if d2 > 0.0 && d2 < 1.0 && d3 > 0.0 && d3 < 1.0 {
+                        // Simple collinear case, 1---2---3---4
+                        // We can leave just two endpoints
+                        return;
+                    }
+                    let proj = |d: f64, x: f64, y: f64| {
+                        if d <= 0.0 {
+                            calc_sq_distance(x, y, x1, y1)
+                        } else if d >= 1.0 {
+                            calc_sq_distance(x, y, x4, y4)
+                        } else {
+                            calc_sq_distance(x, y, x1 + d*dx, y1 + d*dy)
+                        }
+                    };
+                    (proj(d2, x2, y2), proj(d3, x3, y3))
+                };
+                if d2 > d3 {
+                    if d2 < dts {
+                        out.push((x2, y2));
+                        return;
+                    }
+                } else if d3 < dts {
+                    out.push((x3, y3));
+                    return;
+                }
+            },
+            (false, true) => {
+                // p1,p2,p4 are collinear, p3 is significant
+                if d3 * d3 <= dts * (dx*dx + dy*dy) {
+                    if self.angle_tolerance < CURVE_ANGLE_TOLERANCE_EPSILON {
+                        out.push((x23, y23));
+                        return;
+                    }
+                    // Angle Condition
+                    let da1 = angle_diff((y4 - y3).atan2(x4 - x3),
+                                         (y3 - y2).atan2(x3 - x2));
+                    if da1 < self.angle_tolerance {
+                        out.push((x2, y2));
+                        out.push((x3, y3));
+                        return;
+                    }
+                    if self.cusp_limit != 0.0 && da1 > self.cusp_limit {
+                        out.push((x3, y3));
+                        return;
+                    }
+                }
+            },
+            (true, false) => {
+                // p1,p3,p4 are collinear, p2 is significant
+                if d2 * d2 <= dts * (dx*dx + dy*dy) {
+                    if self.angle_tolerance < CURVE_ANGLE_TOLERANCE_EPSILON {
+                        out.push((x23, y23));
+                        return;
+                    }
+                    // Angle Condition
+                    let da1 = angle_diff((y3 - y2).atan2(x3 - x2),
+                                         (y2 - y1).atan2(x2 - x1));
+                    if da1 < self.angle_tolerance {
+                        out.push((x2, y2));
+                        out.push((x3, y3));
+                        return;
+                    }
+                    if self.cusp_limit != 0.0 && da1 > self.cusp_limit {
+                        out.push((x2, y2));
+                        return;
+                    }
+                }
+            },
+            (true, true) => {
+                // Regular case
+                if (d2 + d3) * (d2 + d3) <= dts * (dx*dx + dy*dy) {
+                    // Curvature is within the distance tolerance
+                    if self.angle_tolerance < CURVE_ANGLE_TOLERANCE_EPSILON {
+                        out.push((x23, y23));
+                        return;
+                    }
+                    // Angle & Cusp Condition
+                    let k   = (y3 - y2).atan2(x3 - x2);
+                    let da1 = angle_diff(k, (y2 - y1).atan2(x2 - x1));
+                    let da2 = angle_diff((y4 - y3).atan2(x4 - x3), k);
+                    if da1 + da2 < self.angle_tolerance {
+                        // Curvature is within the angle tolerance
+                        out.push((x23, y23));
+                        return;
+                    }
+                    if self.cusp_limit != 0.0 {
+                        if da1 > self.cusp_limit {
+                            out.push((x2, y2));
+                            return;
+                        }
+                        if da2 > self.cusp_limit {
+                            out.push((x3, y3));
+                            return;
+                        }
+                    }
+                }
+            },
+        }
+        // Continue subdivision
+        self.recursive_bezier(out, dts, x1, y1, x12, y12, x123, y123, x1234, y1234, level + 1);
+        self.recursive_bezier(out, dts, x1234, y1234, x234, y234, x34, y34, x4, y4, level + 1);
+    }
+}
+
+/// Curve Converter
+///
+/// Replaces `Curve3` and `Curve4` commands of a Vertex Source with
+///   line segments.  All other commands are passed through unchanged
+///
+/// `Curve3` commands are expected in pairs (control, end) and `Curve4`
+///   commands in triplets (control, control, end) following a vertex,
+///   as produced by [`Path::curve3`] and [`Path::curve4`]
+///
+/// [`Path::curve3`]: ../paths/struct.Path.html#method.curve3
+/// [`Path::curve4`]: ../paths/struct.Path.html#method.curve4
+#[derive(Debug)]
+pub struct ConvCurve<T: VertexSource> {
+    /// Source of Vertices
+    source: T,
+    /// Quadratic curve approximation parameters
+    curve3: Curve3,
+    /// Cubic curve approximation parameters
+    curve4: Curve4,
+}
+
+impl<T> VertexSource for ConvCurve<T> where T: VertexSource {
+    fn xconvert(&self) -> Vec<Vertex<f64>> {
+        self.convert()
+    }
+}
+
+impl<T> ConvCurve<T> where T: VertexSource {
+    /// Create a new Curve Converter from a Vertex Source
+    pub fn new(source: T) -> Self {
+        Self { source, curve3: Curve3::new(), curve4: Curve4::new() }
+    }
+    /// Set the approximation method for both curve types
+    ///
+    /// Available options are
+    ///   - `Inc` - Incremental
+    ///   - `Div` - Adaptive subdivision (default)
+    pub fn approximation_method(&mut self, method: CurveApproximationMethod) {
+        self.curve3.approximation_method(method);
+        self.curve4.approximation_method(method);
+    }
+    /// Set approximation scale
+    pub fn approximation_scale(&mut self, scale: f64) {
+        self.curve3.approximation_scale(scale);
+        self.curve4.approximation_scale(scale);
+    }
+    /// Set angle tolerance in radians
+    pub fn angle_tolerance(&mut self, angle: f64) {
+        self.curve3.angle_tolerance(angle);
+        self.curve4.angle_tolerance(angle);
+    }
+    /// Set cusp limit in radians, cubic curves only
+    pub fn cusp_limit(&mut self, limit: f64) {
+        self.curve4.cusp_limit(limit);
+    }
+    /// Flatten the curves of the Vertex Source
+    fn convert(&self) -> Vec<Vertex<f64>> {
+        let src = self.source.xconvert();
+        let mut out = Vec::with_capacity(src.len());
+        let (mut last_x, mut last_y) = (0.0, 0.0);
+        let mut i = 0;
+        while i < src.len() {
+            let v = src[i];
+            match v.cmd {
+                PathCommand::Curve3 if i + 1 < src.len() => {
+                    let end = src[i+1];
+                    let mut c = self.curve3.clone();
+                    c.init(last_x, last_y, v.x, v.y, end.x, end.y);
+                    // Skip the first point, it is the current position
+                    out.extend(c.xconvert().into_iter().skip(1));
+                    last_x = end.x;
+                    last_y = end.y;
+                    i += 2;
+                },
+                PathCommand::Curve4 if i + 2 < src.len() => {
+                    let ctrl = src[i+1];
+                    let end = src[i+2];
+                    let mut c = self.curve4.clone();
+                    c.init(last_x, last_y, v.x, v.y, ctrl.x, ctrl.y, end.x, end.y);
+                    // Skip the first point, it is the current position
+                    out.extend(c.xconvert().into_iter().skip(1));
+                    last_x = end.x;
+                    last_y = end.y;
+                    i += 3;
+                },
+                PathCommand::Curve3 | PathCommand::Curve4 => {
+                    // Incomplete curve, treat as a line
+                    out.push(Vertex::line_to(v.x, v.y));
+                    last_x = v.x;
+                    last_y = v.y;
+                    i += 1;
+                },
+                _ => {
+                    out.push(v);
+                    last_x = v.x;
+                    last_y = v.y;
+                    i += 1;
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Approximate an elliptical arc with up to four cubic Bezier curves
+///
+/// Arc is centered at (`cx`,`cy`) with radii `rx` and `ry`, starting at
+///   `start_angle` and sweeping through `sweep_angle`, both in radians
+///
+/// Returns the start point followed by the (control, control, end) points
+///   of each curve.  If the sweep is negligible, only the start and end
+///   points are returned
+pub fn bezier_arc(cx: f64, cy: f64, rx: f64, ry: f64,
+                  start_angle: f64, sweep_angle: f64) -> Vec<(f64,f64)> {
+    const BEZIER_ARC_ANGLE_EPSILON : f64 = 0.01;
+
+    let mut start_angle = start_angle % (2.0 * PI);
+    let sweep_angle = sweep_angle.clamp(-2.0 * PI, 2.0 * PI);
+
+    if sweep_angle.abs() < 1e-10 {
+        return vec![(cx + rx * start_angle.cos(), cy + ry * start_angle.sin()),
+                    (cx + rx * (start_angle + sweep_angle).cos(),
+                     cy + ry * (start_angle + sweep_angle).sin())];
+    }
+    let mut out = vec![];
+    let mut total_sweep = 0.0;
+    let mut done = false;
+    while ! done && out.len() < 13 {
+        let prev_sweep = total_sweep;
+        let mut local_sweep;
+        if sweep_angle < 0.0 {
+            local_sweep  = -PI * 0.5;
+            total_sweep -=  PI * 0.5;
+            if total_sweep <= sweep_angle + BEZIER_ARC_ANGLE_EPSILON {
+                local_sweep = sweep_angle - prev_sweep;
+                done = true;
+            }
+        } else {
+            local_sweep  = PI * 0.5;
+            total_sweep += PI * 0.5;
+            if total_sweep >= sweep_angle - BEZIER_ARC_ANGLE_EPSILON {
+                local_sweep = sweep_angle - prev_sweep;
+                done = true;
+            }
+        }
+        let curve = arc_to_bezier(cx, cy, rx, ry, start_angle, local_sweep);
+        let skip = if out.is_empty() { 0 } else { 1 };
+        out.extend(curve.iter().skip(skip));
+        start_angle += local_sweep;
+    }
+    out
+}
+
+/// Single cubic Bezier curve approximating an arc of at most 90 degrees
+fn arc_to_bezier(cx: f64, cy: f64, rx: f64, ry: f64,
+                 start_angle: f64, sweep_angle: f64) -> [(f64,f64);4] {
+    let x0 = (sweep_angle / 2.0).cos();
+    let y0 = (sweep_angle / 2.0).sin();
+    let tx = (1.0 - x0) * 4.0 / 3.0;
+    let ty = y0 - tx * x0 / y0;
+    let px = [x0, x0 + tx, x0 + tx, x0];
+    let py = [-y0, -ty, ty, y0];
+
+    let sn = (start_angle + sweep_angle / 2.0).sin();
+    let cs = (start_angle + sweep_angle / 2.0).cos();
+
+    let mut out = [(0.0, 0.0); 4];
+    for i in 0 .. 4 {
+        out[i] = (cx + rx * (px[i] * cs - py[i] * sn),
+                  cy + ry * (px[i] * sn + py[i] * cs));
+    }
+    out
+}
+
+/// Elliptical Arc defined by end points, as used by SVG
+///
+/// Computes the arc from (`x0`,`y0`) to (`x2`,`y2`) with radii `rx` and
+///   `ry`, an x-axis rotation of `angle` in radians and the `large_arc`
+///   and `sweep` flags.  The arc is approximated by cubic Bezier curves
+///
+/// See [SVG Elliptical Arc Implementation Notes](https://www.w3.org/TR/SVG/implnote.html#ArcImplementationNotes)
+#[derive(Debug,Clone)]
+pub struct BezierArcSvg {
+    /// Radii were large enough, or close to large enough, to reach the end point
+    radii_ok: bool,
+    /// Start point followed by triplets of (control, control, end) points
+    points: Vec<(f64,f64)>,
+}
+
+impl BezierArcSvg {
+    /// Create a new Elliptical Arc
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(x0: f64, y0: f64, rx: f64, ry: f64, angle: f64,
+               large_arc: bool, sweep: bool, x2: f64, y2: f64) -> Self {
+        let mut radii_ok = true;
+        let mut rx = rx.abs();
+        let mut ry = ry.abs();
+
+        // Calculate the middle point between the current and the final points
+        let dx2 = (x0 - x2) / 2.0;
+        let dy2 = (y0 - y2) / 2.0;
+
+        let cos_a = angle.cos();
+        let sin_a = angle.sin();
+
+        // Calculate (x1, y1)
+        let x1 =  cos_a * dx2 + sin_a * dy2;
+        let y1 = -sin_a * dx2 + cos_a * dy2;
+
+        // Ensure radii are large enough
+        let mut prx = rx * rx;
+        let mut pry = ry * ry;
+        let px1 = x1 * x1;
+        let py1 = y1 * y1;
+
+        // Check that radii are large enough
+        let radii_check = px1 / prx + py1 / pry;
+        if radii_check > 1.0 {
+            rx *= radii_check.sqrt();
+            ry *= radii_check.sqrt();
+            prx = rx * rx;
+            pry = ry * ry;
+            if radii_check > 10.0 {
+                radii_ok = false;
+            }
+        }
+
+        // Calculate (cx1, cy1)
+        let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+        let sq = (prx * pry - prx * py1 - pry * px1) / (prx * py1 + pry * px1);
+        let coef = sign * if sq < 0.0 { 0.0 } else { sq.sqrt() };
+        let cx1 = coef *  ((rx * y1) / ry);
+        let cy1 = coef * -((ry * x1) / rx);
+
+        // Calculate (cx, cy) from (cx1, cy1)
+        let sx2 = (x0 + x2) / 2.0;
+        let sy2 = (y0 + y2) / 2.0;
+        let cx = sx2 + (cos_a * cx1 - sin_a * cy1);
+        let cy = sy2 + (sin_a * cx1 + cos_a * cy1);
+
+        // Calculate the start_angle (angle1) and the sweep_angle (dangle)
+        let ux =  (x1 - cx1) / rx;
+        let uy =  (y1 - cy1) / ry;
+        let vx = (-x1 - cx1) / rx;
+        let vy = (-y1 - cy1) / ry;
+
+        // Calculate the angle start
+        let n = (ux * ux + uy * uy).sqrt();
+        let p = ux; // (1 * ux) + (0 * uy)
+        let sign = if uy < 0.0 { -1.0 } else { 1.0 };
+        let v = (p / n).clamp(-1.0, 1.0);
+        let start_angle = sign * v.acos();
+
+        // Calculate the sweep angle
+        let n = ((ux * ux + uy * uy) * (vx * vx + vy * vy)).sqrt();
+        let p = ux * vx + uy * vy;
+        let sign = if ux * vy - uy * vx < 0.0 { -1.0 } else { 1.0 };
+        let v = (p / n).clamp(-1.0, 1.0);
+        let mut sweep_angle = sign * v.acos();
+        if ! sweep && sweep_angle > 0.0 {
+            sweep_angle -= PI * 2.0;
+        } else if sweep && sweep_angle < 0.0 {
+            sweep_angle += PI * 2.0;
+        }
+
+        // Build and transform the resulting arc
+        let mut points = bezier_arc(0.0, 0.0, rx, ry, start_angle, sweep_angle);
+        for p in points.iter_mut() {
+            let (x, y) = *p;
+            *p = (cx + x * cos_a - y * sin_a,
+                  cy + x * sin_a + y * cos_a);
+        }
+        // Ensure the end points are exact
+        let n = points.len();
+        points[0] = (x0, y0);
+        points[n-1] = (x2, y2);
+
+        Self { radii_ok, points }
+    }
+    /// Return if the radii were large enough to reach the end point
+    pub fn radii_ok(&self) -> bool {
+        self.radii_ok
+    }
+}
+
+impl VertexSource for BezierArcSvg {
+    /// Start point as a MoveTo followed by Curve4 commands
+    ///
+    /// If the arc is degenerate, the end point is a LineTo
+    fn xconvert(&self) -> Vec<Vertex<f64>> {
+        let cmd = if self.points.len() == 2 {
+            Vertex::line_to
+        } else {
+            Vertex::curve4
+        };
+        self.points.iter().enumerate()
+            .map(|(i, &(x,y))| if i == 0 {
+                Vertex::move_to(x,y)
+            } else {
+                cmd(x,y)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Path;
+
+    #[test]
+    fn curve3_inc_test() {
+        let mut c = Curve3::new();
+        c.approximation_method(CurveApproximationMethod::Inc);
+        c.init(0.0, 0.0, 5.0, 5.0, 10.0, 0.0);
+        let v = c.xconvert();
+        // Short curve is clamped to 4 steps
+        assert_eq!(v.len(), 5);
+        assert_eq!(v[0].cmd, PathCommand::MoveTo);
+        assert_eq!((v[0].x, v[0].y), (0.0, 0.0));
+        assert!((v[2].x - 5.0).abs() < 1e-9);
+        assert!((v[2].y - 2.5).abs() < 1e-9);
+        assert_eq!((v[4].x, v[4].y), (10.0, 0.0));
+    }
+    #[test]
+    fn curve4_inc_test() {
+        let mut c = Curve4::new();
+        c.approximation_method(CurveApproximationMethod::Inc);
+        c.init(0.0, 0.0, 0.0, 80.0, 80.0, 80.0, 80.0, 0.0);
+        let v = c.xconvert();
+        assert_eq!(v.len(), 61);
+        assert!((v[30].x - 40.0).abs() < 1e-9);
+        assert!((v[30].y - 60.0).abs() < 1e-9);
+        assert_eq!((v[60].x, v[60].y), (80.0, 0.0));
+    }
+    #[test]
+    fn curve_div_tolerance_test() {
+        // Every point lies within the distance tolerance of the curve
+        let c = Curve4::with_points(0.0, 0.0, 0.0, 100.0, 100.0, 100.0, 100.0, 0.0);
+        let v = c.xconvert();
+        assert!(v.len() > 4);
+        for p in &v {
+            assert!(p.y >= 0.0 && p.y <= 75.0 + 0.5);
+        }
+        let mut c = Curve4::new();
+        c.approximation_scale(4.0);
+        c.init(0.0, 0.0, 0.0, 100.0, 100.0, 100.0, 100.0, 0.0);
+        assert!(c.xconvert().len() > v.len());
+
+        // Collinear control points collapse to the end points
+        let c = Curve3::with_points(0.0, 0.0, 5.0, 0.0, 10.0, 0.0);
+        assert_eq!(c.xconvert().len(), 2);
+    }
+    #[test]
+    fn conv_curve_test() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.curve3(5.0, 5.0, 10.0, 0.0);
+        path.curve3_smooth(20.0, 0.0);
+        path.line_to(20.0, 20.0);
+        let mut conv = ConvCurve::new(path);
+        conv.approximation_method(CurveApproximationMethod::Inc);
+        let v = conv.xconvert();
+        assert_eq!(v.len(), 1 + 4 + 4 + 1);
+        assert_eq!(v[0].cmd, PathCommand::MoveTo);
+        assert!(v[1..].iter().all(|v| v.cmd == PathCommand::LineTo));
+        // Smooth curve reflects the control point to (15,-5)
+        assert!((v[6].x - 15.0).abs() < 1e-9);
+        assert!((v[6].y + 2.5).abs() < 1e-9);
+        assert_eq!((v[9].x, v[9].y), (20.0, 20.0));
+    }
+    #[test]
+    fn bezier_arc_svg_test() {
+        // Half circle, radius 10, from (0,0) to (20,0)
+        let a = BezierArcSvg::new(0.0, 0.0, 10.0, 10.0, 0.0, false, true, 20.0, 0.0);
+        assert!(a.radii_ok());
+        let v = a.xconvert();
+        assert_eq!(v.len(), 1 + 3 * 2);
+        assert_eq!(v[0].cmd, PathCommand::MoveTo);
+        assert!(v[1..].iter().all(|v| v.cmd == PathCommand::Curve4));
+        assert_eq!((v[6].x, v[6].y), (20.0, 0.0));
+        // Sweep flag set travels in the positive angle direction, through
+        //   negative y at the arc mid-point
+        assert!((v[3].x - 10.0).abs() < 1e-9);
+        assert!((v[3].y + 10.0).abs() < 1e-9);
+
+        // Radii too small are scaled up
+        let a = BezierArcSvg::new(0.0, 0.0, 1.0, 1.0, 0.0, false, false, 20.0, 0.0);
+        assert!(! a.radii_ok());
+        let v = a.xconvert();
+        assert!((v[3].y - 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,415 @@
+//! Gouraud Shading
+//!
+//! Triangles with a color at each vertex, filled by interpolating the
+//!   colors across the triangle
+//!
+//!   - [`SpanGouraudRgba`] - Color, generates [`Rgba8`]
+//!   - [`SpanGouraudGray`] - Gray scale, generates [`Gray8`]
+//!
+//! Each is both the [`VertexSource`] of the triangle to rasterize and the
+//!   [`SpanGenerator`] used to color it.  Meshes of triangles sharing
+//!   edges may show thin seams from anti-aliasing; dilating each triangle
+//!   by a fraction of a pixel covers the seams
+//!
+//! # Example
+//!
+//!     let pix = agg::Pixfmt::<agg::Rgb8>::new(100,100);
+//!     let mut ren_base = agg::RenderingBase::new(pix);
+//!     ren_base.clear(agg::Rgba8::white());
+//!
+//!     let mut span = agg::SpanGouraudRgba::new(agg::Rgba8::new(255,0,0,255),
+//!                                              agg::Rgba8::new(0,255,0,255),
+//!                                              agg::Rgba8::new(0,0,255,255),
+//!                                              10.0, 10.0, 90.0, 30.0, 40.0, 90.0,
+//!                                              0.0);
+//!     let mut ras = agg::RasterizerScanline::new();
+//!     ras.add_path(&span);
+//!     agg::render_scanlines_aa(&mut ras, &mut ren_base, &mut span);
+//!
+//! [`SpanGouraudRgba`]: struct.SpanGouraudRgba.html
+//! [`SpanGouraudGray`]: struct.SpanGouraudGray.html
+//! [`Rgba8`]: ../color/struct.Rgba8.html
+//! [`Gray8`]: ../color/struct.Gray8.html
+//! [`VertexSource`]: ../trait.VertexSource.html
+//! [`SpanGenerator`]: ../trait.SpanGenerator.html
+
+use crate::paths::Vertex;
+use crate::paths::cross;
+use crate::Color;
+use crate::Rgba8;
+use crate::Gray8;
+use crate::VertexSource;
+use crate::SpanGenerator;
+
+const SUBPIXEL_SHIFT : i64 = 4;
+const SUBPIXEL_SCALE : i64 = 1 << SUBPIXEL_SHIFT;
+
+/// Fractional bits used to step colors along a span
+const DDA_SHIFT : i64 = 14;
+
+/// Triangle vertex with a color
+#[derive(Debug,Default,Copy,Clone)]
+struct Coord<C> {
+    x: f64,
+    y: f64,
+    color: C,
+}
+
+/// Triangle with a color at each vertex
+///
+/// Vertex source for the Gouraud span generators
+#[derive(Debug,Clone)]
+pub struct SpanGouraud<C> {
+    /// Vertices and colors used for interpolation
+    coord: [Coord<C>; 3],
+    /// Vertices of the, possibly dilated, triangle
+    vertices: Vec<Vertex<f64>>,
+}
+
+impl<C> VertexSource for SpanGouraud<C> {
+    fn xconvert(&self) -> Vec<Vertex<f64>> {
+        self.vertices.clone()
+    }
+}
+
+impl<C: Color + Default> SpanGouraud<C> {
+    /// Create a new Triangle from colors, vertices and dilation `d`
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(c1: C, c2: C, c3: C,
+               x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64,
+               d: f64) -> Self {
+        let mut s = Self { coord: [Coord::default(); 3], vertices: vec![] };
+        s.colors(c1, c2, c3);
+        s.triangle(x1, y1, x2, y2, x3, y3, d);
+        s
+    }
+    /// Set the colors at each vertex
+    pub fn colors(&mut self, c1: C, c2: C, c3: C) {
+        self.coord[0].color = c1;
+        self.coord[1].color = c2;
+        self.coord[2].color = c3;
+    }
+    /// Set the triangle and dilate it by `d`
+    ///
+    /// A dilated triangle is drawn as a six-sided polygon, with bevel joins
+    ///   at each vertex, for numerical stability.  Colors are interpolated
+    ///   from the vertices of the miter joins
+    #[allow(clippy::too_many_arguments)]
+    pub fn triangle(&mut self,
+                    x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64,
+                    d: f64) {
+        let p = [(x1,y1), (x2,y2), (x3,y3)];
+        for (c, (x,y)) in self.coord.iter_mut().zip(p.iter()) {
+            c.x = *x;
+            c.y = *y;
+        }
+        let p = if d != 0.0 {
+            let p = dilate_triangle(x1, y1, x2, y2, x3, y3, d);
+            for (i, c) in self.coord.iter_mut().enumerate() {
+                let a = p[(i * 2 + 4) % 6];
+                let b = p[(i * 2 + 5) % 6];
+                let c0 = p[(i * 2) % 6];
+                let d0 = p[(i * 2 + 1) % 6];
+                if let Some((x,y)) = calc_intersection(a, b, c0, d0) {
+                    c.x = x;
+                    c.y = y;
+                }
+            }
+            p.to_vec()
+        } else {
+            p.to_vec()
+        };
+        self.vertices = p.iter().enumerate().map(|(i, &(x,y))| {
+            if i == 0 {
+                Vertex::move_to(x, y)
+            } else {
+                Vertex::line_to(x, y)
+            }
+        }).collect();
+        let v = self.vertices[0];
+        self.vertices.push( Vertex::close_polygon(v.x, v.y) );
+    }
+    /// Vertices sorted by increasing y
+    fn arrange_vertices(&self) -> [Coord<C>; 3] {
+        let mut coord = self.coord;
+        if self.coord[0].y > self.coord[2].y {
+            coord[0] = self.coord[2];
+            coord[2] = self.coord[0];
+        }
+        if coord[0].y > coord[1].y {
+            coord.swap(0, 1);
+        }
+        if coord[1].y > coord[2].y {
+            coord.swap(1, 2);
+        }
+        coord
+    }
+}
+
+/// Offset a line from (`x1`,`y1`) to (`x2`,`y2`) perpendicular by `d`
+fn calc_orthogonal(d: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> (f64, f64) {
+    let dx = x2 - x1;
+    let dy = y2 - y1;
+    let len = (dx * dx + dy * dy).sqrt();
+    (d * dy / len, -d * dx / len)
+}
+
+/// Move each edge of a triangle outward by `d`, returning the six end points
+fn dilate_triangle(x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64,
+                   d: f64) -> [(f64,f64); 6] {
+    let (mut d1, mut d2, mut d3) = ((0.0,0.0), (0.0,0.0), (0.0,0.0));
+    let loc = cross(&Vertex::xy(x1,y1), &Vertex::xy(x2,y2), &Vertex::xy(x3,y3));
+    if loc.abs() > 1e-30 {
+        let d = if loc > 0.0 { -d } else { d };
+        d1 = calc_orthogonal(d, x1, y1, x2, y2);
+        d2 = calc_orthogonal(d, x2, y2, x3, y3);
+        d3 = calc_orthogonal(d, x3, y3, x1, y1);
+    }
+    [(x1 + d1.0, y1 + d1.1), (x2 + d1.0, y2 + d1.1),
+     (x2 + d2.0, y2 + d2.1), (x3 + d2.0, y3 + d2.1),
+     (x3 + d3.0, y3 + d3.1), (x1 + d3.0, y1 + d3.1)]
+}
+
+/// Intersection of the lines through `a` -> `b` and `c` -> `d`
+fn calc_intersection(a: (f64,f64), b: (f64,f64), c: (f64,f64), d: (f64,f64))
+                     -> Option<(f64,f64)> {
+    let num = (a.1-c.1) * (d.0-c.0) - (a.0-c.0) * (d.1-c.1);
+    let den = (b.0-a.0) * (d.1-c.1) - (b.1-a.1) * (d.0-c.0);
+    if den.abs() < 1e-30 {
+        return None;
+    }
+    let r = num / den;
+    Some((a.0 + r * (b.0-a.0), a.1 + r * (b.1-a.1)))
+}
+
+/// Color and position along one edge of the triangle
+///
+/// Holds `N` color channels
+#[derive(Debug,Copy,Clone)]
+struct ColorCalc<const N: usize> {
+    x1: f64,
+    y1: f64,
+    dx: f64,
+    /// Inverse of the edge height
+    dy_inv: f64,
+    /// Channels at the start of the edge
+    v1: [i64; N],
+    /// Change in channels along the edge
+    dv: [i64; N],
+    /// Channels at the current y
+    v: [i64; N],
+    /// Position at the current y, in subpixels
+    x: i64,
+}
+
+impl<const N: usize> Default for ColorCalc<N> {
+    fn default() -> Self {
+        Self { x1: 0.0, y1: 0.0, dx: 0.0, dy_inv: 0.0,
+               v1: [0; N], dv: [0; N], v: [0; N], x: 0 }
+    }
+}
+
+impl<const N: usize> ColorCalc<N> {
+    /// Edge from (`x1`,`y1`) to (`x2`,`y2`) with colors `v1` to `v2`
+    fn new(x1: f64, y1: f64, v1: [u8; N], x2: f64, y2: f64, v2: [u8; N]) -> Self {
+        let dy = y2 - y1;
+        let mut s = Self { x1: x1 - 0.5, y1: y1 - 0.5, dx: x2 - x1,
+                           dy_inv: if dy < 1e-5 { 1e5 } else { 1.0 / dy },
+                           .. Self::default() };
+        for i in 0 .. N {
+            s.v1[i] = i64::from(v1[i]);
+            s.dv[i] = i64::from(v2[i]) - s.v1[i];
+        }
+        s
+    }
+    /// Compute position and color at `y`
+    fn calc(&mut self, y: f64) {
+        let k = ((y - self.y1) * self.dy_inv).clamp(0.0, 1.0);
+        for i in 0 .. N {
+            self.v[i] = self.v1[i] + (self.dv[i] as f64 * k).round() as i64;
+        }
+        self.x = ((self.x1 + self.dx * k) * SUBPIXEL_SCALE as f64).round() as i64;
+    }
+}
+
+/// Edges of a triangle, split into upper and lower sub-triangles
+#[derive(Debug,Default,Copy,Clone)]
+struct Edges<const N: usize> {
+    /// Swap left and right edges
+    swap: bool,
+    /// Middle vertex, boundary between the sub-triangles
+    y2: i64,
+    /// Edge from top to bottom
+    c1: ColorCalc<N>,
+    /// Edge from top to middle
+    c2: ColorCalc<N>,
+    /// Edge from middle to bottom
+    c3: ColorCalc<N>,
+}
+
+impl<const N: usize> Edges<N> {
+    /// Compute edges from vertices sorted by y
+    fn new<C, F>(coord: &[Coord<C>; 3], channels: F) -> Self
+        where F: Fn(&C) -> [u8; N]
+    {
+        let edge = |a: &Coord<C>, b: &Coord<C>| {
+            ColorCalc::new(a.x, a.y, channels(&a.color),
+                           b.x, b.y, channels(&b.color))
+        };
+        let (p0, p1, p2) = (&coord[0], &coord[1], &coord[2]);
+        Self {
+            y2: p1.y as i64,
+            swap: cross(&Vertex::xy(p0.x, p0.y),
+                        &Vertex::xy(p2.x, p2.y),
+                        &Vertex::xy(p1.x, p1.y)) < 0.0,
+            c1: edge(p0, p2),
+            c2: edge(p0, p1),
+            c3: edge(p1, p2),
+        }
+    }
+    /// Interpolate channels for `len` pixels starting at (`x`,`y`)
+    fn generate<C, F>(&mut self, x: i64, y: i64, span: &mut [C], color: F)
+        where F: Fn([u8; N]) -> C
+    {
+        self.c1.calc(y as f64);
+        let c2 = if y <= self.y2 {
+            // Lower sub-triangle
+            self.c2.calc(y as f64 + self.c2.dy_inv);
+            &self.c2
+        } else {
+            // Upper sub-triangle
+            self.c3.calc(y as f64 - self.c3.dy_inv);
+            &self.c3
+        };
+        let (pc1, pc2) = if self.swap { (c2, &self.c1) } else { (&self.c1, c2) };
+
+        // Horizontal length in subpixels, protected from division by zero
+        let nlen = (pc2.x - pc1.x).abs().max(1);
+        let mut inc = pc2.v;
+        for (inc, v1) in inc.iter_mut().zip(pc1.v.iter()) {
+            *inc = ((*inc - v1) << DDA_SHIFT) / nlen;
+        }
+        // Start of the gradient relative to the span, in subpixels
+        let start = pc1.x - (x << SUBPIXEL_SHIFT);
+
+        // Pixels outside the gradient, at either end or from anti-aliasing,
+        //   may overflow and are clamped
+        for (j, pix) in span.iter_mut().enumerate() {
+            let dist = j as i64 * SUBPIXEL_SCALE - start;
+            let mut v = [0u8; N];
+            for i in 0 .. N {
+                v[i] = (pc1.v[i] + ((inc[i] * dist) >> DDA_SHIFT)).clamp(0, 255) as u8;
+            }
+            *pix = color(v);
+        }
+    }
+}
+
+/// Gouraud shaded triangle, Color
+///
+/// Interpolates the red, green, blue and alpha channels
+#[derive(Debug,Clone)]
+pub struct SpanGouraudRgba {
+    triangle: SpanGouraud<Rgba8>,
+    edges: Edges<4>,
+}
+
+/// Gouraud shaded triangle, Gray scale
+///
+/// Interpolates the value and alpha channels
+#[derive(Debug,Clone)]
+pub struct SpanGouraudGray {
+    triangle: SpanGouraud<Gray8>,
+    edges: Edges<2>,
+}
+
+macro_rules! impl_span_gouraud {
+    ($name:ident, $color:ident) => {
+        impl $name {
+            /// Create a new Gouraud shaded triangle
+            ///
+            /// Colors `c1`, `c2` and `c3` are at vertices (`x1`,`y1`),
+            ///   (`x2`,`y2`) and (`x3`,`y3`).  The triangle is dilated by `d`,
+            ///   typically 0.0 or a fraction of a pixel
+            #[allow(clippy::too_many_arguments)]
+            pub fn new<C: Color>(c1: C, c2: C, c3: C,
+                                 x1: f64, y1: f64, x2: f64, y2: f64,
+                                 x3: f64, y3: f64, d: f64) -> Self {
+                let triangle = SpanGouraud::new($color::from_trait(c1),
+                                                $color::from_trait(c2),
+                                                $color::from_trait(c3),
+                                                x1, y1, x2, y2, x3, y3, d);
+                Self { triangle, edges: Edges::default() }
+            }
+            /// Set the colors at each vertex
+            pub fn colors<C: Color>(&mut self, c1: C, c2: C, c3: C) {
+                self.triangle.colors($color::from_trait(c1),
+                                     $color::from_trait(c2),
+                                     $color::from_trait(c3));
+            }
+            /// Set the triangle and dilate it by `d`
+            #[allow(clippy::too_many_arguments)]
+            pub fn triangle(&mut self,
+                            x1: f64, y1: f64, x2: f64, y2: f64,
+                            x3: f64, y3: f64, d: f64) {
+                self.triangle.triangle(x1, y1, x2, y2, x3, y3, d);
+            }
+        }
+        impl VertexSource for $name {
+            fn xconvert(&self) -> Vec<Vertex<f64>> {
+                self.triangle.xconvert()
+            }
+        }
+    }
+}
+
+impl_span_gouraud!(SpanGouraudRgba, Rgba8);
+impl_span_gouraud!(SpanGouraudGray, Gray8);
+
+impl SpanGenerator for SpanGouraudRgba {
+    type Color = Rgba8;
+    fn prepare(&mut self) {
+        let coord = self.triangle.arrange_vertices();
+        self.edges = Edges::new(&coord, |c| [c.r, c.g, c.b, c.a]);
+    }
+    fn generate(&mut self, x: i64, y: i64, _len: usize, span: &mut [Rgba8]) {
+        self.edges.generate(x, y, span, |v| Rgba8::new(v[0], v[1], v[2], v[3]));
+    }
+}
+
+impl SpanGenerator for SpanGouraudGray {
+    type Color = Gray8;
+    fn prepare(&mut self) {
+        let coord = self.triangle.arrange_vertices();
+        self.edges = Edges::new(&coord, |c| [c.value, c.alpha]);
+    }
+    fn generate(&mut self, x: i64, y: i64, _len: usize, span: &mut [Gray8]) {
+        self.edges.generate(x, y, span, |v| Gray8::new_with_alpha(v[0], v[1]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn span_gouraud_test() {
+        let mut span = SpanGouraudGray::new(Gray8::new(0), Gray8::new(0),
+                                            Gray8::new(200),
+                                            0.0, 0.0, 100.0, 0.0, 0.0, 100.0,
+                                            0.0);
+        assert_eq!(span.xconvert().len(), 4);
+        span.prepare();
+        let mut colors = vec![Gray8::default(); 10];
+        span.generate(0, 50, 10, &mut colors);
+        // Value only varies along y
+        for c in &colors {
+            assert!((c.value as i64 - 100).abs() <= 2, "{:?}", c);
+            assert_eq!(c.alpha, 255);
+        }
+
+        // Dilation adds a bevel at each vertex
+        span.triangle(0.0, 0.0, 100.0, 0.0, 0.0, 100.0, 0.5);
+        assert_eq!(span.xconvert().len(), 7);
+    }
+}
@@ -0,0 +1,450 @@
+//! Gradient Functions
+//!
+//! Shapes of gradients used by [`SpanGradient`].  Each maps a position,
+//!   relative to the gradient origin after the gradient transform, to a
+//!   distance along the gradient
+//!
+//!   - [`GradientX`] and [`GradientY`] - Linear
+//!   - [`GradientRadial`] and [`GradientRadialD`] - Circular
+//!   - [`GradientRadialFocus`] - Circular with the focal point offset
+//!   - [`GradientDiamond`] - Diamond, or square rotated by 45 degrees
+//!   - [`GradientXY`] and [`GradientSqrtXY`] - Cross, product of x and y
+//!   - [`GradientConic`] - Angle around the origin
+//!
+//! Colors along the gradient come from a slice of colors, which may be
+//!   built from color stops with a [`GradientLut`]
+//!
+//! Beyond the ends of the gradient, colors are padded with the first and
+//!   last colors.  Wrap a gradient with [`GradientRepeat`] or
+//!   [`GradientReflect`] to repeat or mirror the gradient instead
+//!
+//! # Example
+//!
+//!     use agg::Gradient;
+//!
+//!     // Distances are in subpixels, 16 per pixel
+//!     let g = agg::GradientRadial{};
+//!     assert_eq!(g.calculate(3 * 16, 4 * 16, 0), 5 * 16);
+//!
+//!     let g = agg::GradientDiamond{};
+//!     assert_eq!(g.calculate(-3 * 16, 4 * 16, 0), 4 * 16);
+//!
+//! [`SpanGradient`]: ../render/struct.SpanGradient.html
+//! [`GradientX`]: struct.GradientX.html
+//! [`GradientY`]: struct.GradientY.html
+//! [`GradientRadial`]: struct.GradientRadial.html
+//! [`GradientRadialD`]: struct.GradientRadialD.html
+//! [`GradientRadialFocus`]: struct.GradientRadialFocus.html
+//! [`GradientDiamond`]: struct.GradientDiamond.html
+//! [`GradientXY`]: struct.GradientXY.html
+//! [`GradientSqrtXY`]: struct.GradientSqrtXY.html
+//! [`GradientConic`]: struct.GradientConic.html
+//! [`GradientLut`]: struct.GradientLut.html
+//! [`GradientRepeat`]: struct.GradientRepeat.html
+//! [`GradientReflect`]: struct.GradientReflect.html
+
+use crate::Gradient;
+use crate::Color;
+use crate::color::Rgba8;
+
+use std::f64::consts::PI;
+
+/// Subpixel shift of gradient coordinates and distances
+pub(crate) const GRADIENT_SUBPIXEL_SHIFT : i64 = 4;
+/// Subpixels per pixel of gradient coordinates and distances
+pub(crate) const GRADIENT_SUBPIXEL_SCALE : i64 = 1 << GRADIENT_SUBPIXEL_SHIFT;
+
+/// Integer square root, truncated
+fn fast_sqrt(v: i64) -> i64 {
+    (v as f64).sqrt() as i64
+}
+
+/// Linear gradient along x
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientX {}
+impl Gradient for GradientX {
+    fn calculate(&self, x: i64, _: i64, _: i64) -> i64 {
+        x
+    }
+}
+
+/// Linear gradient along y
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientY {}
+impl Gradient for GradientY {
+    fn calculate(&self, _: i64, y: i64, _: i64) -> i64 {
+        y
+    }
+}
+
+/// Radial gradient, distance from the origin
+///
+/// Uses an integer square root, see [`GradientRadialD`] for a more
+///   accurate version
+///
+/// [`GradientRadialD`]: struct.GradientRadialD.html
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientRadial {}
+impl Gradient for GradientRadial {
+    fn calculate(&self, x: i64, y: i64, _: i64) -> i64 {
+        fast_sqrt(x * x + y * y)
+    }
+}
+
+/// Radial gradient, distance from the origin, computed in floating point
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientRadialD {}
+impl Gradient for GradientRadialD {
+    fn calculate(&self, x: i64, y: i64, _: i64) -> i64 {
+        let (x, y) = (x as f64, y as f64);
+        (x * x + y * y).sqrt().round() as i64
+    }
+}
+
+/// Radial gradient with an offset focal point
+///
+/// The gradient is 0 at the focal point and reaches its end at radius
+///   `r` from the origin
+#[derive(Debug,Copy,Clone)]
+pub struct GradientRadialFocus {
+    r: i64,
+    fx: i64,
+    fy: i64,
+    r2: f64,
+    fx2: f64,
+    fy2: f64,
+    mul: f64,
+}
+
+impl Default for GradientRadialFocus {
+    fn default() -> Self {
+        Self::new(100.0, 0.0, 0.0)
+    }
+}
+
+impl GradientRadialFocus {
+    /// Create a new Radial Gradient with radius `r` and focal point
+    ///   (`fx`,`fy`), in pixels
+    pub fn new(r: f64, fx: f64, fy: f64) -> Self {
+        let s = GRADIENT_SUBPIXEL_SCALE as f64;
+        let mut g = Self {
+            r: (r * s).round() as i64,
+            fx: (fx * s).round() as i64,
+            fy: (fy * s).round() as i64,
+            r2: 0.0, fx2: 0.0, fy2: 0.0, mul: 0.0,
+        };
+        g.update_values();
+        g
+    }
+    /// Radius in pixels
+    pub fn radius(&self) -> f64 {
+        self.r as f64 / GRADIENT_SUBPIXEL_SCALE as f64
+    }
+    /// Focal point x in pixels
+    pub fn focus_x(&self) -> f64 {
+        self.fx as f64 / GRADIENT_SUBPIXEL_SCALE as f64
+    }
+    /// Focal point y in pixels
+    pub fn focus_y(&self) -> f64 {
+        self.fy as f64 / GRADIENT_SUBPIXEL_SCALE as f64
+    }
+    fn update_values(&mut self) {
+        // Calculate the invariant values.  In case the focal center
+        //   lies exactly on the gradient circle the divisor degenerates
+        //   into zero. In this case we just move the focal center by
+        //   one subpixel unit possibly in the direction to the origin (0,0)
+        //   and calculate the values again.
+        self.r2  = (self.r  * self.r)  as f64;
+        self.fx2 = (self.fx * self.fx) as f64;
+        self.fy2 = (self.fy * self.fy) as f64;
+        let mut d = self.r2 - (self.fx2 + self.fy2);
+        if d == 0.0 {
+            if self.fx != 0 {
+                self.fx -= self.fx.signum();
+            }
+            if self.fy != 0 {
+                self.fy -= self.fy.signum();
+            }
+            self.fx2 = (self.fx * self.fx) as f64;
+            self.fy2 = (self.fy * self.fy) as f64;
+            d = self.r2 - (self.fx2 + self.fy2);
+        }
+        self.mul = self.r as f64 / d;
+    }
+}
+
+impl Gradient for GradientRadialFocus {
+    fn calculate(&self, x: i64, y: i64, _: i64) -> i64 {
+        let dx = (x - self.fx) as f64;
+        let dy = (y - self.fy) as f64;
+        let (fx, fy) = (self.fx as f64, self.fy as f64);
+        let d2 = dx * fy - dy * fx;
+        let d3 = self.r2 * (dx * dx + dy * dy) - d2 * d2;
+        ((dx * fx + dy * fy + d3.abs().sqrt()) * self.mul).round() as i64
+    }
+}
+
+/// Diamond gradient, maximum of |x| and |y|
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientDiamond {}
+impl Gradient for GradientDiamond {
+    fn calculate(&self, x: i64, y: i64, _: i64) -> i64 {
+        x.abs().max(y.abs())
+    }
+}
+
+/// Cross gradient, product of |x| and |y| scaled by the gradient size
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientXY {}
+impl Gradient for GradientXY {
+    fn calculate(&self, x: i64, y: i64, d: i64) -> i64 {
+        if d == 0 {
+            return 0;
+        }
+        x.abs() * y.abs() / d
+    }
+}
+
+/// Cross gradient, square root of the product of |x| and |y|
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientSqrtXY {}
+impl Gradient for GradientSqrtXY {
+    fn calculate(&self, x: i64, y: i64, _: i64) -> i64 {
+        fast_sqrt(x.abs() * y.abs())
+    }
+}
+
+/// Conic gradient, angle around the origin
+///
+/// The gradient is symmetric about the x axis, the angle from 0 to PI
+///   is scaled to the gradient size
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientConic {}
+impl Gradient for GradientConic {
+    fn calculate(&self, x: i64, y: i64, d: i64) -> i64 {
+        ((y as f64).atan2(x as f64).abs() * d as f64 / PI).round() as i64
+    }
+}
+
+/// Repeat Spread, the gradient restarts after reaching its end
+///
+///     use agg::Gradient;
+///     let g = agg::GradientRepeat::new(agg::GradientX{});
+///     assert_eq!(g.calculate(130, 0, 100), 30);
+///     assert_eq!(g.calculate(-30, 0, 100), 70);
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientRepeat<G: Gradient> {
+    gradient: G,
+}
+impl<G> GradientRepeat<G> where G: Gradient {
+    /// Repeat `gradient`
+    pub fn new(gradient: G) -> Self {
+        Self { gradient }
+    }
+}
+impl<G> Gradient for GradientRepeat<G> where G: Gradient {
+    fn calculate(&self, x: i64, y: i64, d: i64) -> i64 {
+        if d == 0 {
+            return 0;
+        }
+        self.gradient.calculate(x, y, d).rem_euclid(d)
+    }
+}
+
+/// Reflect Spread, the gradient reverses direction after reaching its end
+///
+///     use agg::Gradient;
+///     let g = agg::GradientReflect::new(agg::GradientX{});
+///     assert_eq!(g.calculate(130, 0, 100), 70);
+///     assert_eq!(g.calculate(-30, 0, 100), 30);
+#[derive(Debug,Default,Copy,Clone)]
+pub struct GradientReflect<G: Gradient> {
+    gradient: G,
+}
+impl<G> GradientReflect<G> where G: Gradient {
+    /// Reflect `gradient`
+    pub fn new(gradient: G) -> Self {
+        Self { gradient }
+    }
+}
+impl<G> Gradient for GradientReflect<G> where G: Gradient {
+    fn calculate(&self, x: i64, y: i64, d: i64) -> i64 {
+        if d == 0 {
+            return 0;
+        }
+        let d2 = d * 2;
+        let ret = self.gradient.calculate(x, y, d).rem_euclid(d2);
+        if ret >= d {
+            d2 - ret
+        } else {
+            ret
+        }
+    }
+}
+
+/// Gradient Color Lookup Table
+///
+/// Colors are added as stops at offsets from 0 to 1 and are linearly
+///   interpolated, including alpha, when the table is built.  Before the
+///   first stop and after the last stop the colors are constant
+///
+///     let mut lut = agg::GradientLut::new();
+///     lut.add_color(0.0, agg::Rgba8::new(255,0,0,255));
+///     lut.add_color(1.0, agg::Rgba8::new(0,0,255,0));
+///     lut.build_lut();
+///     assert_eq!(lut.len(), 256);
+///     assert_eq!(lut.colors()[128], agg::Rgba8::new(128,0,128,128));
+#[derive(Debug,Clone)]
+pub struct GradientLut {
+    /// Color stops, offset and color
+    stops: Vec<(f64, Rgba8)>,
+    /// Interpolated colors
+    lut: Vec<Rgba8>,
+}
+
+impl Default for GradientLut {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GradientLut {
+    /// Create a new Lookup Table with 256 colors
+    pub fn new() -> Self {
+        Self::with_size(256)
+    }
+    /// Create a new Lookup Table with `size` colors
+    pub fn with_size(size: usize) -> Self {
+        Self { stops: vec![], lut: vec![Rgba8::new(0,0,0,0); size] }
+    }
+    /// Remove all color stops
+    pub fn remove_all(&mut self) {
+        self.stops.clear();
+    }
+    /// Add a color stop at `offset`, clamped to [0,1]
+    ///
+    /// If multiple stops have the same offset, the first one is used.  A
+    ///   stop with a NaN offset is ignored
+    pub fn add_color<C: Color>(&mut self, offset: f64, color: C) {
+        if offset.is_nan() {
+            return;
+        }
+        self.stops.push((offset.clamp(0.0, 1.0), Rgba8::from_trait(color)));
+    }
+    /// Interpolate the color stops into the table
+    ///
+    /// With a single stop, the table is filled with its color.  With no
+    ///   stops, the table is unchanged
+    pub fn build_lut(&mut self) {
+        self.stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        self.stops.dedup_by(|b, a| a.0 == b.0);
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first.1, last.1),
+            _ => return,
+        };
+        let size = self.lut.len();
+        let index = |offset: f64| (offset * size as f64).round() as usize;
+        let mut start = index(self.stops[0].0);
+        for c in &mut self.lut[..start] {
+            *c = first;
+        }
+        for w in self.stops.windows(2) {
+            let end = index(w[1].0);
+            let n = (end - start) as f64;
+            for (i, c) in self.lut[start..end].iter_mut().enumerate() {
+                *c = w[0].1.gradient(w[1].1, i as f64 / n);
+            }
+            start = end;
+        }
+        for c in &mut self.lut[start..] {
+            *c = last;
+        }
+    }
+    /// Number of colors in the table
+    pub fn len(&self) -> usize {
+        self.lut.len()
+    }
+    /// Return if the table has no colors
+    pub fn is_empty(&self) -> bool {
+        self.lut.is_empty()
+    }
+    /// Interpolated colors
+    pub fn colors(&self) -> &[Rgba8] {
+        &self.lut
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const S: i64 = GRADIENT_SUBPIXEL_SCALE;
+
+    #[test]
+    fn gradient_functions_test() {
+        let d = 100 * S;
+        assert_eq!(GradientX{}.calculate(3, 4, d), 3);
+        assert_eq!(GradientY{}.calculate(3, 4, d), 4);
+        assert_eq!(GradientRadial{}.calculate(3 * S, -4 * S, d), 5 * S);
+        assert_eq!(GradientRadialD{}.calculate(-3 * S, 4 * S, d), 5 * S);
+        assert_eq!(GradientDiamond{}.calculate(-7, 4, d), 7);
+        assert_eq!(GradientXY{}.calculate(-50 * S, 20 * S, d), 10 * S);
+        assert_eq!(GradientSqrtXY{}.calculate(-9 * S, 4 * S, d), 6 * S);
+        assert_eq!(GradientConic{}.calculate(10, 0, d), 0);
+        assert_eq!(GradientConic{}.calculate(0, 10, d), d / 2);
+        assert_eq!(GradientConic{}.calculate(-10, 0, d), d);
+        assert_eq!(GradientConic{}.calculate(0, -10, d), d / 2);
+    }
+    #[test]
+    fn gradient_radial_focus_test() {
+        // Focus at the origin is a radial gradient
+        let g = GradientRadialFocus::new(100.0, 0.0, 0.0);
+        assert_eq!(g.calculate(30 * S, 40 * S, 0), 50 * S);
+        // Offset focus, 0 at the focus and the radius at the edge
+        let g = GradientRadialFocus::new(100.0, 50.0, 0.0);
+        assert_eq!(g.calculate(50 * S, 0, 0), 0);
+        assert_eq!(g.calculate(100 * S, 0, 0), 100 * S);
+        assert_eq!(g.calculate(-100 * S, 0, 0), 100 * S);
+        assert_eq!(g.calculate(0, 100 * S, 0), 100 * S);
+        // Focus on the circle is moved inside
+        let g = GradientRadialFocus::new(10.0, 10.0, 0.0);
+        assert_eq!(g.focus_x(), 10.0 - 1.0 / S as f64);
+        assert_eq!(g.radius(), 10.0);
+    }
+    #[test]
+    fn gradient_spread_test() {
+        let repeat = GradientRepeat::new(GradientY{});
+        let reflect = GradientReflect::new(GradientY{});
+        let d = 10;
+        let r0 : Vec<_> = (-12 ..= 22).step_by(2).map(|y| repeat.calculate(0, y, d)).collect();
+        let r1 : Vec<_> = (-12 ..= 22).step_by(2).map(|y| reflect.calculate(0, y, d)).collect();
+        assert_eq!(r0, vec![8, 0, 2, 4, 6, 8, 0, 2, 4, 6, 8, 0, 2, 4, 6, 8, 0, 2]);
+        assert_eq!(r1, vec![8, 10, 8, 6, 4, 2, 0, 2, 4, 6, 8, 10, 8, 6, 4, 2, 0, 2]);
+        assert_eq!(repeat.calculate(0, 5, 0), 0);
+        assert_eq!(reflect.calculate(0, 5, 0), 0);
+    }
+    #[test]
+    fn gradient_lut_test() {
+        let red   = Rgba8::new(255, 0, 0, 255);
+        let green = Rgba8::new(0, 255, 0, 255);
+        let clear = Rgba8::new(0, 0, 255, 0);
+        let mut lut = GradientLut::with_size(10);
+        lut.add_color(0.8, clear);
+        lut.add_color(0.2, red);
+        lut.add_color(0.5, green);
+        lut.add_color(0.5, red);
+        lut.build_lut();
+        let c = lut.colors();
+        assert_eq!(c.len(), 10);
+        assert_eq!(&c[..3], &[red, red, red]);
+        assert_eq!(c[3], Rgba8::new(170, 85, 0, 255));
+        assert_eq!(c[5], green);
+        assert_eq!(c[6], Rgba8::new(0, 170, 85, 170));
+        assert_eq!(&c[8..], &[clear, clear]);
+
+        lut.remove_all();
+        lut.add_color(0.3, green);
+        lut.add_color(f64::NAN, red);
+        lut.build_lut();
+        assert!(lut.colors().iter().all(|&c| c == green));
+    }
+}
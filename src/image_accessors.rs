@@ -0,0 +1,194 @@
+//! Image Accessors
+//!
+//! Read pixels from an image for the image span generators, defining the
+//!   colors of locations outside of the image
+//!
+//!   - [`ImageAccessorNoClip`] - No checks, locations must be in the image
+//!   - [`ImageAccessorClip`] - Background color outside the image
+//!   - [`ImageAccessorClone`] - Edges of the image extended outward
+//!   - [`ImageAccessorWrap`] - Image tiled, see [`WrapMode`]
+//!
+//! [`ImageAccessorNoClip`]: struct.ImageAccessorNoClip.html
+//! [`ImageAccessorClip`]: struct.ImageAccessorClip.html
+//! [`ImageAccessorClone`]: struct.ImageAccessorClone.html
+//! [`ImageAccessorWrap`]: struct.ImageAccessorWrap.html
+//! [`WrapMode`]: enum.WrapMode.html
+
+use crate::Rgba8;
+use crate::Color;
+use crate::Source;
+use crate::Pixel;
+use crate::ImageAccessor;
+
+/// Image without checks on locations
+///
+/// Fastest, but locations outside of the image panic.  Only use when
+///   all sampled pixels, including those under the filter, are in the image
+///
+///     use agg::ImageAccessor;
+///
+///     let mut pix = agg::Pixfmt::<agg::Rgb8>::new(2,2);
+///     pix.clear();
+///     let img = agg::ImageAccessorNoClip::new(&pix);
+///     assert_eq!(img.pixel(1,1), agg::Rgba8::white());
+///
+#[derive(Debug)]
+pub struct ImageAccessorNoClip<'a, T> {
+    pix: &'a T,
+}
+
+/// Image with a background color outside of its bounds
+///
+///     use agg::ImageAccessor;
+///
+///     let mut pix = agg::Pixfmt::<agg::Rgb8>::new(2,2);
+///     pix.clear();
+///     let bg = agg::Rgba8::new(0,0,0,0);
+///     let img = agg::ImageAccessorClip::new(&pix, bg);
+///     assert_eq!(img.pixel(1,1), agg::Rgba8::white());
+///     assert_eq!(img.pixel(2,1), bg);
+///     assert_eq!(img.pixel(-1,0), bg);
+///
+#[derive(Debug)]
+pub struct ImageAccessorClip<'a, T> {
+    pix: &'a T,
+    background: Rgba8,
+}
+
+/// Image with edge pixels extended outside of its bounds
+///
+///     use agg::ImageAccessor;
+///
+///     let mut pix = agg::Pixfmt::<agg::Rgb8>::new(2,1);
+///     let red = agg::Rgba8::new(255,0,0,255);
+///     pix.copy_pixel(0,0, red);
+///     let img = agg::ImageAccessorClone::new(&pix);
+///     assert_eq!(img.pixel(-5,0), red);
+///     assert_eq!(img.pixel(-5,-5), red);
+///     assert_eq!(img.pixel(5,5), img.pixel(1,0));
+///
+#[derive(Debug)]
+pub struct ImageAccessorClone<'a, T> {
+    pix: &'a T,
+}
+
+/// Wrapping of locations outside an image, in one direction
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub enum WrapMode {
+    /// Image is repeated: 0, 1, .. n-1, 0, 1, ..
+    #[default]
+    Repeat,
+    /// Image is repeated and mirrored: 0, 1, .. n-1, n-1, .. 1, 0, 0, 1, ..
+    Reflect,
+}
+
+impl WrapMode {
+    /// Wrap location `v` into the range [0, `size`)
+    ///
+    ///     use agg::WrapMode;
+    ///
+    ///     assert_eq!(WrapMode::Repeat.wrap(-1, 4), 3);
+    ///     assert_eq!(WrapMode::Repeat.wrap(9, 4), 1);
+    ///     assert_eq!(WrapMode::Reflect.wrap(-1, 4), 0);
+    ///     assert_eq!(WrapMode::Reflect.wrap(5, 4), 2);
+    ///
+    pub fn wrap(self, v: i64, size: i64) -> i64 {
+        match self {
+            WrapMode::Repeat => v.rem_euclid(size),
+            WrapMode::Reflect => {
+                let v = v.rem_euclid(size * 2);
+                if v >= size {
+                    size * 2 - v - 1
+                } else {
+                    v
+                }
+            }
+        }
+    }
+}
+
+/// Image tiled infinitely in both directions
+///
+/// Each direction is wrapped independently
+///
+///     use agg::{ImageAccessor,WrapMode};
+///
+///     let mut pix = agg::Pixfmt::<agg::Rgb8>::new(2,2);
+///     let red = agg::Rgba8::new(255,0,0,255);
+///     pix.copy_pixel(0,0, red);
+///     let img = agg::ImageAccessorWrap::new(&pix, WrapMode::Repeat, WrapMode::Reflect);
+///     assert_eq!(img.pixel(2,0), red);
+///     assert_eq!(img.pixel(0,3), red);
+///     assert_eq!(img.pixel(0,2), img.pixel(0,1));
+///
+#[derive(Debug)]
+pub struct ImageAccessorWrap<'a, T> {
+    pix: &'a T,
+    wrap_x: WrapMode,
+    wrap_y: WrapMode,
+}
+
+impl<'a, T> ImageAccessorNoClip<'a, T> where T: Source + Pixel {
+    /// Create a new accessor of `pix`
+    pub fn new(pix: &'a T) -> Self {
+        Self { pix }
+    }
+}
+
+impl<'a, T> ImageAccessorClip<'a, T> where T: Source + Pixel {
+    /// Create a new accessor of `pix` with `background` color
+    pub fn new<C: Color>(pix: &'a T, background: C) -> Self {
+        Self { pix, background: Rgba8::from_trait(background) }
+    }
+    /// Set the background color
+    pub fn background<C: Color>(&mut self, background: C) {
+        self.background = Rgba8::from_trait(background);
+    }
+}
+
+impl<'a, T> ImageAccessorClone<'a, T> where T: Source + Pixel {
+    /// Create a new accessor of `pix`
+    pub fn new(pix: &'a T) -> Self {
+        Self { pix }
+    }
+}
+
+impl<'a, T> ImageAccessorWrap<'a, T> where T: Source + Pixel {
+    /// Create a new accessor of `pix` wrapping in x and y
+    pub fn new(pix: &'a T, wrap_x: WrapMode, wrap_y: WrapMode) -> Self {
+        Self { pix, wrap_x, wrap_y }
+    }
+}
+
+impl<T> ImageAccessor for ImageAccessorNoClip<'_, T> where T: Source + Pixel {
+    fn pixel(&self, x: i64, y: i64) -> Rgba8 {
+        self.pix.get((x as usize, y as usize))
+    }
+}
+
+impl<T> ImageAccessor for ImageAccessorClip<'_, T> where T: Source + Pixel {
+    fn pixel(&self, x: i64, y: i64) -> Rgba8 {
+        if x >= 0 && y >= 0 &&
+            x < self.pix.width() as i64 && y < self.pix.height() as i64 {
+            self.pix.get((x as usize, y as usize))
+        } else {
+            self.background
+        }
+    }
+}
+
+impl<T> ImageAccessor for ImageAccessorClone<'_, T> where T: Source + Pixel {
+    fn pixel(&self, x: i64, y: i64) -> Rgba8 {
+        let x = x.clamp(0, self.pix.width() as i64 - 1);
+        let y = y.clamp(0, self.pix.height() as i64 - 1);
+        self.pix.get((x as usize, y as usize))
+    }
+}
+
+impl<T> ImageAccessor for ImageAccessorWrap<'_, T> where T: Source + Pixel {
+    fn pixel(&self, x: i64, y: i64) -> Rgba8 {
+        let x = self.wrap_x.wrap(x, self.pix.width() as i64);
+        let y = self.wrap_y.wrap(y, self.pix.height() as i64);
+        self.pix.get((x as usize, y as usize))
+    }
+}
@@ -130,16 +130,26 @@ use std::fmt::Debug;
 pub use freetype as ft;
 
 pub mod paths;
+pub mod curves;
+pub mod svg_path;
+pub mod svg;
 pub mod stroke;
 pub mod transform;
 pub mod color;
 pub mod pixfmt;
+pub mod comp_op;
+pub mod blur;
 pub mod base;
 pub mod clip;
 pub mod raster;
 pub mod ppm;
 pub mod alphamask;
 pub mod render;
+pub mod gradient;
+pub mod gouraud;
+pub mod image_filters;
+pub mod image_accessors;
+pub mod span_image;
 pub mod text;
 pub mod outline;
 pub mod outline_aa;
@@ -147,7 +157,7 @@ pub mod line_interp;
 
 pub mod math;
 pub(crate) mod scan;
-pub(crate) mod buffer;
+pub mod buffer;
 pub(crate) mod cell;
 
 
@@ -156,14 +166,24 @@ pub mod gallery;
 #[doc(hidden)]
 pub use crate::paths::*;
 #[doc(hidden)]
+pub use crate::curves::*;
+#[doc(hidden)]
+pub use crate::svg_path::*;
+#[doc(hidden)]
+pub use crate::svg::*;
+#[doc(hidden)]
 pub use crate::stroke::*;
 #[doc(hidden)]
 pub use crate::transform::*;
 #[doc(hidden)]
 pub use crate::color::*;
 #[doc(hidden)]
+pub use crate::buffer::*;
+#[doc(hidden)]
 pub use crate::pixfmt::*;
 #[doc(hidden)]
+pub use crate::comp_op::*;
+#[doc(hidden)]
 pub use crate::base::*;
 #[doc(hidden)]
 pub use crate::clip::*;
@@ -174,6 +194,18 @@ pub use crate::alphamask::*;
 #[doc(hidden)]
 pub use crate::render::*;
 #[doc(hidden)]
+pub use crate::scan::ScanlineU8Am;
+#[doc(hidden)]
+pub use crate::gradient::*;
+#[doc(hidden)]
+pub use crate::gouraud::*;
+#[doc(hidden)]
+pub use crate::image_filters::*;
+#[doc(hidden)]
+pub use crate::image_accessors::*;
+#[doc(hidden)]
+pub use crate::span_image::*;
+#[doc(hidden)]
 pub use crate::text::*;
 #[doc(hidden)]
 pub use crate::line_interp::*;
@@ -231,7 +263,39 @@ pub trait Render {
     /// Set the Color of the Renderer
     fn color<C: Color>(&mut self, color: C);
     /// Prepare the Renderer
-    fn prepare(&self) { }
+    fn prepare(&mut self) { }
+}
+/// Generate colors for spans of pixels
+///
+/// Used by [`RenderingScanlineAA`] to color each span of a scanline
+///
+/// [`RenderingScanlineAA`]: render/struct.RenderingScanlineAA.html
+pub trait SpanGenerator {
+    /// Color type generated
+    type Color: Color + Default;
+    /// Prepare the generator, called once before rendering scanlines
+    fn prepare(&mut self) { }
+    /// Write the colors of `len` pixels from (`x`,`y`) to (`x+len-1`,`y`)
+    ///   into `span`
+    fn generate(&mut self, x: i64, y: i64, len: usize, span: &mut [Self::Color]);
+}
+/// Colors of the styles drawn by a compound rasterizer
+///
+/// Used by [`render_scanlines_compound`] to color each style, either with a
+///   solid color or with generated spans, e.g. from a [`SpanGenerator`]
+///
+/// A slice of colors is a Style Handler with solid colors indexed by style
+///
+/// [`render_scanlines_compound`]: render/fn.render_scanlines_compound.html
+/// [`SpanGenerator`]: trait.SpanGenerator.html
+pub trait StyleHandler {
+    /// Return if `style` is a solid color
+    fn is_solid(&self, style: usize) -> bool;
+    /// Return the color of a solid `style`
+    fn color(&self, style: usize) -> Rgba8;
+    /// Write the colors of `style` for `len` pixels from (`x`,`y`) to
+    ///   (`x+len-1`,`y`) into `span`
+    fn generate_span(&mut self, x: i64, y: i64, len: usize, style: usize, span: &mut [Rgba8]);
 }
 /*
 /// Rasterize lines, path, and other things to scanlines
@@ -256,10 +320,46 @@ pub trait Source {
     fn get(&self, id: (usize, usize)) -> Rgba8;
 }
 
+/// Gradient shape function
+///
+/// Maps a position (`x`,`y`), relative to the gradient origin, to a
+///   distance along the gradient.  Values are in subpixels, see
+///   [`SpanGradient`], and `d` is the distance of the end of the gradient
+///
+/// [`SpanGradient`]: render/struct.SpanGradient.html
+pub trait Gradient {
+    /// Calculate the distance along the gradient at (`x`,`y`)
+    fn calculate(&self, x: i64, y: i64, d: i64) -> i64;
+}
+
+/// Image filter kernel
+///
+/// Filters are symmetric and tabulated into an [`ImageFilterLut`]
+///
+/// [`ImageFilterLut`]: image_filters/struct.ImageFilterLut.html
+pub trait ImageFilter {
+    /// Distance from the center where the filter becomes zero, in pixels
+    fn radius(&self) -> f64;
+    /// Weight at distance `x` from the center, 0 <= `x` <= radius
+    fn calc_weight(&self, x: f64) -> f64;
+}
+
+/// Access image pixels at any location
+///
+/// Locations outside of the image are handled by each implementation
+pub trait ImageAccessor {
+    /// Get the color of pixel (`x`,`y`)
+    fn pixel(&self, x: i64, y: i64) -> Rgba8;
+}
+
 /// Drawing and pixel related routines
 pub trait Pixel {
     fn cover_mask() -> u64;
     fn bpp() -> usize;
+    /// Underlying data as stored, including any padding between rows
+    ///
+    /// Rows are packed and top row first only for images created with
+    ///   `new`; attached buffers keep their stride and row order
     fn as_bytes(&self) -> &[u8];
     fn to_file<P: AsRef<std::path::Path>>(&self, filename: P) -> Result<(),std::io::Error>;
     fn width(&self) -> usize;
@@ -430,6 +530,63 @@ pub trait Pixel {
     }
 }
 
+/// Coverage values from an alpha mask
+///
+/// Mask values are combined with coverage values as
+///   `(cover * mask + 255) >> 8`
+pub trait AlphaMask {
+    /// Mask value at (`x`,`y`)
+    fn pixel(&self, x: i64, y: i64) -> u64;
+    /// Mask value at (`x`,`y`) combined with `cover`
+    fn combine_pixel(&self, x: i64, y: i64, cover: u64) -> u64;
+    /// Fill `dst` with mask values from (`x`,`y`) to (`x+dst.len()-1`,`y`)
+    fn fill_hspan(&self, x: i64, y: i64, dst: &mut [u64]);
+    /// Combine `dst` with mask values from (`x`,`y`) to (`x+dst.len()-1`,`y`)
+    fn combine_hspan(&self, x: i64, y: i64, dst: &mut [u64]);
+    /// Fill `dst` with mask values from (`x`,`y`) to (`x`,`y+dst.len()-1`)
+    fn fill_vspan(&self, x: i64, y: i64, dst: &mut [u64]);
+    /// Combine `dst` with mask values from (`x`,`y`) to (`x`,`y+dst.len()-1`)
+    fn combine_vspan(&self, x: i64, y: i64, dst: &mut [u64]);
+}
+
+/// Access to rows and pixels in their stored color type
+///
+///     use agg::{Pixfmt,PixelData,RenderingBase,Rgb8,Rgba8};
+///
+///     let mut ren_base = RenderingBase::new(Pixfmt::<Rgb8>::new(4,2));
+///     ren_base.clear(Rgba8::white());
+///     ren_base.blend_hline(0, 1, 3, Rgba8::black(), 255);
+///     assert_eq!(ren_base.pixel(2,1), Rgb8::black());
+///     assert_eq!(ren_base.row(0), &[255u8; 12][..]);
+///     assert_eq!(ren_base.pixels().filter(|&c| c == Rgb8::white()).count(), 4);
+///
+pub trait PixelData: Pixel {
+    /// Color type of each stored pixel
+    type Color: Color;
+    /// Stored colors are premultiplied
+    const PREMULTIPLIED: bool = false;
+    /// Pixel at (`x`,`y`)
+    fn pixel(&self, x: usize, y: usize) -> Self::Color;
+    /// Bytes of row `y`, without padding
+    fn row(&self, y: usize) -> &[u8];
+    /// Mutable bytes of row `y`, without padding
+    fn row_mut(&mut self, y: usize) -> &mut [u8];
+    /// Iterator over the pixels of row `y`
+    ///
+    /// # Panics
+    ///
+    /// If `y` is outside of the image
+    fn row_pixels(&self, y: usize) -> Pixels<'_, Self> where Self: Sized {
+        assert!(y < self.height(), "row {} >= {} height", y, self.height());
+        let w = self.width();
+        Pixels::new(self, y * w, (y + 1) * w)
+    }
+    /// Iterator over all pixels, top row first
+    fn pixels(&self) -> Pixels<'_, Self> where Self: Sized {
+        Pixels::new(self, 0, self.width() * self.height())
+    }
+}
+
 
 
 pub(crate) trait LineInterp {
@@ -36,13 +36,44 @@ pub fn multiply_u8(a: u8, b: u8) -> u8 {
     tt as u8
 }
 
+/// Interpolate a value between two end points using fixed point math, 16-bit
+///
+/// See agg_color_rgba.h:rgba16::lerp of agg version 2.6
+///
+pub fn lerp_u16(p: u16, q: u16, a: u16) -> u16 {
+    let base_shift = 16;
+    let base_msb = 1 << (base_shift - 1);
+    let v = if p > q { 1 } else { 0 };
+    let (q,p,a) = (i64::from(q), i64::from(p), i64::from(a));
+    let t0 : i64 = (q - p) * a + base_msb - v;
+    let t1 : i64 = ((t0>>base_shift) + t0) >> base_shift;
+    (p + t1) as u16
+}
+
+/// Interpolator a value between two end points pre-calculated by alpha, 16-bit
+///
+/// p + q - (p*a)
+pub fn prelerp_u16(p: u16, q: u16, a: u16) -> u16 {
+    p.wrapping_add(q).wrapping_sub(multiply_u16(p,a))
+}
 
+/// Multiply two u16 values using fixed point math
+pub fn multiply_u16(a: u16, b: u16) -> u16 {
+    let base_shift = 16;
+    let base_msb = 1 << (base_shift - 1);
+    let (a,b) = (u64::from(a), u64::from(b));
+    let t : u64  = a * b + base_msb;
+    let tt : u64 = ((t >> base_shift) + t) >> base_shift;
+    tt as u16
+}
 
 #[cfg(test)]
 mod tests {
     use super::multiply_u8;
     use super::lerp_u8;
     use super::prelerp_u8;
+    use super::multiply_u16;
+    use super::lerp_u16;
 
     fn mu864(i: u8, j: u8) -> u8 {
         let i = i as f64 / 255.0;
@@ -103,4 +134,18 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn u16_test() {
+        let f = |x: u16| x as f64 / 65535.0;
+        let r = |x: f64| (x * 65535.0).round() as u16;
+        for p in (0 ..= 65535).step_by(257) {
+            for q in (0 ..= 65535).step_by(771) {
+                for a in (0 ..= 65535).step_by(1285) {
+                    let (p,q,a) = (p as u16, q as u16, a as u16);
+                    assert_eq!(multiply_u16(p,a), r(f(p) * f(a)));
+                    assert_eq!(lerp_u16(p,q,a), r(f(p) + (f(q) - f(p)) * f(a)));
+                }
+            }
+        }
+    }
 }
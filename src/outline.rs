@@ -73,7 +73,9 @@ impl<'a,T> RasterizerOutline<'a,T> where T: Pixel {
         for v in path.xconvert().iter() {
             match v.cmd {
                 PathCommand::MoveTo => self.move_to_d(v.x, v.y),
-                PathCommand::LineTo => self.line_to_d(v.x, v.y),
+                PathCommand::LineTo |
+                PathCommand::Curve3 |
+                PathCommand::Curve4 => self.line_to_d(v.x, v.y),
                 PathCommand::Close => self.close(),
                 PathCommand::Stop => unimplemented!("stop encountered"),
             }
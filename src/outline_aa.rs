@@ -87,7 +87,9 @@ impl<'a,T> RasterizerOutlineAA<'a, T> where T: DrawOutline {
         for v in path.xconvert().iter() {
             match v.cmd {
                 PathCommand::MoveTo => self.move_to_d(v.x, v.y),
-                PathCommand::LineTo => self.line_to_d(v.x, v.y),
+                PathCommand::LineTo |
+                PathCommand::Curve3 |
+                PathCommand::Curve4 => self.line_to_d(v.x, v.y),
                 PathCommand::Close => self.close_path(),
                 PathCommand::Stop => unimplemented!("stop encountered"),
             }
@@ -1,6 +1,7 @@
 
 use crate::clip::Rectangle;
 use crate::VertexSource;
+use crate::curves::BezierArcSvg;
 
 #[derive(Debug,Copy,Clone,PartialEq)]
 pub enum PathCommand {
@@ -8,13 +9,19 @@ pub enum PathCommand {
     MoveTo,
     LineTo,
     Close,
-    //Curve3,
-    //Curve4,
+    Curve3,
+    Curve4,
     //CurveN,
     //Catrom,
     //UBSpline,
     //EndPoly,
 }
+impl PathCommand {
+    /// Return if the command is a curve control or end point
+    pub fn is_curve(self) -> bool {
+        self == PathCommand::Curve3 || self == PathCommand::Curve4
+    }
+}
 impl Default for PathCommand {
     fn default() -> PathCommand {
         PathCommand::MoveTo
@@ -44,6 +51,12 @@ impl<T> Vertex<T> {
     pub fn close_polygon(x: T, y: T) -> Self {
         Self { x, y, cmd: PathCommand::Close }
     }
+    pub fn curve3(x: T, y: T) -> Self {
+        Self { x, y, cmd: PathCommand::Curve3 }
+    }
+    pub fn curve4(x: T, y: T) -> Self {
+        Self { x, y, cmd: PathCommand::Curve4 }
+    }
 }
 
 /// Compute length between two points
@@ -64,7 +77,7 @@ pub fn cross(p1: &Vertex<f64>, p2: &Vertex<f64>, p: &Vertex<f64>) -> f64 {
 }
 
 //  typedef path_base<vertex_block_storage<double> > path_storage;
-#[derive(Debug,Default)]
+#[derive(Debug,Default,Clone)]
 pub struct Path {
     pub vertices: Vec<Vertex<f64>>,
 }
@@ -90,13 +103,106 @@ impl Path {
         //self.vertices.push( Vertex::new(x,y, PathCommand::LineTo) );
         self.vertices.push( Vertex::line_to(x,y) );
     }
+    /// Quadratic Bezier curve from the current point to (`x`,`y`) with
+    ///   control point (`x_ctrl`,`y_ctrl`)
+    ///
+    /// Curves are stored as control points, use [`ConvCurve`] to flatten
+    ///
+    /// [`ConvCurve`]: ../curves/struct.ConvCurve.html
+    pub fn curve3(&mut self, x_ctrl: f64, y_ctrl: f64, x: f64, y: f64) {
+        self.vertices.push( Vertex::curve3(x_ctrl, y_ctrl) );
+        self.vertices.push( Vertex::curve3(x, y) );
+    }
+    /// Smooth Quadratic Bezier curve from the current point to (`x`,`y`)
+    ///
+    /// The control point is the reflection of the previous curve's control
+    ///   point about the current point, or the current point if the
+    ///   previous segment was not a curve
+    pub fn curve3_smooth(&mut self, x: f64, y: f64) {
+        if let Some((x_ctrl, y_ctrl)) = self.reflected_control_point() {
+            self.curve3(x_ctrl, y_ctrl, x, y);
+        }
+    }
+    /// Cubic Bezier curve from the current point to (`x`,`y`) with
+    ///   control points (`x_ctrl1`,`y_ctrl1`) and (`x_ctrl2`,`y_ctrl2`)
+    ///
+    /// Curves are stored as control points, use [`ConvCurve`] to flatten
+    ///
+    /// [`ConvCurve`]: ../curves/struct.ConvCurve.html
+    pub fn curve4(&mut self, x_ctrl1: f64, y_ctrl1: f64,
+                  x_ctrl2: f64, y_ctrl2: f64, x: f64, y: f64) {
+        self.vertices.push( Vertex::curve4(x_ctrl1, y_ctrl1) );
+        self.vertices.push( Vertex::curve4(x_ctrl2, y_ctrl2) );
+        self.vertices.push( Vertex::curve4(x, y) );
+    }
+    /// Smooth Cubic Bezier curve from the current point to (`x`,`y`) with
+    ///   second control point (`x_ctrl2`,`y_ctrl2`)
+    ///
+    /// The first control point is the reflection of the previous curve's
+    ///   control point about the current point, or the current point if the
+    ///   previous segment was not a curve
+    pub fn curve4_smooth(&mut self, x_ctrl2: f64, y_ctrl2: f64, x: f64, y: f64) {
+        if let Some((x_ctrl1, y_ctrl1)) = self.reflected_control_point() {
+            self.curve4(x_ctrl1, y_ctrl1, x_ctrl2, y_ctrl2, x, y);
+        }
+    }
+    /// Elliptical Arc from the current point to (`x`,`y`)
+    ///
+    /// Arc has radii `rx` and `ry`, x-axis rotation `angle` in radians and
+    ///   follows the SVG `large_arc` and `sweep` flags.  The arc is stored as
+    ///   cubic Bezier curves, see [`BezierArcSvg`]
+    ///
+    /// If there is no current point, this is a move_to.  If either radius
+    ///   is zero, this is a line_to
+    ///
+    /// [`BezierArcSvg`]: ../curves/struct.BezierArcSvg.html
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc_to(&mut self, rx: f64, ry: f64, angle: f64,
+                  large_arc: bool, sweep: bool, x: f64, y: f64) {
+        const EPSILON : f64 = 1e-30;
+        let last = match self.vertices.last() {
+            Some(last) if last.cmd != PathCommand::Close &&
+                last.cmd != PathCommand::Stop => *last,
+            _ => return self.move_to(x, y),
+        };
+        if rx.abs() < EPSILON || ry.abs() < EPSILON {
+            return self.line_to(x, y);
+        }
+        // Coincident end points omit the arc
+        if (x - last.x).hypot(y - last.y) < EPSILON {
+            return;
+        }
+        let arc = BezierArcSvg::new(last.x, last.y, rx, ry, angle,
+                                    large_arc, sweep, x, y);
+        if arc.radii_ok() {
+            self.vertices.extend( arc.xconvert().into_iter().skip(1) );
+        } else {
+            self.line_to(x, y);
+        }
+    }
+    /// Control point for a smooth curve
+    ///
+    /// Returns `None` if there is no current point
+    fn reflected_control_point(&self) -> Option<(f64, f64)> {
+        let n = self.vertices.len();
+        let last = self.vertices.last()?;
+        if last.cmd == PathCommand::Close || last.cmd == PathCommand::Stop {
+            return None;
+        }
+        if n >= 2 && last.cmd.is_curve() && self.vertices[n-2].cmd.is_curve() {
+            let prev = self.vertices[n-2];
+            Some((last.x + last.x - prev.x, last.y + last.y - prev.y))
+        } else {
+            Some((last.x, last.y))
+        }
+    }
     pub fn close_polygon(&mut self) {
         if self.vertices.is_empty() {
             return;
         }
         let n = self.vertices.len();
         let last = self.vertices[n-1];
-        if last.cmd == PathCommand::LineTo {
+        if last.cmd == PathCommand::LineTo || last.cmd.is_curve() {
             self.vertices.push( Vertex::close_polygon(last.x, last.y) );
         }
     }
@@ -123,6 +229,8 @@ pub fn split(path: &[Vertex<f64>]) -> Vec<(usize, usize)> {
                         start = Some(i);
                     },
                     PathCommand::LineTo |
+                    PathCommand::Curve3 |
+                    PathCommand::Curve4 |
                     PathCommand::Close  |
                     PathCommand::Stop => { },
                 }
@@ -130,7 +238,9 @@ pub fn split(path: &[Vertex<f64>]) -> Vec<(usize, usize)> {
             (Some(_),None) => {
                 match v.cmd {
                     PathCommand::MoveTo => { start = Some(i); },
-                    PathCommand::LineTo => { end = Some(i); },
+                    PathCommand::LineTo |
+                    PathCommand::Curve3 |
+                    PathCommand::Curve4 => { end = Some(i); },
                     PathCommand::Close |
                     PathCommand::Stop => { end = Some(i) },
                 }
@@ -143,6 +253,8 @@ pub fn split(path: &[Vertex<f64>]) -> Vec<(usize, usize)> {
                         end = None;
                     },
                     PathCommand::LineTo  |
+                    PathCommand::Curve3  |
+                    PathCommand::Curve4  |
                     PathCommand::Close   |
                     PathCommand::Stop => { end = Some(i) },
                 }
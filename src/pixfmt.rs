@@ -7,6 +7,7 @@ use crate::math::*;
 use crate::Color;
 use crate::Source;
 use crate::Pixel;
+use crate::PixelData;
 
 use std::marker::PhantomData;
 
@@ -15,11 +16,51 @@ use std::path::Path;
 /// Pixel Format Wrapper around raw pixel component data
 ///
 #[derive(Debug)]
-pub struct Pixfmt<T> {
-    rbuf: RenderingBuffer,
+pub struct Pixfmt<T, B = Vec<u8>> {
+    rbuf: RenderingBuffer<B>,
     phantom: PhantomData<T>,
 }
 
+/// Iterator over pixels in their stored color type
+///
+/// Created by [`PixelData::pixels`] and [`PixelData::row_pixels`]
+///
+/// [`PixelData::pixels`]: ../trait.PixelData.html#method.pixels
+/// [`PixelData::row_pixels`]: ../trait.PixelData.html#method.row_pixels
+#[derive(Debug)]
+pub struct Pixels<'a, T> {
+    pixf: &'a T,
+    /// Index of the next pixel, row-major
+    i: usize,
+    /// Index after the last pixel
+    end: usize,
+}
+
+impl<'a, T> Pixels<'a, T> where T: PixelData {
+    pub(crate) fn new(pixf: &'a T, i: usize, end: usize) -> Self {
+        Self { pixf, i, end }
+    }
+}
+
+impl<T> Iterator for Pixels<'_, T> where T: PixelData {
+    type Item = T::Color;
+    fn next(&mut self) -> Option<T::Color> {
+        if self.i >= self.end {
+            return None;
+        }
+        let w = self.pixf.width();
+        let c = self.pixf.pixel(self.i % w, self.i / w);
+        self.i += 1;
+        Some(c)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.end - self.i;
+        (n, Some(n))
+    }
+}
+
+impl<T> ExactSizeIterator for Pixels<'_, T> where T: PixelData { }
+
 impl<T> Pixfmt<T> where Pixfmt<T>: Pixel {
     /// Create new Pixel Format of width * height * bpp
     ///
@@ -32,6 +73,50 @@ impl<T> Pixfmt<T> where Pixfmt<T>: Pixel {
                phantom: PhantomData
         }
     }
+    pub fn from_file<P: AsRef<Path>>(filename: P) -> Result<Self,image::ImageError> {
+        let (buf,w,h) = crate::ppm::read_file(filename)?;
+        Ok(Self{ rbuf: RenderingBuffer::from_buf(buf, w, h, 3), phantom: PhantomData })
+    }
+}
+
+impl<T,B> Pixfmt<T,B> where Pixfmt<T,B>: Pixel, B: AsRef<[u8]> + AsMut<[u8]> {
+    /// Attach a Pixel Format to existing data `buf`
+    ///
+    /// The image is `width` x `height` pixels, with `stride` bytes from the
+    ///   start of one row to the next.  A negative `stride` places row 0 at
+    ///   the end of `buf`, rows are then stored bottom-up
+    ///
+    ///     use agg::{Pixfmt,Pixel,Source,Rgb8,Rgba8};
+    ///
+    ///     // 2x2 image with 2 bytes of padding at the end of each row
+    ///     let mut buf = vec![0u8; 16];
+    ///     {
+    ///         let mut pix = Pixfmt::<Rgb8,_>::attach(&mut buf[..], 2, 2, 8);
+    ///         pix.copy_pixel(1, 1, Rgba8::new(1, 2, 3, 255));
+    ///         assert_eq!(pix.get((1,1)), Rgba8::new(1, 2, 3, 255));
+    ///     }
+    ///     assert_eq!(&buf[8..], &[0,0,0, 1,2,3, 0,0]);
+    ///
+    /// # Panics
+    ///
+    /// If `width` or `height` is 0, the stride is too small for a row or
+    ///   `buf` is too short
+    pub fn attach(buf: B, width: usize, height: usize, stride: isize) -> Self {
+        if width == 0 || height == 0 {
+            panic!("Cannot create pixfmt with 0 width or height");
+        }
+        Self { rbuf: RenderingBuffer::attach(buf, width, height, Self::bpp(), stride),
+               phantom: PhantomData
+        }
+    }
+    /// Underlying Rendering Buffer, with access to rows of pixels
+    pub fn rendering_buffer(&self) -> &RenderingBuffer<B> {
+        &self.rbuf
+    }
+    /// Underlying mutable Rendering Buffer, with access to rows of pixels
+    pub fn rendering_buffer_mut(&mut self) -> &mut RenderingBuffer<B> {
+        &mut self.rbuf
+    }
     // /// Fill with a color
     // pub fn fill<C: Color>(&mut self, color: C) {
     //     let (w,h) = (self.width(), self.height());
@@ -150,45 +235,57 @@ impl<T> Pixfmt<T> where Pixfmt<T>: Pixel {
             self.set((x,y+i), c);
         }
     }
+}
 
-    pub fn from_file<P: AsRef<Path>>(filename: P) -> Result<Self,image::ImageError> {
-        let (buf,w,h) = crate::ppm::read_file(filename)?;
-        Ok(Self{ rbuf: RenderingBuffer::from_buf(buf, w, h, 3), phantom: PhantomData })
+impl<B> Source for Pixfmt<Rgba8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    fn get(&self, id: (usize, usize)) -> Rgba8 {
+        self.raw(id)
     }
 }
-
-impl Source for Pixfmt<Rgba8> {
+impl<B> Source for Pixfmt<Rgba8pre,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     fn get(&self, id: (usize, usize)) -> Rgba8 {
         let p = &self.rbuf[id];
         Rgba8::new(p[0],p[1],p[2],p[3])
     }
 }
-impl Source for Pixfmt<Rgba8pre> {
+impl<B> Source for Pixfmt<Srgba8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     fn get(&self, id: (usize, usize)) -> Rgba8 {
-        let p = &self.rbuf[id];
-        Rgba8::new(p[0],p[1],p[2],p[3])
+        Rgba8::from_trait(self.raw(id))
+    }
+}
+impl<B> Source for Pixfmt<Rgb16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    fn get(&self, id: (usize, usize)) -> Rgba8 {
+        Rgba8::from_trait(self.raw(id))
+    }
+}
+impl<B> Source for Pixfmt<Rgba16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    fn get(&self, id: (usize, usize)) -> Rgba8 {
+        Rgba8::from_trait(self.raw(id))
+    }
+}
+impl<B> Source for Pixfmt<Gray16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    fn get(&self, id: (usize, usize)) -> Rgba8 {
+        Rgba8::from_trait(self.raw(id))
     }
 }
-impl Source for Pixfmt<Rgb8> {
+impl<B> Source for Pixfmt<Rgb8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     fn get(&self, id: (usize, usize)) -> Rgba8 {
         let p = &self.rbuf[id];
         Rgba8::new(p[0],p[1],p[2],255)
     }
 }
-impl Source for Pixfmt<Rgba32> {
+impl<B> Source for Pixfmt<Rgba32,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     fn get(&self, id: (usize, usize)) -> Rgba8 {
-        //let n = (id.0 + id.1 * self.rbuf.width) * Pixfmt::<Rgba32>::bpp();
+        Rgba8::from_trait(self.raw(id))
+    }
+}
+impl<B> Pixfmt<Rgba32,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    pub fn raw(&self, id: (usize, usize)) -> Rgba32 {
         let p = &self.rbuf[id];
-        let red   : f32 = unsafe { std::mem::transmute::<[u8;4],f32>([p[0],p[1],p[2],p[3]]) };
-        let green : f32 = unsafe { std::mem::transmute::<[u8;4],f32>([p[4],p[5],p[6],p[7]]) };
-        let blue  : f32 = unsafe { std::mem::transmute::<[u8;4],f32>([p[8],p[9],p[10],p[11]]) };
-        let alpha : f32 = unsafe { std::mem::transmute::<[u8;4],f32>([p[12],p[13],p[14],p[15]]) };
-
-        let c = Rgba32::new(red,green,blue,alpha);
-        Rgba8::from_trait(c)
+        let c = |i: usize| f32::from_ne_bytes([p[i],p[i+1],p[i+2],p[i+3]]);
+        Rgba32::new(c(0), c(4), c(8), c(12))
     }
 }
-
 macro_rules! impl_pixel { 
     () => {
         /// Height of rendering buffer in pixels
@@ -201,15 +298,49 @@ macro_rules! impl_pixel {
         }
         /// Return a underlying raw pixel/component data
         fn as_bytes(&self) -> &[u8] {
-            &self.rbuf.data
+            self.rbuf.as_bytes()
         }
         fn to_file<P: AsRef<std::path::Path>>(&self, filename: P) -> Result<(),std::io::Error> {
-            crate::ppm::write_file(self.as_bytes(), self.width(), self.height(), filename)
+            crate::ppm::write_file(&self.rbuf.to_packed(), self.width(), self.height(), filename)
         }
     }
 }
 
-impl Pixel for Pixfmt<Rgba8> {
+macro_rules! impl_pixel16 {
+    ($channels:expr) => {
+        /// Height of rendering buffer in pixels
+        fn height(&self) -> usize {
+            self.rbuf.height
+        }
+        /// Width of rendering buffer in pixels
+        fn width(&self) -> usize {
+            self.rbuf.width
+        }
+        /// Return a underlying raw pixel/component data
+        fn as_bytes(&self) -> &[u8] {
+            self.rbuf.as_bytes()
+        }
+        /// Write to a file with 16-bit components
+        fn to_file<P: AsRef<std::path::Path>>(&self, filename: P) -> Result<(),std::io::Error> {
+            crate::ppm::write_file16(&self.components16(), self.width(), self.height(), $channels, filename)
+        }
+        fn bpp() -> usize { 2 * $channels }
+        fn cover_mask() -> u64 { 255 }
+        fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
+            for i in 0 .. n {
+                self.set((id.0+i,id.1), c);
+            }
+        }
+        fn fill<C: Color>(&mut self, color: C) {
+            let (w,h) = (self.width(), self.height());
+            for i in 0 .. h {
+                self.setn((0,i), w, color);
+            }
+        }
+    }
+}
+
+impl<B> Pixel for Pixfmt<Rgba8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     impl_pixel!();
     fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
         let bpp = Self::bpp();
@@ -223,7 +354,7 @@ impl Pixel for Pixfmt<Rgba8> {
     fn cover_mask() -> u64 { 255 }
     fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
         let c = Rgba8::from_trait(c);
-        assert!(! self.rbuf.data.is_empty() );
+        assert!(! self.rbuf.is_empty() );
         self.rbuf[id][0] = c.red8();
         self.rbuf[id][1] = c.green8();
         self.rbuf[id][2] = c.blue8();
@@ -250,18 +381,20 @@ impl Pixel for Pixfmt<Rgba8> {
         let bpp = Self::bpp();
         let c = Rgba8::from_trait(color).into_slice();
         let c2 = [ c[0],c[1],c[2],c[3],  c[0],c[1],c[2],c[3],  c[0],c[1],c[2],c[3],  c[0],c[1],c[2],c[3] ];
-        let mut chunks = self.rbuf.data.chunks_exact_mut(bpp*n);
-        while let Some(chunk) = chunks.next() {
-            chunk.copy_from_slice(&c2);
-        }
-        for chunk in chunks.into_remainder().chunks_mut(bpp) {
-            chunk.copy_from_slice(&c);
+        for y in 0 .. self.rbuf.height {
+            let mut chunks = self.rbuf.row_mut(y).chunks_exact_mut(bpp*n);
+            for chunk in chunks.by_ref() {
+                chunk.copy_from_slice(&c2);
+            }
+            for chunk in chunks.into_remainder().chunks_mut(bpp) {
+                chunk.copy_from_slice(&c);
+            }
         }
     }
 
 }
 
-impl Pixel for Pixfmt<Rgb8> {
+impl<B> Pixel for Pixfmt<Rgb8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     impl_pixel!();
     fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
         let bpp = Self::bpp();
@@ -291,17 +424,19 @@ impl Pixel for Pixfmt<Rgb8> {
         let bpp = Self::bpp();
         let c = Rgb8::from_trait(color).into_slice();
         let c2 = [ c[0],c[1],c[2],  c[0],c[1],c[2], c[0],c[1],c[2], c[0],c[1],c[2] ];
-        let mut chunks = self.rbuf.data.chunks_exact_mut(bpp*n);
-        while let Some(chunk) = chunks.next() {
-            chunk.copy_from_slice(&c2);
-        }
-        for chunk in chunks.into_remainder().chunks_mut(bpp) {
-            chunk.copy_from_slice(&c);
+        for y in 0 .. self.rbuf.height {
+            let mut chunks = self.rbuf.row_mut(y).chunks_exact_mut(bpp*n);
+            for chunk in chunks.by_ref() {
+                chunk.copy_from_slice(&c2);
+            }
+            for chunk in chunks.into_remainder().chunks_mut(bpp) {
+                chunk.copy_from_slice(&c);
+            }
         }
     }
 
 }
-impl Pixfmt<Gray8> {
+impl<B> Pixfmt<Gray8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     fn mix_pix(&mut self, id: (usize,usize), c: Gray8, alpha: u8) -> Gray8 {
         let p = Gray8::from_slice( &self.rbuf[id] );
         Gray8::new_with_alpha(lerp_u8(p.value, c.value, alpha), alpha)
@@ -311,7 +446,11 @@ impl Pixfmt<Gray8> {
     }
 }
 
-impl Pixfmt<Rgba8> {
+impl<B> Pixfmt<Rgba8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    pub fn raw(&self, id: (usize, usize)) -> Rgba8 {
+        let p = &self.rbuf[id];
+        Rgba8::new(p[0],p[1],p[2],p[3])
+    }
     /// Computer **over** operator
     ///
     /// # Arguments
@@ -337,7 +476,7 @@ impl Pixfmt<Rgba8> {
         self.set(id, pix);
     }
 }
-impl Pixel for Pixfmt<Rgba8pre> {
+impl<B> Pixel for Pixfmt<Rgba8pre,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     impl_pixel!();
     fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
         let bpp = Self::bpp();
@@ -368,17 +507,19 @@ impl Pixel for Pixfmt<Rgba8pre> {
         let bpp = Self::bpp();
         let c = Rgba8pre::from_trait(color).into_slice();
         let c2 = [ c[0],c[1],c[2],c[3],  c[0],c[1],c[2],c[3],  c[0],c[1],c[2],c[3],  c[0],c[1],c[2],c[3] ];
-        let mut chunks = self.rbuf.data.chunks_exact_mut(bpp*n);
-        while let Some(chunk) = chunks.next() {
-            chunk.copy_from_slice(&c2);
-        }
-        for chunk in chunks.into_remainder().chunks_mut(bpp) {
-            chunk.copy_from_slice(&c);
+        for y in 0 .. self.rbuf.height {
+            let mut chunks = self.rbuf.row_mut(y).chunks_exact_mut(bpp*n);
+            for chunk in chunks.by_ref() {
+                chunk.copy_from_slice(&c2);
+            }
+            for chunk in chunks.into_remainder().chunks_mut(bpp) {
+                chunk.copy_from_slice(&c);
+            }
         }
     }
 }
 
-impl Pixfmt<Rgb8> {
+impl<B> Pixfmt<Rgb8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     pub fn raw(&self, id: (usize, usize)) -> Rgb8 {
          let p = &self.rbuf[id];
         Rgb8::new(p[0],p[1],p[2])
@@ -402,7 +543,11 @@ impl Pixfmt<Rgb8> {
         Rgb8::new(red, green, blue)
     }
 }
-impl Pixfmt<Rgba8pre> {
+impl<B> Pixfmt<Rgba8pre,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    pub fn raw(&self, id: (usize, usize)) -> Rgba8pre {
+        let p = &self.rbuf[id];
+        Rgba8pre::new(p[0],p[1],p[2],p[3])
+    }
     /// Compute **over** operator
     ///
     /// # Arguments
@@ -427,7 +572,14 @@ impl Pixfmt<Rgba8pre> {
         Rgba8pre::new(red, green, blue, alpha)
     }
     pub fn drop_alpha(&self) -> Pixfmt<Rgb8> {
-        let buf : Vec<_> = self.as_bytes().iter()
+        self.rgb_from_rgba()
+    }
+}
+
+impl<T,B> Pixfmt<T,B> where Pixfmt<T,B>: Pixel, B: AsRef<[u8]> + AsMut<[u8]> {
+    /// Copy the first three of four components into a new Rgb8 Pixel Format
+    fn rgb_from_rgba(&self) -> Pixfmt<Rgb8> {
+        let buf : Vec<_> = self.rbuf.to_packed().iter()
             .enumerate()
             .filter(|(i,_)| i%4 < 3)
             .map(|(_,x)| *x)
@@ -442,7 +594,47 @@ impl Pixfmt<Rgba8pre> {
     }
 }
 
-impl Pixel for Pixfmt<Rgba32> {
+impl<B> Pixfmt<Srgba8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    /// Raw sRGB encoded pixel at `id`
+    pub fn raw(&self, id: (usize, usize)) -> Srgba8 {
+        Srgba8::from_slice(&self.rbuf[id])
+    }
+    /// Copy the color components, without alpha, to a new Rgb8 Pixel Format
+    ///
+    /// Components remain sRGB encoded
+    pub fn drop_alpha(&self) -> Pixfmt<Rgb8> {
+        self.rgb_from_rgba()
+    }
+    /// Compute **over** operator in linear light
+    ///
+    /// # Arguments
+    ///   - p     - Current pixel, linear
+    ///   - c     - Overlaying pixel, linear, not premultiplied
+    ///   - alpha - Alpha channel, including coverage
+    ///
+    /// # Output
+    ///   - lerp(p, c, alpha)
+    ///
+    fn mix_pix(p: Rgba16, c: Rgba16, alpha: u16) -> Rgba16 {
+        let red   = lerp_u16(p.r, c.r, alpha);
+        let green = lerp_u16(p.g, c.g, alpha);
+        let blue  = lerp_u16(p.b, c.b, alpha);
+        let alpha = prelerp_u16(p.a, alpha, alpha);
+        Rgba16::new(red, green, blue, alpha)
+    }
+    /// Blend linear color `c` with the pixel at `id`
+    fn blend_pix_linear(&mut self, id: (usize, usize), c: Rgba16, cover: u64) {
+        let alpha = multiply_u16(c.a, cover as u16 * 257);
+        if alpha == 0 {
+            return;
+        }
+        let p = Rgba16::from_srgba8(self.raw(id));
+        let p = Self::mix_pix(p, c, alpha);
+        self.rbuf[id][..4].copy_from_slice(&Srgba8::from_rgba16(p).into_slice());
+    }
+}
+
+impl<B> Pixel for Pixfmt<Rgba32,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     impl_pixel!();
     fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
         for i in 0 .. n {
@@ -451,7 +643,7 @@ impl Pixel for Pixfmt<Rgba32> {
     }
     fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
         let c = Rgba32::from_trait(c);
-        assert!(self.rbuf.data.len() > 0);
+        assert!(! self.rbuf.is_empty() );
         let red   : [u8;4] = unsafe { std::mem::transmute(c.r) };
         let green : [u8;4] = unsafe { std::mem::transmute(c.g) };
         let blue  : [u8;4] = unsafe { std::mem::transmute(c.b) };
@@ -487,7 +679,320 @@ impl Pixel for Pixfmt<Rgba32> {
 
 }
 
-impl Pixel for Pixfmt<Gray8> {
+/// sRGB encoded pixels blended in linear light
+///
+/// Pixels are stored as sRGB.  Colors are blended by converting the
+///   stored pixel to a linear [`Rgba16`] through lookup tables, blending with
+///   the linear color and encoding the result back to sRGB
+///
+///     use agg::{Pixfmt,Pixel,Srgba8,Rgba8};
+///
+///     let mut pix = Pixfmt::<Srgba8>::new(1,1);
+///     pix.fill(Rgba8::white());
+///     pix.blend_pix((0,0), Rgba8::black(), 128);
+///     assert_eq!(pix.raw((0,0)), Srgba8::new(187,187,187,255));
+///
+/// [`Rgba16`]: ../color/struct.Rgba16.html
+impl<B> Pixel for Pixfmt<Srgba8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    impl_pixel!();
+    fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
+        let bpp = Self::bpp();
+        let c = Srgba8::from_rgba16(Rgba16::from_trait(c)).into_slice();
+        let p = &mut self.rbuf[id][..n*bpp];
+        for chunk in p.chunks_mut(bpp) {
+            chunk.copy_from_slice(&c);
+        }
+    }
+    fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
+        let c = Srgba8::from_rgba16(Rgba16::from_trait(c)).into_slice();
+        self.rbuf[id][..4].copy_from_slice(&c);
+    }
+    fn bpp() -> usize { 4 }
+    fn cover_mask() -> u64 { 255 }
+    fn blend_pix<C: Color>(&mut self, id: (usize, usize), c: C, cover: u64) {
+        self.blend_pix_linear(id, Rgba16::from_trait(c), cover);
+    }
+    fn blend_hline<C: Color>(&mut self, x: i64, y: i64, len: i64, color: C, cover: u64) {
+        if color.is_transparent() {
+            return;
+        }
+        let (x,y,len) = (x as usize, y as usize, len as usize);
+        if color.is_opaque() && cover == Self::cover_mask() {
+            self.setn((x,y), len, color);
+        } else {
+            let c = Rgba16::from_trait(color);
+            for i in 0 .. len {
+                self.blend_pix_linear((x+i,y), c, cover);
+            }
+        }
+    }
+    fn blend_solid_hspan<C: Color>(&mut self, x: i64, y: i64, len: i64, color: C, covers: &[u64]) {
+        assert_eq!(len as usize, covers.len());
+        if color.is_transparent() {
+            return;
+        }
+        let c = Rgba16::from_trait(color);
+        for (i, &cover) in covers.iter().enumerate() {
+            if color.is_opaque() && cover == Self::cover_mask() {
+                self.setn((x as usize + i, y as usize), 1, color);
+            } else {
+                self.blend_pix_linear((x as usize + i, y as usize), c, cover);
+            }
+        }
+    }
+    fn fill<C: Color>(&mut self, color: C) {
+        let (w,h) = (self.width(), self.height());
+        for i in 0 .. h {
+            self.setn((0,i), w, color);
+        }
+    }
+}
+
+impl<T,B> Pixfmt<T,B> where Pixfmt<T,B>: Pixel, B: AsRef<[u8]> + AsMut<[u8]> {
+    /// Component `i` of a 16-bit pixel at `id`
+    fn get16(&self, id: (usize, usize), i: usize) -> u16 {
+        let p = &self.rbuf[id];
+        u16::from_ne_bytes([p[2*i], p[2*i+1]])
+    }
+    /// Set the components of a 16-bit pixel at `id`
+    fn set16(&mut self, id: (usize, usize), v: &[u16]) {
+        let p = &mut self.rbuf[id];
+        for (i, x) in v.iter().enumerate() {
+            p[2*i..2*i+2].copy_from_slice(&x.to_ne_bytes());
+        }
+    }
+    /// All components of a 16-bit Pixel Format
+    fn components16(&self) -> Vec<u16> {
+        self.rbuf.to_packed().chunks_exact(2)
+            .map(|v| u16::from_ne_bytes([v[0], v[1]]))
+            .collect()
+    }
+}
+
+impl<B> Pixfmt<Rgb16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    pub fn raw(&self, id: (usize, usize)) -> Rgb16 {
+        Rgb16::new(self.get16(id, 0), self.get16(id, 1), self.get16(id, 2))
+    }
+}
+impl<B> Pixfmt<Rgba16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    pub fn raw(&self, id: (usize, usize)) -> Rgba16 {
+        Rgba16::new(self.get16(id, 0), self.get16(id, 1),
+                    self.get16(id, 2), self.get16(id, 3))
+    }
+}
+impl<B> Pixfmt<Gray16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    pub fn raw(&self, id: (usize, usize)) -> Gray16 {
+        Gray16::new_with_alpha(self.get16(id, 0), self.get16(id, 1))
+    }
+}
+
+impl<B> Pixel for Pixfmt<Rgb16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    impl_pixel16!(3);
+    fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
+        self.set16(id, &Rgb16::from_trait(c).into_slice());
+    }
+    fn blend_pix<C: Color>(&mut self, id: (usize, usize), c: C, cover: u64) {
+        let c = Rgba16::from_trait(c);
+        let alpha = multiply_u16(c.a, cover as u16 * 257);
+        let p = self.raw(id);
+        let p = Rgb16::new(lerp_u16(p.r, c.r, alpha),
+                           lerp_u16(p.g, c.g, alpha),
+                           lerp_u16(p.b, c.b, alpha));
+        self.set16(id, &p.into_slice());
+    }
+}
+
+impl<B> Pixel for Pixfmt<Rgba16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    impl_pixel16!(4);
+    fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
+        self.set16(id, &Rgba16::from_trait(c).into_slice());
+    }
+    fn blend_pix<C: Color>(&mut self, id: (usize, usize), c: C, cover: u64) {
+        let c = Rgba16::from_trait(c);
+        let alpha = multiply_u16(c.a, cover as u16 * 257);
+        let p = self.raw(id);
+        let p = Rgba16::new(lerp_u16(p.r, c.r, alpha),
+                            lerp_u16(p.g, c.g, alpha),
+                            lerp_u16(p.b, c.b, alpha),
+                            prelerp_u16(p.a, alpha, alpha));
+        self.set16(id, &p.into_slice());
+    }
+}
+
+impl<B> Pixel for Pixfmt<Gray16,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+    impl_pixel16!(2);
+    fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
+        self.set16(id, &Gray16::from_trait(c).into_slice());
+    }
+    fn blend_pix<C: Color>(&mut self, id: (usize, usize), c: C, cover: u64) {
+        let c = Gray16::from_trait(c);
+        let alpha = multiply_u16(c.alpha, cover as u16 * 257);
+        let p = self.raw(id);
+        let p = Gray16::new_with_alpha(lerp_u16(p.value, c.value, alpha),
+                                       prelerp_u16(p.alpha, alpha, alpha));
+        self.set16(id, &p.into_slice());
+    }
+}
+
+/// Pixel Format with the components of `$color` at byte offsets `[$i,..]`
+///   for red, green, blue and optionally alpha
+macro_rules! impl_pixel_order {
+    ($color:ty, [$r:expr, $g:expr, $b:expr $(, $a:expr)?], $bpp:expr) => {
+        impl<B> Source for Pixfmt<$color,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+            fn get(&self, id: (usize, usize)) -> Rgba8 {
+                let p = &self.rbuf[id];
+                #[allow(unused_mut)]
+                let mut c = Rgba8::new(p[$r], p[$g], p[$b], 255);
+                $( c.a = p[$a]; )?
+                c
+            }
+        }
+        impl<B> Pixfmt<$color,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+            pub fn raw(&self, id: (usize, usize)) -> $color {
+                <$color>::from_trait(self.get(id))
+            }
+            /// Components of `c` in memory order
+            fn pixel_bytes<C: Color>(c: C) -> [u8; $bpp] {
+                let mut p = [0u8; $bpp];
+                p[$r] = c.red8();
+                p[$g] = c.green8();
+                p[$b] = c.blue8();
+                $( p[$a] = c.alpha8(); )?
+                p
+            }
+        }
+        impl<B> Pixel for Pixfmt<$color,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+            /// Height of rendering buffer in pixels
+            fn height(&self) -> usize {
+                self.rbuf.height
+            }
+            /// Width of rendering buffer in pixels
+            fn width(&self) -> usize {
+                self.rbuf.width
+            }
+            /// Return a underlying raw pixel/component data
+            fn as_bytes(&self) -> &[u8] {
+                self.rbuf.as_bytes()
+            }
+            /// Write the red, green and blue components to a file
+            fn to_file<P: AsRef<std::path::Path>>(&self, filename: P) -> Result<(),std::io::Error> {
+                let buf : Vec<u8> = self.rbuf.to_packed().chunks_exact($bpp)
+                    .flat_map(|p| vec![p[$r], p[$g], p[$b]])
+                    .collect();
+                crate::ppm::write_file(&buf, self.width(), self.height(), filename)
+            }
+            fn bpp() -> usize { $bpp }
+            fn cover_mask() -> u64 { 255 }
+            fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
+                self.rbuf[id][..$bpp].copy_from_slice(&Self::pixel_bytes(c));
+            }
+            fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
+                let c = Self::pixel_bytes(c);
+                let p = &mut self.rbuf[id][..n*$bpp];
+                for chunk in p.chunks_mut($bpp) {
+                    chunk.copy_from_slice(&c);
+                }
+            }
+            fn blend_pix<C: Color>(&mut self, id: (usize, usize), c: C, cover: u64) {
+                let alpha = multiply_u8(c.alpha8(), cover as u8);
+                let p = &mut self.rbuf[id];
+                p[$r] = lerp_u8(p[$r], c.red8(),   alpha);
+                p[$g] = lerp_u8(p[$g], c.green8(), alpha);
+                p[$b] = lerp_u8(p[$b], c.blue8(),  alpha);
+                $( p[$a] = prelerp_u8(p[$a], alpha, alpha); )?
+            }
+            fn fill<C: Color>(&mut self, color: C) {
+                let c = Self::pixel_bytes(color);
+                for y in 0 .. self.rbuf.height {
+                    for chunk in self.rbuf.row_mut(y).chunks_exact_mut($bpp) {
+                        chunk.copy_from_slice(&c);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl_pixel_order!(Bgr8,  [2, 1, 0],    3);
+impl_pixel_order!(Bgra8, [2, 1, 0, 3], 4);
+impl_pixel_order!(Argb8, [1, 2, 3, 0], 4);
+impl_pixel_order!(Abgr8, [3, 2, 1, 0], 4);
+
+/// Pixel Format of colors packed into 16 bits
+macro_rules! impl_pixel_packed {
+    ($color:ident) => {
+        impl<B> Pixfmt<$color,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+            /// Raw packed pixel at `id`
+            pub fn raw(&self, id: (usize, usize)) -> $color {
+                let p = &self.rbuf[id];
+                $color { value: u16::from_ne_bytes([p[0], p[1]]) }
+            }
+        }
+        impl<B> Source for Pixfmt<$color,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+            fn get(&self, id: (usize, usize)) -> Rgba8 {
+                Rgba8::from_trait(self.raw(id))
+            }
+        }
+        impl<B> Pixel for Pixfmt<$color,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+            /// Height of rendering buffer in pixels
+            fn height(&self) -> usize {
+                self.rbuf.height
+            }
+            /// Width of rendering buffer in pixels
+            fn width(&self) -> usize {
+                self.rbuf.width
+            }
+            /// Return a underlying raw pixel/component data
+            fn as_bytes(&self) -> &[u8] {
+                self.rbuf.as_bytes()
+            }
+            /// Write the expanded red, green and blue components to a file
+            fn to_file<P: AsRef<std::path::Path>>(&self, filename: P) -> Result<(),std::io::Error> {
+                let buf : Vec<u8> = self.rbuf.to_packed().chunks_exact(2)
+                    .map(|p| $color { value: u16::from_ne_bytes([p[0], p[1]]) })
+                    .flat_map(|c| vec![c.red8(), c.green8(), c.blue8()])
+                    .collect();
+                crate::ppm::write_file(&buf, self.width(), self.height(), filename)
+            }
+            fn bpp() -> usize { 2 }
+            fn cover_mask() -> u64 { 255 }
+            fn set<C: Color>(&mut self, id: (usize, usize), c: C) {
+                let c = $color::from_trait(c).value.to_ne_bytes();
+                self.rbuf[id][..2].copy_from_slice(&c);
+            }
+            fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, c: C) {
+                let c = $color::from_trait(c).value.to_ne_bytes();
+                let p = &mut self.rbuf[id][..n*2];
+                for chunk in p.chunks_mut(2) {
+                    chunk.copy_from_slice(&c);
+                }
+            }
+            /// Blend by unpacking to 8-bit components, interpolating and
+            ///   packing the result
+            fn blend_pix<C: Color>(&mut self, id: (usize, usize), c: C, cover: u64) {
+                let alpha = multiply_u8(c.alpha8(), cover as u8);
+                let p = self.raw(id);
+                let p = $color::new(lerp_u8(p.red8(),   c.red8(),   alpha),
+                                    lerp_u8(p.green8(), c.green8(), alpha),
+                                    lerp_u8(p.blue8(),  c.blue8(),  alpha));
+                self.rbuf[id][..2].copy_from_slice(&p.value.to_ne_bytes());
+            }
+            fn fill<C: Color>(&mut self, color: C) {
+                let c = $color::from_trait(color).value.to_ne_bytes();
+                for y in 0 .. self.rbuf.height {
+                    for chunk in self.rbuf.row_mut(y).chunks_exact_mut(2) {
+                        chunk.copy_from_slice(&c);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl_pixel_packed!(Rgb565);
+impl_pixel_packed!(Rgb555);
+
+impl<B> Pixel for Pixfmt<Gray8,B> where B: AsRef<[u8]> + AsMut<[u8]> {
     impl_pixel!();
     fn setn<C: Color>(&mut self, id: (usize, usize), n: usize, color: C) {
         let bpp = Self::bpp();
@@ -514,16 +1019,46 @@ impl Pixel for Pixfmt<Gray8> {
         let bpp = Self::bpp();
         let c = Gray8::from_trait(color).into_slice();
         let c2 = [c[0],c[1], c[0],c[1], c[0],c[1], c[0],c[1]];
-        let mut chunks = self.rbuf.data.chunks_exact_mut(bpp*n);
-        while let Some(chunk) = chunks.next() {
-            chunk.copy_from_slice(&c2);
-        }
-        for chunk in chunks.into_remainder().chunks_mut(bpp) {
-            chunk.copy_from_slice(&c);
+        for y in 0 .. self.rbuf.height {
+            let mut chunks = self.rbuf.row_mut(y).chunks_exact_mut(bpp*n);
+            for chunk in chunks.by_ref() {
+                chunk.copy_from_slice(&c2);
+            }
+            for chunk in chunks.into_remainder().chunks_mut(bpp) {
+                chunk.copy_from_slice(&c);
+            }
         }
     }
 }
 
+macro_rules! impl_pixel_data {
+    (@impl $color:ty, $pre:expr) => {
+        impl<B> PixelData for Pixfmt<$color,B> where B: AsRef<[u8]> + AsMut<[u8]> {
+            type Color = $color;
+            const PREMULTIPLIED: bool = $pre;
+            fn pixel(&self, x: usize, y: usize) -> $color {
+                assert!(x < self.width() && y < self.height(),
+                        "pixel ({},{}) outside of {}x{} image", x, y, self.width(), self.height());
+                self.raw((x,y))
+            }
+            fn row(&self, y: usize) -> &[u8] {
+                self.rbuf.row(y)
+            }
+            fn row_mut(&mut self, y: usize) -> &mut [u8] {
+                self.rbuf.row_mut(y)
+            }
+        }
+    };
+    (premultiplied $color:ty) => { impl_pixel_data!(@impl $color, true); };
+    ($($color:ty),*) => { $( impl_pixel_data!(@impl $color, false); )* };
+}
+
+impl_pixel_data!(premultiplied Rgba8pre);
+impl_pixel_data!(Rgb8, Rgba8, Srgba8, Rgba32, Gray8,
+                 Rgb16, Rgba16, Gray16,
+                 Bgr8, Bgra8, Argb8, Abgr8,
+                 Rgb565, Rgb555);
+
 use crate::base::RenderingBase;
 
 pub struct PixfmtAlphaBlend<'a,T,C> where T: Pixel {
@@ -565,7 +1100,7 @@ impl Pixel for PixfmtAlphaBlend<'_,Pixfmt<Rgb8>,Gray8> {
         self.ren.pixf.as_bytes()
     }
     fn to_file<P: AsRef<std::path::Path>>(&self, filename: P) -> Result<(),std::io::Error> {
-        crate::ppm::write_file(self.as_bytes(), self.width(), self.height(), filename)
+        self.ren.pixf.to_file(filename)
     }
     fn fill<C: Color>(&mut self, color: C) {
         let (w,h) = (self.width(), self.height());
@@ -626,10 +1161,15 @@ mod tests {
     use crate::Rgba8pre;
     use crate::Srgba8;
     use crate::Rgba32;
+    use crate::Rgb16;
+    use crate::Rgba16;
+    use crate::Gray16;
+    use crate::{Bgr8,Bgra8,Argb8,Abgr8};
+    use crate::{Rgb565,Rgb555};
     #[test]
     fn pixfmt_test() {
         let mut p = Pixfmt::<Rgb8>::new(10,10);
-        assert_eq!(p.rbuf.data.len(),300);
+        assert_eq!(p.rbuf.len(),300);
 
         p.copy_pixel(0,0, Rgb8::black());
         assert_eq!(p.get((0,0)), Rgba8::black());
@@ -857,5 +1397,156 @@ mod tests {
         pix.copy_or_blend_pix_with_cover((0,0), Rgba8::new(0,0,0,beta), cover);
         assert_eq!(pix.get((0,0)), Rgba8::new(191,191,191,160));
     }
+    #[test]
+    fn pixfmt_16_test() {
+        let mut pix = Pixfmt::<Rgb16>::new(2,1);
+        pix.copy_pixel(0,0, Rgb16::new(1,2,65535));
+        assert_eq!(pix.raw((0,0)), Rgb16::new(1,2,65535));
+        assert_eq!(pix.get((0,0)), Rgba8::new(0,0,255,255));
+        // Blending keeps 16 bits of precision
+        pix.copy_pixel(1,0, Rgb16::black());
+        pix.blend_pix((1,0), Rgba16::new(65535,1000,3,65535), 1);
+        assert_eq!(pix.raw((1,0)), Rgb16::new(257,4,0));
+
+        let mut pix = Pixfmt::<Rgba16>::new(1,1);
+        pix.copy_pixel(0,0, Rgba16::new(0,0,0,0));
+        pix.copy_or_blend_pix_with_cover((0,0), Rgba8::new(255,255,255,128), 255);
+        assert_eq!(pix.raw((0,0)), Rgba16::new(32896,32896,32896,32896));
+
+        let mut pix = Pixfmt::<Gray16>::new(1,1);
+        pix.copy_pixel(0,0, Gray16::new(1000));
+        assert_eq!(pix.raw((0,0)), Gray16::new(1000));
+        pix.blend_pix((0,0), Gray16::new(2000), 128);
+        assert_eq!(pix.raw((0,0)), Gray16::new(1502));
+    }
+    #[test]
+    fn pixfmt_order_test() {
+        let c = Rgba8::new(10,20,30,40);
+        let mut pix = Pixfmt::<Bgr8>::new(1,1);
+        pix.copy_pixel(0,0,c);
+        assert_eq!(pix.as_bytes(), &[30,20,10]);
+        assert_eq!(pix.get((0,0)), Rgba8::new(10,20,30,255));
+
+        let mut pix = Pixfmt::<Bgra8>::new(1,1);
+        pix.copy_pixel(0,0,c);
+        assert_eq!(pix.as_bytes(), &[30,20,10,40]);
+        assert_eq!(pix.get((0,0)), c);
+
+        let mut pix = Pixfmt::<Argb8>::new(1,1);
+        pix.copy_pixel(0,0,c);
+        assert_eq!(pix.as_bytes(), &[40,10,20,30]);
+        assert_eq!(pix.get((0,0)), c);
+
+        let mut pix = Pixfmt::<Abgr8>::new(1,1);
+        pix.copy_pixel(0,0,c);
+        assert_eq!(pix.as_bytes(), &[40,30,20,10]);
+        assert_eq!(pix.get((0,0)), c);
+
+        // Blending matches Rgba8
+        let mut pix = Pixfmt::<Argb8>::new(1,1);
+        let mut rgba = Pixfmt::<Rgba8>::new(1,1);
+        pix.copy_pixel(0,0,Rgba8::new(255,255,255,128));
+        rgba.copy_pixel(0,0,Rgba8::new(255,255,255,128));
+        pix.copy_or_blend_pix_with_cover((0,0), Rgba8::new(0,0,0,128), 128);
+        rgba.copy_or_blend_pix_with_cover((0,0), Rgba8::new(0,0,0,128), 128);
+        assert_eq!(pix.get((0,0)), rgba.get((0,0)));
+    }
+    #[test]
+    fn pixfmt_packed_test() {
+        let mut pix = Pixfmt::<Rgb565>::new(2,1);
+        pix.copy_pixel(0,0,Rgba8::new(255,0,255,255));
+        assert_eq!(pix.raw((0,0)).value, 0xF81F);
+        assert_eq!(pix.get((0,0)), Rgba8::new(255,0,255,255));
+        pix.copy_pixel(1,0,Rgb8::black());
+        pix.blend_pix((1,0), Rgb8::white(), 128);
+        assert_eq!(pix.get((1,0)), Rgba8::new(132,130,132,255));
+
+        let mut pix = Pixfmt::<Rgb555>::new(1,1);
+        pix.fill(Rgb8::white());
+        assert_eq!(pix.raw((0,0)).value, 0xFFFF);
+        pix.blend_pix((0,0), Rgba8::new(0,0,0,255), 255);
+        assert_eq!(pix.raw((0,0)).value, 0x8000);
+    }
+    #[test]
+    fn pixfmt_attach_test() {
+        // 3x2 pixels within a 4x3 image of Rgb8, bottom-up
+        let mut data = [0u8; 4 * 3 * 3];
+        {
+            let mut pix = Pixfmt::<Rgb8,_>::attach(&mut data[..24], 3, 2, -12);
+            pix.fill(Rgb8::white());
+            pix.copy_hline(0,0,3,Rgb8::black());
+            pix.copy_pixel(2,1,Rgba8::new(1,2,3,255));
+            assert_eq!(pix.get((2,1)), Rgba8::new(1,2,3,255));
+            assert_eq!(pix.rendering_buffer().row(0), &[0u8; 9]);
+            assert_eq!(pix.rendering_buffer().to_packed().len(), 18);
+        }
+        assert_eq!(&data[ 0..12], &[255,255,255, 255,255,255, 1,2,3, 0,0,0]);
+        assert_eq!(&data[12..24], &[0,0,0, 0,0,0, 0,0,0, 0,0,0]);
+        assert_eq!(&data[24..], &[0u8; 12]);
+
+        // Padded and bottom-up rows are packed top row first
+        let mut data = [0u8; 2 * 12];
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+        let pix = Pixfmt::<Rgba8pre,_>::attach(&mut data[..], 2, 2, 12);
+        assert_eq!(pix.drop_alpha().as_bytes(), &[0,1,2, 4,5,6, 12,13,14, 16,17,18]);
+        let pix = Pixfmt::<Srgba8,_>::attach(&mut data[..20], 2, 2, -12);
+        assert_eq!(pix.drop_alpha().as_bytes(), &[12,13,14, 16,17,18, 0,1,2, 4,5,6]);
+    }
+    #[test]
+    fn pixel_data_test() {
+        use crate::PixelData;
+        use crate::{Gray8,Rgb565};
+        let mut pix = Pixfmt::<Rgb8>::new(3,2);
+        pix.fill(Rgb8::white());
+        pix.copy_pixel(1,1,Rgb8::new(1,2,3));
+        assert_eq!(pix.pixel(1,1), Rgb8::new(1,2,3));
+        assert_eq!(pix.row(1), &[255,255,255, 1,2,3, 255,255,255]);
+        pix.row_mut(0)[..3].copy_from_slice(&[4,5,6]);
+        assert_eq!(pix.pixel(0,0), Rgb8::new(4,5,6));
+        let row : Vec<_> = pix.row_pixels(1).collect();
+        assert_eq!(row, vec![Rgb8::white(), Rgb8::new(1,2,3), Rgb8::white()]);
+        assert_eq!(pix.pixels().len(), 6);
+        assert_eq!(pix.pixels().filter(|&c| c == Rgb8::white()).count(), 4);
+
+        let mut pix = Pixfmt::<Rgba8pre>::new(2,1);
+        pix.copy_pixel(1,0,Rgba8pre::new(128,0,0,128));
+        assert_eq!(pix.pixel(1,0), Rgba8pre::new(128,0,0,128));
+
+        let mut pix = Pixfmt::<Bgra8>::new(1,1);
+        pix.copy_pixel(0,0,Rgba8::new(10,20,30,40));
+        assert_eq!(pix.pixel(0,0), Bgra8::new(10,20,30,40));
+        assert_eq!(pix.row(0), &[30,20,10,40]);
+
+        let mut pix = Pixfmt::<Gray16>::new(1,1);
+        pix.fill(Rgba16::white());
+        assert_eq!(pix.pixel(0,0), Gray16::new(65535));
+
+        let mut pix = Pixfmt::<Rgba32>::new(1,1);
+        pix.copy_pixel(0,0,Rgba32::new(0.25,0.5,0.75,1.0));
+        assert_eq!(pix.pixel(0,0), Rgba32::new(0.25,0.5,0.75,1.0));
+
+        let mut pix = Pixfmt::<Rgb565>::new(1,1);
+        pix.fill(Rgb8::white());
+        assert_eq!(pix.pixels().collect::<Vec<_>>(), vec![Rgb565 { value: 0xFFFF }]);
+
+        // Rows of an attached, bottom-up buffer
+        let mut data = [0u8; 8];
+        let mut pix = Pixfmt::<Gray8,_>::attach(&mut data[..], 1, 2, -4);
+        pix.copy_pixel(0,0,Gray8::new(7));
+        assert_eq!(pix.pixel(0,0), Gray8::new(7));
+        assert_eq!(pix.row(0), &[7,255]);
+        assert_eq!(pix.row_pixels(1).next(), Some(Gray8::new_with_alpha(0,0)));
+    }
+    #[test]
+    fn blur_empty_test() {
+        // Blurring an image without pixels does nothing
+        let mut pix = Pixfmt::<Rgb8> {
+            rbuf: crate::buffer::RenderingBuffer::attach(vec![], 0, 10, 3, 0),
+            phantom: std::marker::PhantomData,
+        };
+        pix.stack_blur(2, 2);
+        pix.recursive_blur(2.0, 2.0);
+    }
 }
-
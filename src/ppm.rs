@@ -14,6 +14,27 @@ pub fn write_file<P: AsRef<Path>>(buf: &[u8], width: usize, height: usize, filen
     image::save_buffer(filename, buf, width as u32, height as u32, image::RGB(8))
 }
 
+/// Write 16-bit components to a file, e.g. a 16-bit PNG
+///
+/// `channels` is the number of components per pixel: 1 (Gray), 2 (Gray,
+///   Alpha), 3 (RGB) or 4 (RGBA), any other count is an `InvalidInput` error
+///
+///     let r = agg::ppm::write_file16(&[0; 5], 1, 1, 5, "tests/tmp/channels5.png");
+///     assert_eq!(r.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+///
+pub fn write_file16<P: AsRef<Path>>(buf: &[u16], width: usize, height: usize, channels: usize, filename: P) -> Result<(), std::io::Error> {
+    let color = match channels {
+        1 => image::Gray(16),
+        2 => image::GrayA(16),
+        3 => image::RGB(16),
+        4 => image::RGBA(16),
+        _ => return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput,
+                                            format!("unsupported number of channels: {}", channels))),
+    };
+    let bytes : Vec<u8> = buf.iter().flat_map(|v| v.to_be_bytes()).collect();
+    image::save_buffer(filename, &bytes, width as u32, height as u32, color)
+}
+
 pub fn img_diff<P: AsRef<Path>>(f1: P, f2: P) -> Result<bool,image::ImageError> {
     let (d1,w1,h1) = read_file(f1)?;
     let (d2,w2,h2) = read_file(f2)?;
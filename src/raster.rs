@@ -6,12 +6,15 @@ use crate::POLY_SUBPIXEL_SCALE;
 
 use crate::clip::Clip;
 use crate::scan::ScanlineU8;
+use crate::scan::ScanlineU8Am;
+use crate::cell::Cell;
 use crate::cell::RasterizerCell;
 use crate::paths::PathCommand;
 use crate::paths::Vertex;
 
 //use crate::Rasterize;
 use crate::VertexSource;
+use crate::AlphaMask;
 
 use std::cmp::min;
 use std::cmp::max;
@@ -42,6 +45,23 @@ impl Default for FillingRule {
     }
 }
 
+/// Order styles are drawn in by a [`RasterizerCompound`]
+///
+/// Where styles overlap, a later style with full coverage replaces earlier
+///   styles, and a translucent or partially covering one is drawn over them
+///
+/// [`RasterizerCompound`]: struct.RasterizerCompound.html
+#[derive(Debug,PartialEq,Copy,Clone,Default)]
+pub enum LayerOrder {
+    /// Order styles are encountered along a row
+    Unsorted,
+    /// Decreasing style index, lower styles are drawn last
+    #[default]
+    Direct,
+    /// Increasing style index, higher styles are drawn last
+    Inverse,
+}
+
 /// Path Status
 #[derive(Debug,PartialEq,Copy,Clone)]
 pub enum PathStatus {
@@ -95,7 +115,9 @@ impl RasterizerScanline {
         }
         for seg in path.xconvert() {
             match seg.cmd {
-                PathCommand::LineTo => self.line_to(seg.x, seg.y),
+                PathCommand::LineTo |
+                PathCommand::Curve3 |
+                PathCommand::Curve4 => self.line_to(seg.x, seg.y),
                 PathCommand::MoveTo => self.move_to(seg.x, seg.y),
                 PathCommand::Close  => self.close_polygon(),
                 PathCommand::Stop => unimplemented!("stop encountered"),
@@ -182,6 +204,16 @@ impl RasterizerScanline {
         self.scan_y += 1;
         true
     }
+    /// Sweep the Scanline, combining covers with an Alpha Mask
+    ///
+    /// Returns true if data exists in the input Scanline
+    pub fn sweep_scanline_am<M: AlphaMask>(&mut self, sl: &mut ScanlineU8Am<M>) -> bool {
+        if ! self.sweep_scanline(&mut sl.data.sl) {
+            return false;
+        }
+        sl.apply_mask();
+        true
+    }
     /// Return minimum x value from the RasterizerCell
     pub fn min_x(&self) -> i64 {
         self.outline.min_x
@@ -230,6 +262,18 @@ impl RasterizerScanline {
         new.gamma( gfunc );
         new
     }
+    /// Set the Filling Rule
+    ///
+    /// Defaults to `NonZero`, see [`FillingRule`]
+    ///
+    /// [`FillingRule`]: enum.FillingRule.html
+    pub fn set_filling_rule(&mut self, filling_rule: FillingRule) {
+        self.filling_rule = filling_rule;
+    }
+    /// Return the Filling Rule
+    pub fn filling_rule(&self) -> FillingRule {
+        self.filling_rule
+    }
     /// Set Clip Box
     pub fn clip_box(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
         self.clipper.clip_box(RasConvInt::upscale(x1),
@@ -266,26 +310,404 @@ impl RasterizerScanline {
     ///
     ///
     fn calculate_alpha(&self, area: i64) -> u64 {
-        let aa_shift  = 8;
-        let aa_scale  = 1 << aa_shift;
-        let aa_scale2 = aa_scale * 2;
-        let aa_mask   = aa_scale  - 1;
-        let aa_mask2  = aa_scale2 - 1;
+        calculate_alpha(area, self.filling_rule, &self.gamma)
+    }
+}
 
-        let mut cover = area >> (POLY_SUBPIXEL_SHIFT*2 + 1 - aa_shift);
-        cover = cover.abs();
-        if self.filling_rule == FillingRule::EvenOdd {
-            cover *= aa_mask2;
-            if cover > aa_scale {
-                cover = aa_scale2 - cover;
-            }
+
+
+/// Calculate alpha term based on area, the filling rule and gamma values
+fn calculate_alpha(area: i64, filling_rule: FillingRule, gamma: &[u64]) -> u64 {
+    let aa_shift  = 8;
+    let aa_scale  = 1 << aa_shift;
+    let aa_scale2 = aa_scale * 2;
+    let aa_mask   = aa_scale  - 1;
+    let aa_mask2  = aa_scale2 - 1;
+
+    let mut cover = area >> (POLY_SUBPIXEL_SHIFT*2 + 1 - aa_shift);
+    cover = cover.abs();
+    if filling_rule == FillingRule::EvenOdd {
+        cover &= aa_mask2;
+        if cover > aa_scale {
+            cover = aa_scale2 - cover;
         }
-        cover = max(0, min(cover, aa_mask));
-        self.gamma[cover as usize]
     }
+    cover = max(0, min(cover, aa_mask));
+    gamma[cover as usize]
 }
 
 
+/// Cells of a single style on the current row
+#[derive(Debug,Default,Copy,Clone)]
+struct StyleInfo {
+    /// Index of first cell
+    start_cell: usize,
+    /// Number of cells
+    num_cells: usize,
+    /// x position of last cell
+    last_x: i64,
+}
+
+/// Compound Rasterizer Anti-Alias using Scanline
+///
+/// Each edge carries a style on its left and right side, so shapes sharing
+///   edges are rasterized together and drawn in a single pass.  Anti-aliased
+///   shared edges do not show seams, as happens when adjacent shapes are
+///   drawn one at a time
+///
+/// Styles are set with [`styles`] before adding paths and are drawn with
+///   [`render_scanlines_compound`]
+///
+/// [`styles`]: #method.styles
+/// [`render_scanlines_compound`]: ../render/fn.render_scanlines_compound.html
+#[derive(Debug)]
+pub struct RasterizerCompound {
+    /// Clipping Region
+    clipper: Clip,
+    /// Collection of Rasterizing Cells
+    outline: RasterizerCell,
+    /// Status of Path
+    status: PathStatus,
+    /// Current x position
+    x0: i64,
+    /// Current y position
+    y0: i64,
+    /// Next y row to be worked on
+    scan_y: i64,
+    /// Filling Rule for Polygons
+    filling_rule: FillingRule,
+    /// Gamma Corection Values
+    gamma: Vec<u64>,
+    /// Order styles are drawn
+    layer_order: LayerOrder,
+    /// Minimum style index
+    min_style: i64,
+    /// Maximum style index
+    max_style: i64,
+    /// Cells of each style on the current row, index 0 is no style
+    styles: Vec<StyleInfo>,
+    /// Active styles on the current row, as indices into styles
+    active: Vec<usize>,
+    /// Active style flags
+    active_flag: Vec<bool>,
+    /// Cells of the current row, grouped by style
+    cells: Vec<Cell>,
+}
+
+impl RasterizerCompound {
+    /// Create a new RasterizerCompound
+    pub fn new() -> Self {
+        Self { clipper: Clip::new(), outline: RasterizerCell::new(),
+               status: PathStatus::Initial,
+               x0: 0, y0: 0, scan_y: 0,
+               filling_rule: FillingRule::NonZero,
+               gamma: (0..256).collect(),
+               layer_order: LayerOrder::Direct,
+               min_style: i64::MAX, max_style: i64::MIN,
+               styles: vec![], active: vec![], active_flag: vec![], cells: vec![],
+        }
+    }
+    /// Reset Rasterizer
+    ///
+    /// Removes all paths and styles, following edges have no style
+    pub fn reset(&mut self) {
+        self.outline.reset();
+        self.status = PathStatus::Initial;
+        self.min_style = i64::MAX;
+        self.max_style = i64::MIN;
+    }
+    /// Reset the Rasterizer if it has been rendered, keeping the current styles
+    fn reset_if_sorted(&mut self) {
+        if self.outline.sorted() {
+            let (left, right) = self.outline.current_style();
+            self.reset();
+            self.styles(left, right);
+        }
+    }
+    /// Set the Filling Rule
+    ///
+    /// Defaults to `NonZero`, see [`FillingRule`]
+    ///
+    /// [`FillingRule`]: enum.FillingRule.html
+    pub fn set_filling_rule(&mut self, filling_rule: FillingRule) {
+        self.filling_rule = filling_rule;
+    }
+    /// Return the Filling Rule
+    pub fn filling_rule(&self) -> FillingRule {
+        self.filling_rule
+    }
+    /// Set the Layer Order
+    ///
+    /// Defaults to `Direct`, see [`LayerOrder`]
+    ///
+    /// [`LayerOrder`]: enum.LayerOrder.html
+    pub fn set_layer_order(&mut self, layer_order: LayerOrder) {
+        self.layer_order = layer_order;
+    }
+    /// Return the Layer Order
+    pub fn layer_order(&self) -> LayerOrder {
+        self.layer_order
+    }
+    /// Set the gamma function
+    ///
+    /// See [`RasterizerScanline::gamma`](struct.RasterizerScanline.html#method.gamma)
+    pub fn gamma<F>(&mut self, gfunc: F)
+        where F: Fn(f64) -> f64
+    {
+        let aa_mask = 255.0;
+        self.gamma = (0..256)
+            .map(|i| gfunc(f64::from(i) / aa_mask ))
+            .map(|v| (v * aa_mask).round() as u64)
+            .collect();
+    }
+    /// Set Clip Box
+    pub fn clip_box(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        self.clipper.clip_box(RasConvInt::upscale(x1),
+                              RasConvInt::upscale(y1),
+                              RasConvInt::upscale(x2),
+                              RasConvInt::upscale(y2));
+    }
+    /// Set the styles on the `left` and `right` of following edges
+    ///
+    /// Negative values are no style.  Adjacent shapes are typically added
+    ///   with their own style on one side and no style on the other,
+    ///   with all shapes having the same orientation
+    pub fn styles(&mut self, left: i64, right: i64) {
+        self.reset_if_sorted();
+        self.outline.style(left, right);
+        for &s in &[left, right] {
+            if s >= 0 {
+                self.min_style = min(self.min_style, s);
+                self.max_style = max(self.max_style, s);
+            }
+        }
+    }
+    /// Add a Path
+    ///
+    /// Walks the path from the VertexSource and rasterizes it with the
+    ///   current styles
+    ///
+    /// A `Stop` command ends the path
+    pub fn add_path<VS: VertexSource>(&mut self, path: &VS) {
+        self.reset_if_sorted();
+        for seg in path.xconvert() {
+            match seg.cmd {
+                PathCommand::LineTo |
+                PathCommand::Curve3 |
+                PathCommand::Curve4 => self.line_to(seg.x, seg.y),
+                PathCommand::MoveTo => self.move_to(seg.x, seg.y),
+                PathCommand::Close  => self.close_polygon(),
+                PathCommand::Stop   => break,
+            }
+        }
+    }
+    /// Move to point (x,y)
+    ///
+    /// Sets point as the initial point
+    pub fn move_to(&mut self, x: f64, y: f64) {
+        self.reset_if_sorted();
+        self.x0 = RasConvInt::upscale( x );
+        self.y0 = RasConvInt::upscale( y );
+        self.clipper.move_to(self.x0,self.y0);
+        self.status = PathStatus::MoveTo;
+    }
+    /// Draw line from previous point to point (x,y)
+    pub fn line_to(&mut self, x: f64, y: f64) {
+        let x = RasConvInt::upscale( x );
+        let y = RasConvInt::upscale( y );
+        self.clipper.line_to(&mut self.outline, x,y);
+        self.status = PathStatus::LineTo;
+    }
+    /// Close the current polygon
+    ///
+    /// Draw a line from current point to initial "move to" point
+    pub fn close_polygon(&mut self) {
+        if self.status == PathStatus::LineTo {
+            self.clipper.line_to(&mut self.outline, self.x0, self.y0);
+            self.status = PathStatus::Closed;
+        }
+    }
+    /// Return minimum x value from the RasterizerCell
+    pub fn min_x(&self) -> i64 {
+        self.outline.min_x
+    }
+    /// Return maximum x value from the RasterizerCell
+    pub fn max_x(&self) -> i64 {
+        self.outline.max_x
+    }
+    /// Return minimum style index, `None` if no styles are used
+    pub fn min_style(&self) -> Option<usize> {
+        if self.max_style < self.min_style { None } else { Some(self.min_style as usize) }
+    }
+    /// Return maximum style index, `None` if no styles are used
+    pub fn max_style(&self) -> Option<usize> {
+        if self.max_style < self.min_style { None } else { Some(self.max_style as usize) }
+    }
+
+    /// Rewind the Scanline
+    ///
+    /// Close active polygon, sort the Rasterizer Cells, set the
+    /// scan_y value to the minimum y value and return if any cells
+    /// with styles are present
+    pub(crate) fn rewind_scanlines(&mut self) -> bool {
+        self.close_polygon();
+        self.outline.sort_cells();
+        if self.outline.total_cells() == 0 || self.max_style < self.min_style {
+            return false;
+        }
+        self.scan_y = max(self.outline.min_y, 0);
+        let n = (self.max_style - self.min_style + 2) as usize;
+        self.styles = vec![StyleInfo::default(); n];
+        true
+    }
+    /// Index into styles of a style value, 0 for no style
+    ///
+    /// Returns `None` if the style is outside the range of styles
+    fn style_index(&self, style: i64) -> Option<usize> {
+        if style < 0 {
+            Some(0)
+        } else if style < self.min_style || style > self.max_style {
+            None
+        } else {
+            Some((style - self.min_style + 1) as usize)
+        }
+    }
+    /// Mark a style as active on the current row and count one more cell
+    fn add_style(&mut self, i: usize) {
+        if ! self.active_flag[i] {
+            self.active_flag[i] = true;
+            self.active.push(i);
+            self.styles[i] = StyleInfo { start_cell: 0, num_cells: 0, last_x: i64::MIN };
+        }
+        self.styles[i].start_cell += 1;
+    }
+    /// Sweep the styles of the next row containing styled cells
+    ///
+    /// Cells of the row are distributed to the style on each side, the
+    ///   right side with a negated area and cover
+    ///
+    /// Returns the number of styles on the row, 0 if there are no more rows
+    pub(crate) fn sweep_styles(&mut self) -> usize {
+        loop {
+            if self.scan_y > self.outline.max_y {
+                return 0;
+            }
+            let y = self.scan_y as usize;
+            let row = std::mem::take(&mut self.outline.sorted_y[y]);
+            self.active.clear();
+            self.active_flag.clear();
+            self.active_flag.resize(self.styles.len(), false);
+            self.cells.clear();
+            if ! row.is_empty() {
+                // No style is always first
+                self.add_style(0);
+                self.styles[0].start_cell = 0;
+                for c in &row {
+                    for &s in &[c.left, c.right] {
+                        if let Some(i) = self.style_index(s) {
+                            self.add_style(i);
+                        }
+                    }
+                }
+                // Convert cell counts into starting indices
+                let mut start = 0;
+                for &i in &self.active {
+                    let n = self.styles[i].start_cell;
+                    self.styles[i].start_cell = start;
+                    start += n;
+                }
+                self.cells.resize(start, Cell::default());
+                for c in &row {
+                    for &(s, sign) in &[(c.left, 1), (c.right, -1)] {
+                        let i = match self.style_index(s) {
+                            Some(i) => i,
+                            None => continue,
+                        };
+                        let st = &mut self.styles[i];
+                        if c.x == st.last_x {
+                            let cell = &mut self.cells[st.start_cell + st.num_cells - 1];
+                            cell.area  += sign * c.area;
+                            cell.cover += sign * c.cover;
+                        } else {
+                            let cell = &mut self.cells[st.start_cell + st.num_cells];
+                            cell.x     = c.x;
+                            cell.area  = sign * c.area;
+                            cell.cover = sign * c.cover;
+                            st.last_x = c.x;
+                            st.num_cells += 1;
+                        }
+                    }
+                }
+            }
+            self.outline.sorted_y[y] = row;
+            if self.active.len() > 1 {
+                break;
+            }
+            self.scan_y += 1;
+        }
+        self.scan_y += 1;
+        match self.layer_order {
+            LayerOrder::Unsorted => {},
+            LayerOrder::Direct  => self.active[1..].sort_by(|a,b| b.cmp(a)),
+            LayerOrder::Inverse => self.active[1..].sort(),
+        }
+        self.active.len() - 1
+    }
+    /// Return the style value of the `i`th style of the current row
+    pub(crate) fn style(&self, i: usize) -> usize {
+        (self.active[i + 1] as i64 + self.min_style - 1) as usize
+    }
+    /// Sweep the Scanline of the `i`th style of the current row
+    ///
+    /// If `i` is `None`, the cells without a style are swept, covering
+    ///   all styles on the row
+    ///
+    /// Returns true if data exists in the input Scanline
+    pub(crate) fn sweep_scanline(&self, sl: &mut ScanlineU8, i: Option<usize>) -> bool {
+        let scan_y = self.scan_y - 1;
+        if scan_y > self.outline.max_y {
+            return false;
+        }
+        sl.reset_spans();
+        let i = i.map(|i| i + 1).unwrap_or(0);
+        let st = &self.styles[self.active[i]];
+        let cells = &self.cells[st.start_cell .. st.start_cell + st.num_cells];
+        let mut cover = 0;
+        let mut iter = cells.iter().peekable();
+        while let Some(cell) = iter.next() {
+            let mut x = cell.x;
+            cover += cell.cover;
+            if cell.area != 0 {
+                let alpha = self.calculate_alpha((cover << (POLY_SUBPIXEL_SHIFT + 1)) - cell.area);
+                sl.add_cell(x, alpha);
+                x += 1;
+            }
+            if let Some(next) = iter.peek() {
+                if next.x > x {
+                    let alpha = self.calculate_alpha(cover << (POLY_SUBPIXEL_SHIFT + 1));
+                    if alpha > 0 {
+                        sl.add_span(x, next.x - x, alpha);
+                    }
+                }
+            }
+        }
+        if sl.num_spans() == 0 {
+            return false;
+        }
+        sl.finalize(scan_y);
+        true
+    }
+    /// Calculate alpha term based on area
+    fn calculate_alpha(&self, area: i64) -> u64 {
+        calculate_alpha(area, self.filling_rule, &self.gamma)
+    }
+}
+
+impl Default for RasterizerCompound {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 
 pub(crate) fn len_i64(a: &Vertex<i64>, b: &Vertex<i64>) -> i64 {
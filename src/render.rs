@@ -1,6 +1,8 @@
 //! Renderer
 
 use crate::scan::ScanlineU8;
+use crate::scan::Span;
+use crate::scan::ScanlineU8Am;
 use crate::base::RenderingBase;
 use crate::color::Rgba8;
 use crate::POLY_SUBPIXEL_SCALE;
@@ -16,8 +18,10 @@ use crate::raster::len_i64_xy;
 use crate::clip::{INSIDE, TOP,BOTTOM,LEFT,RIGHT};
 use crate::pixfmt::Pixfmt;
 use crate::raster::RasterizerScanline;
-use crate::Rgb8;
+use crate::raster::RasterizerCompound;
 use crate::Transform;
+use crate::gradient::GradientX;
+use crate::gradient::GRADIENT_SUBPIXEL_SHIFT;
 
 use crate::Source;
 use crate::VertexSource;
@@ -25,6 +29,10 @@ use crate::Render;
 use crate::Color;
 use crate::DrawOutline;
 use crate::Pixel;
+use crate::Gradient;
+use crate::SpanGenerator;
+use crate::AlphaMask;
+use crate::StyleHandler;
 
 use crate::outline::Subpixel;
 
@@ -43,30 +51,32 @@ pub struct RenderingScanlineAASolid<'a,T> where T: 'a {
     color: Rgba8,
 }
 
+/// Anti-Aliased Renderer with colors from a Span Generator
 #[derive(Debug)]
-pub struct RenderingScanlineAA<'a,T> {
+pub struct RenderingScanlineAA<'a,T,S: SpanGenerator> {
     base: &'a mut RenderingBase<T>,
-    span: SpanGradient,
+    span: S,
+    /// Reusable buffer of span colors
+    colors: Vec<S::Color>,
 }
 
+/// Gradient Span Generator
+///
+/// Colors are selected by the distance computed by a [`Gradient`] function
+///   at each pixel, after the inverse of the gradient transform is applied
+///
+/// [`Gradient`]: ../trait.Gradient.html
 #[derive(Debug)]
-pub struct SpanGradient {
+pub struct SpanGradient<G: Gradient = GradientX> {
     d1: i64,
     d2: i64,
-    gradient: GradientX,
-    color: Vec<Rgb8>,
+    gradient: G,
+    color: Vec<Rgba8>,
     trans: Transform,
 }
-#[derive(Debug)]
-pub struct GradientX {}
-impl GradientX {
-    pub fn calculate(&self, x: i64, _: i64, _: i64) -> i64 {
-        x
-    }
-}
 
 #[derive(Debug)]
-struct Interpolator {
+pub(crate) struct Interpolator {
     li_x: Option<LineInterpolator>,
     li_y: Option<LineInterpolator>,
     trans: Transform,
@@ -115,17 +125,25 @@ impl Interpolator {
     }
 }
 
-impl SpanGradient {
+impl<G> SpanGradient<G> where G: Gradient {
     #[inline]
     pub fn subpixel_shift(&self) -> i64 {
-        4
+        GRADIENT_SUBPIXEL_SHIFT
     }
     #[inline]
     pub fn subpixel_scale(&self) -> i64 {
         1 << self.subpixel_shift()
     }
-    pub fn new(trans: Transform, gradient: GradientX, color: &[Rgb8], d1: f64, d2: f64) -> Self {
-        let mut s = Self { d1: 0, d2: 1, color: color.to_vec(), gradient, trans };
+    /// Create a new Gradient Span Generator
+    ///
+    /// Pixels are transformed by `trans` into gradient space, where the
+    ///   `gradient` distance from `d1` to `d2` selects from `color`.  Colors
+    ///   may come from a [`GradientLut`]
+    ///
+    /// [`GradientLut`]: ../gradient/struct.GradientLut.html
+    pub fn new<C: Color>(trans: Transform, gradient: G, color: &[C], d1: f64, d2: f64) -> Self {
+        let color = color.iter().map(|&c| Rgba8::from_trait(c)).collect();
+        let mut s = Self { d1: 0, d2: 1, color, gradient, trans };
         s.d1(d1);
         s.d2(d2);
         s
@@ -136,9 +154,11 @@ impl SpanGradient {
     pub fn d2(&mut self, d2: f64) {
         self.d2 = (d2 * self.subpixel_scale() as f64).round() as i64;
     }
-    pub fn prepare(&mut self) {
-    }
-    pub fn generate(&self, x: i64, y: i64, len: usize) -> Vec<Rgb8> {
+}
+
+impl<G> SpanGenerator for SpanGradient<G> where G: Gradient {
+    type Color = Rgba8;
+    fn generate(&mut self, x: i64, y: i64, len: usize, span: &mut [Rgba8]) {
         let mut interp = Interpolator::new(self.trans);
 
         let downscale_shift = interp.subpixel_shift() - self.subpixel_shift();
@@ -148,7 +168,6 @@ impl SpanGradient {
             dd = 1;
         }
         let ncolors = self.color.len() as i64;
-        let mut span = vec![Rgb8::white() ; len];
 
         interp.begin(x as f64 + 0.5, y as f64 + 0.5, len);
 
@@ -167,7 +186,37 @@ impl SpanGradient {
             span[i] = self.color[d as usize];
             interp.inc();
         }
-        span
+    }
+}
+
+/// Closures as Span Generators
+///
+/// The closure receives the same arguments as [`generate`] and writes
+///   [`Rgba8`] colors, other color types need their own generator
+///
+///     use agg::{Pixfmt,Rgb8,Rgba8,RenderingBase,RenderingScanlineAA};
+///     use agg::{RasterizerScanline,Source};
+///
+///     let mut base = RenderingBase::new(Pixfmt::<Rgb8>::new(10,10));
+///     let mut ras = RasterizerScanline::new();
+///     ras.move_to(0.0, 0.0);
+///     ras.line_to(10.0, 0.0);
+///     ras.line_to(10.0, 10.0);
+///     ras.line_to(0.0, 10.0);
+///     let mut ren = RenderingScanlineAA::new(&mut base, |x: i64, _y: i64, len: usize, span: &mut [Rgba8]| {
+///         for (i, c) in span[..len].iter_mut().enumerate() {
+///             *c = Rgba8::new((x + i as i64) as u8 * 20, 0, 0, 255);
+///         }
+///     });
+///     agg::render_scanlines(&mut ras, &mut ren);
+///     assert_eq!(base.pixf.get((3,5)), Rgba8::new(60,0,0,255));
+///
+/// [`generate`]: ../trait.SpanGenerator.html#tymethod.generate
+/// [`Rgba8`]: ../color/struct.Rgba8.html
+impl<F> SpanGenerator for F where F: FnMut(i64, i64, usize, &mut [Rgba8]) {
+    type Color = Rgba8;
+    fn generate(&mut self, x: i64, y: i64, len: usize, span: &mut [Rgba8]) {
+        self(x, y, len, span)
     }
 }
 
@@ -179,8 +228,12 @@ fn render_scanline_bin_solid<T,C: Color>(sl: &ScanlineU8,
 {
     let cover_full = 255;
     for span in &sl.spans {
-        ren.blend_hline(span.x, sl.y, span.x - 1 + span.len.abs(),
-                        color, cover_full);
+        if span.mask.is_empty() {
+            ren.blend_hline(span.x, sl.y, span.x - 1 + span.len.abs(),
+                            color, cover_full);
+        } else {
+            ren.blend_solid_hspan(span.x, sl.y, span.len, color, &span.mask);
+        }
     }
 }
 
@@ -200,9 +253,14 @@ fn render_scanline_aa_solid<T,C: Color>(sl: &ScanlineU8,
 }
 
 /// Render a single Scanline (y-row) with Anti-Aliasing
-fn render_scanline_aa<T>(sl: &ScanlineU8,
-                         ren: &mut RenderingBase<T>,
-                         span_gen: &SpanGradient) where T: Pixel {
+///
+/// Colors are generated into `colors`, which grows as needed
+fn render_scanline_aa<T,S>(sl: &ScanlineU8,
+                           ren: &mut RenderingBase<T>,
+                           span_gen: &mut S,
+                           colors: &mut Vec<S::Color>)
+    where T: Pixel, S: SpanGenerator
+{
     let y = sl.y;
     for span in &sl.spans {
         let x = span.x;
@@ -211,12 +269,12 @@ fn render_scanline_aa<T>(sl: &ScanlineU8,
         if len < 0 {
             len = -len;
         }
-        //dbg!(x);
-        //dbg!(y);
-        //dbg!(len);
-        let colors = span_gen.generate(x, y, len as usize);
-        //dbg!(&colors);
-        ren.blend_color_hspan(x, y, len, &colors,
+        if colors.len() < len as usize {
+            colors.resize(len as usize, Default::default());
+        }
+        let colors = &mut colors[.. len as usize];
+        span_gen.generate(x, y, len as usize, colors);
+        ren.blend_color_hspan(x, y, len, colors,
                               if span.len < 0 { &[] } else { &covers },
                               covers[0]);
     }
@@ -225,7 +283,7 @@ fn render_scanline_aa<T>(sl: &ScanlineU8,
 
 #[derive(Debug)]
 pub struct RenderData {
-    sl: ScanlineU8
+    pub(crate) sl: ScanlineU8
 }
 impl RenderData {
     pub fn new() -> Self {
@@ -256,10 +314,14 @@ impl<T> Render for RenderingScanlineBinSolid<'_,T> where T: Pixel {
                                 color.blue8(), color.alpha8());
     }
 }
-impl<T> Render for RenderingScanlineAA<'_,T> where T: Pixel {
+impl<T,S> Render for RenderingScanlineAA<'_,T,S> where T: Pixel, S: SpanGenerator {
     /// Render a single Scanline Row
     fn render(&mut self, data: &RenderData) {
-        render_scanline_aa(&data.sl, &mut self.base, &self.span);
+        render_scanline_aa(&data.sl, &mut self.base, &mut self.span, &mut self.colors);
+    }
+    /// Prepare the Span Generator
+    fn prepare(&mut self) {
+        self.span.prepare();
     }
     /// Set the current Color
     fn color<C: Color>(&mut self, _color: C) {
@@ -283,9 +345,14 @@ impl<'a,T> RenderingScanlineBinSolid<'a,T> where T: Pixel {
     }
 
 }
-impl<'a,T> RenderingScanlineAA<'a,T> where T: Pixel {
-    pub fn new(base: &'a mut RenderingBase<T>, span: SpanGradient) -> Self {
-        Self { base, span }
+impl<'a,T,S> RenderingScanlineAA<'a,T,S> where T: Pixel, S: SpanGenerator {
+    /// Create a new Renderer from a Rendering Base and Span Generator
+    pub fn new(base: &'a mut RenderingBase<T>, span: S) -> Self {
+        Self { base, span, colors: vec![] }
+    }
+    /// Return the Span Generator
+    pub fn span(&mut self) -> &mut S {
+        &mut self.span
     }
 }
 impl<'a,T> RenderingScanlineAASolid<'a,T> where T: Pixel {
@@ -302,6 +369,139 @@ impl<'a,T> RenderingScanlineAASolid<'a,T> where T: Pixel {
     }
 }
 
+/// Renderer clipping to multiple rectangles
+///
+/// Wraps a scanline renderer, and through it a [`RenderingBase`], so each
+///   rendered row is clipped against every clip box in turn and passed on.
+///   Geometry is rasterized once however many clip boxes there are.
+///   Overlapping clip boxes are drawn more than once
+///
+/// With no clip boxes everything is drawn, or nothing after
+///   [`reset_clipping`]`(false)`.  Drawing is always limited by the clip box
+///   of the wrapped [`RenderingBase`]
+///
+/// Only scanlines rendered through this renderer, e.g. with
+///   [`render_scanlines`], are clipped to the clip boxes.  Drawing directly
+///   on the [`RenderingBase`], or with the outline and primitive renderers,
+///   is limited only by the [`RenderingBase`] clip box
+///
+///     use agg::{Pixfmt,Rgb8,Rgba8,Source,Render};
+///     use agg::{RenderingBase,RenderingScanlineAASolid,RendererMClip};
+///
+///     let mut ren_base = RenderingBase::new(Pixfmt::<Rgb8>::new(10,10));
+///     ren_base.clear(Rgba8::white());
+///     let mut ren = RendererMClip::new(RenderingScanlineAASolid::with_base(&mut ren_base));
+///     ren.add_clip_box(0, 0, 2, 9);
+///     ren.add_clip_box(7, 0, 9, 9);
+///     ren.color(Rgba8::black());
+///
+///     let mut ras = agg::RasterizerScanline::new();
+///     ras.add_path(&agg::Ellipse::new(5.0, 5.0, 10.0, 10.0, 32));
+///     agg::render_scanlines(&mut ras, &mut ren);
+///
+///     assert_eq!(ren_base.pixf.get((1,5)), Rgba8::black());
+///     assert_eq!(ren_base.pixf.get((5,5)), Rgba8::white());
+///     assert_eq!(ren_base.pixf.get((8,5)), Rgba8::black());
+///
+/// [`RenderingBase`]: ../base/struct.RenderingBase.html
+/// [`reset_clipping`]: #method.reset_clipping
+/// [`render_scanlines`]: fn.render_scanlines.html
+#[derive(Debug)]
+pub struct RendererMClip<R> {
+    /// Wrapped renderer
+    pub ren: R,
+    /// Clip boxes, inclusive
+    clip: Vec<Rectangle<i64>>,
+    /// Draw when there are no clip boxes
+    visible: bool,
+    /// Reusable clipped scanline
+    data: RenderData,
+    /// Spans no longer in the clipped scanline, kept for their buffers
+    spare: Vec<Span>,
+}
+
+impl<R> RendererMClip<R> where R: Render {
+    /// Create a new multiple clipping Renderer from a scanline Renderer
+    ///
+    /// There are initially no clip boxes and everything is drawn
+    pub fn new(ren: R) -> Self {
+        Self { ren, clip: vec![], visible: true, data: RenderData::new(), spare: vec![] }
+    }
+    /// Add a clip box (`x1`,`y1`) - (`x2`,`y2`), inclusive
+    pub fn add_clip_box(&mut self, x1: i64, y1: i64, x2: i64, y2: i64) {
+        self.clip.push(Rectangle::new(x1, y1, x2, y2));
+    }
+    /// Remove all clip boxes
+    ///
+    /// Without clip boxes everything is drawn if `visibility`,
+    ///   otherwise nothing is drawn
+    pub fn reset_clipping(&mut self, visibility: bool) {
+        self.clip.clear();
+        self.visible = visibility;
+    }
+    /// Return the clip boxes
+    pub fn clip_boxes(&self) -> &[Rectangle<i64>] {
+        &self.clip
+    }
+}
+
+impl<R> Render for RendererMClip<R> where R: Render {
+    /// Render a single Scanline Row, once for each clip box
+    fn render(&mut self, data: &RenderData) {
+        if self.clip.is_empty() {
+            if self.visible {
+                self.ren.render(data);
+            }
+            return;
+        }
+        let y = data.sl.y;
+        for cb in &self.clip {
+            if y < cb.y1() || y > cb.y2() {
+                continue;
+            }
+            let sl = &mut self.data.sl;
+            self.spare.append(&mut sl.spans);
+            sl.y = y;
+            for span in &data.sl.spans {
+                let len = span.len.abs();
+                let x1 = std::cmp::max(span.x, cb.x1());
+                let x2 = std::cmp::min(span.x + len - 1, cb.x2());
+                if x1 > x2 {
+                    continue;
+                }
+                let (i, n) = ((x1 - span.x) as usize, (x2 - x1 + 1) as usize);
+                let mut clipped = self.spare.pop().unwrap_or_default();
+                clipped.x = x1;
+                clipped.covers.clear();
+                // Solid spans have a single cover
+                if span.len < 0 {
+                    clipped.len = -(n as i64);
+                    clipped.covers.extend_from_slice(&span.covers);
+                } else {
+                    clipped.len = n as i64;
+                    clipped.covers.extend_from_slice(&span.covers[i .. i + n]);
+                }
+                clipped.mask.clear();
+                if ! span.mask.is_empty() {
+                    clipped.mask.extend_from_slice(&span.mask[i .. i + n]);
+                }
+                sl.spans.push(clipped);
+            }
+            if ! sl.spans.is_empty() {
+                self.ren.render(&self.data);
+            }
+        }
+    }
+    /// Prepare the wrapped Renderer
+    fn prepare(&mut self) {
+        self.ren.prepare();
+    }
+    /// Set the current color of the wrapped Renderer
+    fn color<C: Color>(&mut self, color: C) {
+        self.ren.color(color);
+    }
+}
+
 /* pub trait Scale<T> {
     fn upscale(v: f64)   -> T;
     fn downscale(v: i64) -> T;
@@ -339,6 +539,25 @@ pub fn render_scanlines_aa_solid<C,T>(ras: &mut RasterizerScanline,
     }
 }
 
+/// Render rasterized data to an image using colors from a Span Generator,
+///   Anti-aliased
+pub fn render_scanlines_aa<S,T>(ras: &mut RasterizerScanline,
+                                ren: &mut RenderingBase<T>,
+                                span_gen: &mut S)
+    where S: SpanGenerator,
+          T: Pixel
+{
+    let mut sl = ScanlineU8::new();
+    let mut colors = vec![];
+    if ras.rewind_scanlines() {
+        sl.reset(ras.min_x(), ras.max_x());
+        span_gen.prepare();
+        while ras.sweep_scanline(&mut sl) {
+            render_scanline_aa(&sl, ren, span_gen, &mut colors);
+        }
+    }
+}
+
 /// Render rasterized data to an image using the current color
 pub fn render_scanlines<REN>(ras: &mut RasterizerScanline,
                              ren: &mut REN)
@@ -354,6 +573,198 @@ pub fn render_scanlines<REN>(ras: &mut RasterizerScanline,
     }
 }
 
+/// Render rasterized data to an image using the current color, with covers
+///   modulated by the Alpha Mask of the scanline `sl`
+pub fn render_scanlines_am<REN,M>(ras: &mut RasterizerScanline,
+                                  ren: &mut REN,
+                                  sl: &mut ScanlineU8Am<M>)
+    where REN: Render,
+          M: AlphaMask
+{
+    if ras.rewind_scanlines() {
+        sl.data.sl.reset( ras.min_x(), ras.max_x() );
+        ren.prepare();
+        while ras.sweep_scanline_am(sl) {
+            ren.render(&sl.data);
+        }
+    }
+}
+
+/// Sum of colors weighted by alpha and coverage
+///
+/// AGG adds each style's color, scaled by coverage, to the pixel and clamps
+///   the result.  Here coverage is treated as the area of the pixel a style
+///   occupies: styles sharing an edge are averaged by area, and once the
+///   pixel is full, a later style takes its area from the earlier ones, as
+///   if drawn over them
+#[derive(Debug,Default,Copy,Clone)]
+struct ColorMix {
+    r: u64,
+    g: u64,
+    b: u64,
+    /// Sum of alpha times coverage
+    a: u64,
+}
+
+impl ColorMix {
+    /// Full alpha times full coverage
+    const FULL: u64 = 255 * 255;
+
+    /// Add color `c` with coverage `cover`
+    ///
+    /// An opaque color with full coverage replaces the current colors
+    fn add(&mut self, c: Rgba8, cover: u64) {
+        let a = u64::from(c.a) * cover;
+        if self.a + a > Self::FULL {
+            // Earlier colors keep only the area not taken by `c`
+            let keep = Self::FULL - a;
+            self.r = self.r * keep / self.a;
+            self.g = self.g * keep / self.a;
+            self.b = self.b * keep / self.a;
+            self.a = keep;
+        }
+        self.r += u64::from(c.r) * a;
+        self.g += u64::from(c.g) * a;
+        self.b += u64::from(c.b) * a;
+        self.a += a;
+    }
+    /// Average color, with alpha the total coverage
+    fn color(&self) -> Rgba8 {
+        if self.a == 0 {
+            return Rgba8::new(0, 0, 0, 0);
+        }
+        let avg = |v: u64| ((v + self.a / 2) / self.a) as u8;
+        let a = std::cmp::min(255, (self.a + 127) / 255) as u8;
+        Rgba8::new(avg(self.r), avg(self.g), avg(self.b), a)
+    }
+}
+
+/// Render the styles of a compound rasterizer to an image
+///
+/// All styles of a row are resolved in a single pass.  Where styles share a
+///   pixel their coverage is accumulated before blending, so shared edges
+///   of adjacent shapes do not show seams
+///
+/// Unlike AGG, which adds the colors of all styles, a style covering a
+///   pixel already filled by earlier styles is drawn over them
+///
+///     use agg::{Pixfmt,Rgb8,Rgba8,Source,RenderingBase,RasterizerCompound};
+///
+///     let mut ren_base = RenderingBase::new(Pixfmt::<Rgb8>::new(10,10));
+///     ren_base.clear(Rgba8::white());
+///     let mut ras = RasterizerCompound::new();
+///     // Two squares sharing an edge at x = 4.5
+///     for (style, x1, x2) in vec![(0, 1.0, 4.5), (1, 4.5, 9.0)] {
+///         ras.styles(style, -1);
+///         ras.move_to(x1, 1.0);
+///         ras.line_to(x2, 1.0);
+///         ras.line_to(x2, 9.0);
+///         ras.line_to(x1, 9.0);
+///         ras.close_polygon();
+///     }
+///     let mut colors = [Rgba8::new(255,0,0,255), Rgba8::new(0,0,255,255)];
+///     agg::render_scanlines_compound(&mut ras, &mut ren_base, &mut colors[..]);
+///
+///     assert_eq!(ren_base.pixf.get((2,5)), Rgba8::new(255,0,0,255));
+///     assert_eq!(ren_base.pixf.get((4,5)), Rgba8::new(128,0,127,255));
+///     assert_eq!(ren_base.pixf.get((7,5)), Rgba8::new(0,0,255,255));
+///
+pub fn render_scanlines_compound<T,SH>(ras: &mut RasterizerCompound,
+                                       ren: &mut RenderingBase<T>,
+                                       sh: &mut SH)
+    where T: Pixel,
+          SH: StyleHandler + ?Sized
+{
+    if ! ras.rewind_scanlines() {
+        return;
+    }
+    let min_x = ras.min_x();
+    let len = (ras.max_x() - min_x + 2) as usize;
+    let mut sl_aa = ScanlineU8::new();
+    let mut sl_bin = ScanlineU8::new();
+    sl_aa.reset(min_x, ras.max_x());
+    sl_bin.reset(min_x, ras.max_x());
+    let mut color_span = vec![Rgba8::default(); len];
+    let mut mix_buffer = vec![ColorMix::default(); len];
+    loop {
+        let num_styles = ras.sweep_styles();
+        if num_styles == 0 {
+            break;
+        }
+        if num_styles == 1 {
+            // Single style, render directly
+            if ras.sweep_scanline(&mut sl_aa, Some(0)) {
+                let style = ras.style(0);
+                if sh.is_solid(style) {
+                    render_scanline_aa_solid(&sl_aa, ren, sh.color(style));
+                } else {
+                    for span in &sl_aa.spans {
+                        let colors = &mut color_span[.. span.len as usize];
+                        sh.generate_span(span.x, sl_aa.y, colors.len(), style, colors);
+                        ren.blend_color_hspan(span.x, sl_aa.y, span.len, colors, &span.covers, 255);
+                    }
+                }
+            }
+        } else if ras.sweep_scanline(&mut sl_bin, None) {
+            // Accumulate all styles covering the row, then blend once
+            for span in &sl_bin.spans {
+                let i = (span.x - min_x) as usize;
+                for c in &mut mix_buffer[i .. i + span.len as usize] {
+                    *c = ColorMix::default();
+                }
+            }
+            for i in 0 .. num_styles {
+                let style = ras.style(i);
+                if ! ras.sweep_scanline(&mut sl_aa, Some(i)) {
+                    continue;
+                }
+                let solid = sh.is_solid(style);
+                for span in &sl_aa.spans {
+                    let n = span.len as usize;
+                    let j = (span.x - min_x) as usize;
+                    let colors = &mut color_span[.. n];
+                    if solid {
+                        let c = sh.color(style);
+                        for c0 in colors.iter_mut() {
+                            *c0 = c;
+                        }
+                    } else {
+                        sh.generate_span(span.x, sl_aa.y, n, style, colors);
+                    }
+                    let mix = mix_buffer[j .. j + n].iter_mut();
+                    for ((m, c), &cover) in mix.zip(colors.iter()).zip(span.covers.iter()) {
+                        m.add(*c, cover);
+                    }
+                }
+            }
+            for span in &sl_bin.spans {
+                let n = span.len as usize;
+                let i = (span.x - min_x) as usize;
+                let colors = &mut color_span[.. n];
+                for (c, m) in colors.iter_mut().zip(mix_buffer[i .. i + n].iter()) {
+                    *c = m.color();
+                }
+                ren.blend_color_hspan(span.x, sl_bin.y, span.len, colors, &[], 255);
+            }
+        }
+    }
+}
+
+/// Solid colors, indexed by style
+impl StyleHandler for [Rgba8] {
+    fn is_solid(&self, _style: usize) -> bool {
+        true
+    }
+    fn color(&self, style: usize) -> Rgba8 {
+        self[style]
+    }
+    fn generate_span(&mut self, _x: i64, _y: i64, len: usize, style: usize, span: &mut [Rgba8]) {
+        for c in &mut span[.. len] {
+            *c = self[style];
+        }
+    }
+}
+
 /// Render paths after rasterizing to an image using a set of colors
 pub fn render_all_paths<REN,VS,C>(ras: &mut RasterizerScanline,
                                   ren: &mut REN,
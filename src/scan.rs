@@ -2,6 +2,9 @@
 
 //use std::collections::HashMap;
 
+use crate::render::RenderData;
+use crate::AlphaMask;
+
 /// Contigious area of data
 #[derive(Debug,Default)]
 pub(crate) struct Span {
@@ -11,6 +14,8 @@ pub(crate) struct Span {
     pub len: i64,
     /// Cover values with len values
     pub covers: Vec<u64>,
+    /// Alpha mask values with len values, empty if not masked
+    pub mask: Vec<u64>,
 }
 
 /// Unpacked Scanline
@@ -80,7 +85,8 @@ impl ScanlineU8 {
             cur.covers.extend(vec![cover; len as usize]);
         } else {
             let span = Span { x: x + self.min_x, len,
-                              covers: vec![cover; len as usize] };
+                              covers: vec![cover; len as usize],
+                              mask: vec![] };
             self.spans.push(span);
         }
         self.last_x = x + len - 1;
@@ -99,9 +105,43 @@ impl ScanlineU8 {
         } else {
             //let cover = self.covers.get(&x).unwrap().clone();
             let span = Span { x: x + self.min_x, len: 1,
-                              covers: vec![cover] };
+                              covers: vec![cover], mask: vec![] };
             self.spans.push(span);
         }
         self.last_x = x;
     }
 }
+
+/// Unpacked Scanline with covers modulated by an Alpha Mask
+///
+/// During [`sweep_scanline_am`] the covers of each span are combined with
+///   the mask, so masking applies to every renderer, see
+///   [`render_scanlines_am`]
+///
+/// [`sweep_scanline_am`]: ../raster/struct.RasterizerScanline.html#method.sweep_scanline_am
+/// [`render_scanlines_am`]: ../render/fn.render_scanlines_am.html
+#[derive(Debug)]
+pub struct ScanlineU8Am<M> {
+    /// Scanline data passed to renderers
+    pub(crate) data: RenderData,
+    /// Alpha Mask
+    pub mask: M,
+}
+
+impl<M> ScanlineU8Am<M> where M: AlphaMask {
+    /// Create a new empty scanline with an Alpha Mask
+    pub fn new(mask: M) -> Self {
+        Self { data: RenderData::new(), mask }
+    }
+    /// Combine covers of the current row with the Alpha Mask
+    pub(crate) fn apply_mask(&mut self) {
+        let sl = &mut self.data.sl;
+        for span in &mut sl.spans {
+            span.mask.resize(span.covers.len(), 0);
+            self.mask.fill_hspan(span.x, sl.y, &mut span.mask);
+            for (c, &m) in span.covers.iter_mut().zip(span.mask.iter()) {
+                *c = (*c * m + 255) >> 8;
+            }
+        }
+    }
+}
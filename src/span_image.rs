@@ -0,0 +1,212 @@
+//! Image Span Generators
+//!
+//! Fill shapes with an image drawn through a [`Transform`].  As with
+//!   [`SpanGradient`], the transform maps from the destination to the
+//!   image and is the inverse of the transform used to place the image
+//!
+//!   - [`SpanImageFilterRgbaNn`] - Nearest neighbor, fastest
+//!   - [`SpanImageFilterRgbaBilinear`] - Bilinear interpolation
+//!   - [`SpanImageFilterRgba`] - Any filter from an [`ImageFilterLut`]
+//!
+//! Pixels are read through an [`ImageAccessor`], which also defines colors
+//!   outside of the image.  Colors are weighted by their alpha when
+//!   interpolated, so a transparent background does not darken the edges
+//!   of the image
+//!
+//! # Example
+//!
+//!     // Image with a single red pixel
+//!     let mut img = agg::Pixfmt::<agg::Rgb8>::new(1,1);
+//!     img.copy_pixel(0,0, agg::Rgba8::new(255,0,0,255));
+//!     let src = agg::ImageAccessorClip::new(&img, agg::Rgba8::white());
+//!
+//!     // Scale up 50 times
+//!     let mut mtx = agg::Transform::new_scale(50.0, 50.0);
+//!     mtx.invert();
+//!     let lut = agg::ImageFilterLut::new(&agg::ImageFilterHanning{}, true);
+//!     let mut span = agg::SpanImageFilterRgba::new(src, mtx, lut);
+//!
+//!     let pix = agg::Pixfmt::<agg::Rgb8>::new(100,100);
+//!     let mut ren_base = agg::RenderingBase::new(pix);
+//!     let mut ras = agg::RasterizerScanline::new();
+//!     ras.add_path(&agg::Ellipse::new(50.0, 50.0, 50.0, 50.0, 64));
+//!     agg::render_scanlines_aa(&mut ras, &mut ren_base, &mut span);
+//!
+//! [`Transform`]: ../transform/struct.Transform.html
+//! [`SpanGradient`]: ../render/struct.SpanGradient.html
+//! [`SpanImageFilterRgbaNn`]: struct.SpanImageFilterRgbaNn.html
+//! [`SpanImageFilterRgbaBilinear`]: struct.SpanImageFilterRgbaBilinear.html
+//! [`SpanImageFilterRgba`]: struct.SpanImageFilterRgba.html
+//! [`ImageFilterLut`]: ../image_filters/struct.ImageFilterLut.html
+//! [`ImageAccessor`]: ../trait.ImageAccessor.html
+
+use crate::Rgba8;
+use crate::Transform;
+use crate::ImageAccessor;
+use crate::SpanGenerator;
+use crate::render::Interpolator;
+use crate::image_filters::ImageFilterLut;
+use crate::image_filters::IMAGE_FILTER_SHIFT;
+use crate::image_filters::IMAGE_FILTER_SCALE;
+use crate::image_filters::IMAGE_SUBPIXEL_SHIFT;
+use crate::image_filters::IMAGE_SUBPIXEL_SCALE;
+use crate::image_filters::IMAGE_SUBPIXEL_MASK;
+
+/// Image Span Generator, Nearest Neighbor
+#[derive(Debug)]
+pub struct SpanImageFilterRgbaNn<A> {
+    source: A,
+    trans: Transform,
+}
+
+/// Image Span Generator, Bilinear interpolation
+#[derive(Debug)]
+pub struct SpanImageFilterRgbaBilinear<A> {
+    source: A,
+    trans: Transform,
+}
+
+/// Image Span Generator, interpolated with an Image Filter
+#[derive(Debug)]
+pub struct SpanImageFilterRgba<A> {
+    source: A,
+    trans: Transform,
+    filter: ImageFilterLut,
+}
+
+impl<A> SpanImageFilterRgbaNn<A> where A: ImageAccessor {
+    /// Create a new Span Generator from an image and inverse transform
+    pub fn new(source: A, trans: Transform) -> Self {
+        Self { source, trans }
+    }
+}
+
+impl<A> SpanImageFilterRgbaBilinear<A> where A: ImageAccessor {
+    /// Create a new Span Generator from an image and inverse transform
+    pub fn new(source: A, trans: Transform) -> Self {
+        Self { source, trans }
+    }
+}
+
+impl<A> SpanImageFilterRgba<A> where A: ImageAccessor {
+    /// Create a new Span Generator from an image, inverse transform and filter
+    pub fn new(source: A, trans: Transform, filter: ImageFilterLut) -> Self {
+        Self { source, trans, filter }
+    }
+    /// Set the filter
+    pub fn filter(&mut self, filter: ImageFilterLut) {
+        self.filter = filter;
+    }
+}
+
+/// Weighted sum of premultiplied pixel channels
+///
+/// Colors are weighted by their alpha, so transparent pixels, e.g. outside
+///   of the image, do not darken their neighbors
+#[derive(Debug,Default)]
+struct Accum {
+    /// Sum of weight times alpha times color
+    rgb: [i64; 3],
+    /// Sum of weight times alpha
+    a: i64,
+}
+
+impl Accum {
+    fn add(&mut self, c: Rgba8, weight: i64) {
+        let wa = weight * i64::from(c.a);
+        self.rgb[0] += wa * i64::from(c.r);
+        self.rgb[1] += wa * i64::from(c.g);
+        self.rgb[2] += wa * i64::from(c.b);
+        self.a += wa;
+    }
+    /// Color with alpha scaled down by `shift` bits, clamping to [0,255]
+    fn color(&self, shift: i64) -> Rgba8 {
+        if self.a <= 0 {
+            return Rgba8::new(0, 0, 0, 0);
+        }
+        let a = ((self.a + (1 << (shift - 1))) >> shift).clamp(0, 255) as u8;
+        let v = self.rgb.map(|v| ((v + self.a / 2) / self.a).clamp(0, 255) as u8);
+        Rgba8::new(v[0], v[1], v[2], a)
+    }
+}
+
+/// Start interpolating at the centers of pixels
+fn begin(trans: Transform, x: i64, y: i64, len: usize) -> Interpolator {
+    let mut interp = Interpolator::new(trans);
+    interp.begin(x as f64 + 0.5, y as f64 + 0.5, len);
+    interp
+}
+
+impl<A> SpanGenerator for SpanImageFilterRgbaNn<A> where A: ImageAccessor {
+    type Color = Rgba8;
+    fn generate(&mut self, x: i64, y: i64, len: usize, span: &mut [Rgba8]) {
+        let mut interp = begin(self.trans, x, y, len);
+        for c in span.iter_mut() {
+            let (x, y) = interp.coordinates();
+            *c = self.source.pixel(x >> IMAGE_SUBPIXEL_SHIFT,
+                                   y >> IMAGE_SUBPIXEL_SHIFT);
+            interp.inc();
+        }
+    }
+}
+
+impl<A> SpanGenerator for SpanImageFilterRgbaBilinear<A> where A: ImageAccessor {
+    type Color = Rgba8;
+    fn generate(&mut self, x: i64, y: i64, len: usize, span: &mut [Rgba8]) {
+        let mut interp = begin(self.trans, x, y, len);
+        let scale = IMAGE_SUBPIXEL_SCALE;
+        for c in span.iter_mut() {
+            let (x_hr, y_hr) = interp.coordinates();
+            // Pixel centers are at half pixels
+            let x_hr = x_hr - scale / 2;
+            let y_hr = y_hr - scale / 2;
+            let x_lr = x_hr >> IMAGE_SUBPIXEL_SHIFT;
+            let y_lr = y_hr >> IMAGE_SUBPIXEL_SHIFT;
+            let x_hr = x_hr & IMAGE_SUBPIXEL_MASK;
+            let y_hr = y_hr & IMAGE_SUBPIXEL_MASK;
+
+            let mut fg = Accum::default();
+            let src = &self.source;
+            fg.add(src.pixel(x_lr,     y_lr),     (scale - x_hr) * (scale - y_hr));
+            fg.add(src.pixel(x_lr + 1, y_lr),     x_hr * (scale - y_hr));
+            fg.add(src.pixel(x_lr,     y_lr + 1), (scale - x_hr) * y_hr);
+            fg.add(src.pixel(x_lr + 1, y_lr + 1), x_hr * y_hr);
+            *c = fg.color(IMAGE_SUBPIXEL_SHIFT * 2);
+            interp.inc();
+        }
+    }
+}
+
+impl<A> SpanGenerator for SpanImageFilterRgba<A> where A: ImageAccessor {
+    type Color = Rgba8;
+    fn generate(&mut self, x: i64, y: i64, len: usize, span: &mut [Rgba8]) {
+        let mut interp = begin(self.trans, x, y, len);
+        let scale = IMAGE_SUBPIXEL_SCALE;
+        let diameter = self.filter.diameter() as i64;
+        let start = self.filter.start();
+        let weights = self.filter.weight_array();
+        for c in span.iter_mut() {
+            let (x_hr, y_hr) = interp.coordinates();
+            let x_hr = x_hr - scale / 2;
+            let y_hr = y_hr - scale / 2;
+            let x_lr = x_hr >> IMAGE_SUBPIXEL_SHIFT;
+            let y_lr = y_hr >> IMAGE_SUBPIXEL_SHIFT;
+            // Offsets into the weights of the first pixel
+            let x_fract = IMAGE_SUBPIXEL_MASK - (x_hr & IMAGE_SUBPIXEL_MASK);
+            let y_fract = IMAGE_SUBPIXEL_MASK - (y_hr & IMAGE_SUBPIXEL_MASK);
+
+            let mut fg = Accum::default();
+            for j in 0 .. diameter {
+                let weight_y = i64::from(weights[(y_fract + j * scale) as usize]);
+                for i in 0 .. diameter {
+                    let weight_x = i64::from(weights[(x_fract + i * scale) as usize]);
+                    let weight = (weight_y * weight_x + IMAGE_FILTER_SCALE / 2)
+                        >> IMAGE_FILTER_SHIFT;
+                    fg.add(self.source.pixel(x_lr + start + i, y_lr + start + j), weight);
+                }
+            }
+            *c = fg.color(IMAGE_FILTER_SHIFT);
+            interp.inc();
+        }
+    }
+}
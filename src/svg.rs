@@ -0,0 +1,1211 @@
+//! SVG Documents
+//!
+//! Render a practical subset of SVG into a [`RenderingBase`]
+//!
+//! Supported elements are `svg`, `g`, `path`, `rect`, `circle`, `ellipse`,
+//!   `line`, `polyline` and `polygon`.  Supported presentation attributes,
+//!   either as attributes or within a `style` attribute, are `fill`,
+//!   `stroke`, `stroke-width`, `opacity`, `fill-opacity`, `stroke-opacity`,
+//!   `stroke-linecap`, `stroke-linejoin`, `stroke-miterlimit`, `fill-rule`,
+//!   `color` and `display`, along with `transform`, `viewBox` and `preserveAspectRatio`.
+//!
+//! Anything else that affects rendering, e.g. `text` elements, gradient
+//!   fills or unknown attributes, is not drawn and is reported by
+//!   [`SvgDocument::unsupported`].  Attributes without effect on rendering,
+//!   such as `id`, `class` and namespaced attributes, are ignored.
+//!   A fill or stroke referencing a paint server uses its fallback color,
+//!   or none.
+//!   Elements with an `opacity` are drawn into a separate layer which is
+//!   then blended onto the image.  Nested `svg` elements do not clip.
+//!   `title`, `desc`, `metadata` and `defs` are skipped silently as they
+//!   are not rendered.
+//!
+//! # Example
+//!
+//!     let svg = "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 10 10'>
+//!                  <circle cx='5' cy='5' r='4' fill='#c00'/>
+//!                  <text x='1' y='9'>Hi</text>
+//!                </svg>";
+//!     let doc = agg::SvgDocument::parse(svg).unwrap();
+//!     assert_eq!(doc.unsupported().len(), 1);
+//!
+//!     // Draw, scaled to fit the image
+//!     let pixf = agg::Pixfmt::<agg::Rgb8>::new(100,100);
+//!     let mut ren_base = agg::RenderingBase::new(pixf);
+//!     ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+//!     doc.render_with_transform(&mut ren_base, &agg::Transform::new_scale(10.0, 10.0));
+//!
+//! [`RenderingBase`]: ../base/struct.RenderingBase.html
+//! [`SvgDocument::unsupported`]: struct.SvgDocument.html#method.unsupported
+
+use crate::paths::Path;
+use crate::curves::ConvCurve;
+use crate::stroke::Stroke;
+use crate::stroke::LineCap;
+use crate::stroke::LineJoin;
+use crate::transform::Transform;
+use crate::transform::ConvTransform;
+use crate::svg_path::parse_svg_path;
+use crate::svg_path::SvgPathError;
+use crate::raster::RasterizerScanline;
+use crate::raster::FillingRule;
+use crate::render::render_scanlines_aa_solid;
+use crate::base::RenderingBase;
+use crate::pixfmt::Pixfmt;
+use crate::color::Rgba8;
+use crate::color::Rgba8pre;
+use crate::Pixel;
+use crate::VertexSource;
+
+use std::fmt;
+
+/// Error reading an SVG Document
+#[derive(Debug,Clone,PartialEq)]
+pub enum SvgError {
+    /// Error reading the file
+    Io(String),
+    /// Malformed XML at a byte offset
+    Xml { offset: usize, message: String },
+    /// Malformed path data, offset is from the start of the document
+    PathData { offset: usize, error: SvgPathError },
+    /// Root element is not `svg`
+    NotSvg { name: String },
+}
+
+impl From<std::io::Error> for SvgError {
+    fn from(err: std::io::Error) -> Self {
+        SvgError::Io(err.to_string())
+    }
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SvgError::Io(msg) => write!(f, "{}", msg),
+            SvgError::Xml { offset, message } =>
+                write!(f, "{} at byte {}", message, offset),
+            SvgError::PathData { offset, error } =>
+                write!(f, "invalid path data at byte {}: {}", offset, error),
+            SvgError::NotSvg { name } =>
+                write!(f, "root element is <{}>, expected <svg>", name),
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+/// Part of an SVG Document that was not rendered
+#[derive(Debug,Clone,PartialEq)]
+pub enum SvgUnsupported {
+    /// Element, and its children, were not rendered
+    Element { name: String, offset: usize },
+    /// Attribute value was not understood and is ignored
+    Attribute { name: String, value: String, offset: usize },
+}
+
+/// Parsed SVG Document ready for rendering
+///
+/// Shapes are stored as paths with their resolved style and transform
+#[derive(Debug,Clone)]
+pub struct SvgDocument {
+    /// Viewport width in pixels
+    width: f64,
+    /// Viewport height in pixels
+    height: f64,
+    /// Shapes and layers in painting order
+    items: Vec<Item>,
+    /// Elements and attributes not rendered
+    unsupported: Vec<SvgUnsupported>,
+}
+
+/// Single shape with resolved style
+#[derive(Debug,Clone)]
+struct Shape {
+    path: Path,
+    transform: Transform,
+    fill: Option<Rgba8>,
+    fill_rule: FillingRule,
+    stroke: Option<Rgba8>,
+    stroke_width: f64,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    miter_limit: f64,
+}
+
+/// Shape, or items drawn together into a layer
+#[derive(Debug,Clone)]
+enum Item {
+    Shape(Shape),
+    /// Items blended onto the image at once with an `opacity`
+    Layer { opacity: f64, items: Vec<Item> },
+}
+
+impl SvgDocument {
+    /// Parse an SVG Document from a string
+    pub fn parse(text: &str) -> Result<Self, SvgError> {
+        let root = XmlParser::new(text).document()?;
+        if root.name != "svg" {
+            return Err(SvgError::NotSvg { name: root.name });
+        }
+        let mut b = Builder { size: (0.0, 0.0), items: vec![], unsupported: vec![] };
+        let (width, height, viewport) = b.viewport(&root, None);
+        b.size = (width, height);
+        let style = b.style(&root, &Style::default());
+        let mtx = b.transform(&root).mul_transform(&viewport);
+        if style.display {
+            b.layer(style.opacity, |b| b.children(&root, &style, &mtx))?;
+        }
+        Ok(Self { width, height, items: b.items, unsupported: b.unsupported })
+    }
+    /// Read and parse an SVG Document from a file
+    pub fn from_file<P: AsRef<std::path::Path>>(filename: P) -> Result<Self, SvgError> {
+        let text = std::fs::read_to_string(filename)?;
+        Self::parse(&text)
+    }
+    /// Width of the viewport in pixels
+    ///
+    /// From the `width` attribute, or the `viewBox`, of the root element
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+    /// Height of the viewport in pixels
+    ///
+    /// From the `height` attribute, or the `viewBox`, of the root element
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+    /// Elements and attribute values that were not rendered
+    pub fn unsupported(&self) -> &[SvgUnsupported] {
+        &self.unsupported
+    }
+    /// Draw the document with the viewport at the origin
+    pub fn render<T: Pixel>(&self, ren: &mut RenderingBase<T>) {
+        self.render_with_transform(ren, &Transform::new());
+    }
+    /// Draw the document after the viewport is transformed by `mtx`
+    pub fn render_with_transform<T: Pixel>(&self, ren: &mut RenderingBase<T>, mtx: &Transform) {
+        let mut ras = RasterizerScanline::new();
+        draw_items(&self.items, &mut ras, ren, mtx, false);
+    }
+}
+
+/// Draw items, with premultiplied colors if `ren` is premultiplied
+fn draw_items<T: Pixel>(items: &[Item], ras: &mut RasterizerScanline,
+                        ren: &mut RenderingBase<T>, mtx: &Transform, premultiplied: bool) {
+    for item in items {
+        match item {
+            Item::Shape(shape) => draw_shape(shape, ras, ren, mtx, premultiplied),
+            Item::Layer { opacity, items } => {
+                let (w, h) = (ren.pixf.width(), ren.pixf.height());
+                let mut layer = RenderingBase::new(Pixfmt::<Rgba8pre>::new(w, h));
+                draw_items(items, ras, &mut layer, mtx, true);
+                let cover = (opacity * 255.0).round() as u64;
+                let (xmin, xmax, ymin, ymax) = ren.limits();
+                for y in ymin.max(0) ..= ymax.min(h as i64 - 1) {
+                    for x in xmin.max(0) ..= xmax.min(w as i64 - 1) {
+                        let id = (x as usize, y as usize);
+                        let c = layer.pixf.raw(id);
+                        if premultiplied {
+                            ren.pixf.copy_or_blend_pix_with_cover(id, c, cover);
+                        } else {
+                            ren.pixf.copy_or_blend_pix_with_cover(id, c.demultiply(), cover);
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Draw the fill then the stroke of a shape
+fn draw_shape<T: Pixel>(shape: &Shape, ras: &mut RasterizerScanline,
+                        ren: &mut RenderingBase<T>, mtx: &Transform, premultiplied: bool) {
+    let trans = shape.transform.mul_transform(mtx);
+    let scale = transform_scale(&trans);
+    // Curves are flattened in user space
+    let mut curve = ConvCurve::new(shape.path.clone());
+    curve.approximation_scale(scale);
+    let flat = Path { vertices: curve.xconvert() };
+    if let Some(color) = shape.fill {
+        let path = ConvTransform::new(flat.clone(), trans);
+        ras.reset();
+        ras.set_filling_rule(shape.fill_rule);
+        ras.add_path(&path);
+        draw_solid(ras, ren, color, premultiplied);
+    }
+    if let Some(color) = shape.stroke {
+        let mut stroke = Stroke::new(flat);
+        stroke.width(shape.stroke_width);
+        stroke.line_cap(shape.line_cap);
+        stroke.line_join(shape.line_join);
+        stroke.miter_limit(shape.miter_limit);
+        stroke.approximation_scale(scale);
+        let path = ConvTransform::new(stroke, trans);
+        ras.reset();
+        ras.set_filling_rule(FillingRule::NonZero);
+        ras.add_path(&path);
+        draw_solid(ras, ren, color, premultiplied);
+    }
+}
+
+/// Render the rasterized path with `color`, premultiplied if requested
+fn draw_solid<T: Pixel>(ras: &mut RasterizerScanline, ren: &mut RenderingBase<T>,
+                        color: Rgba8, premultiplied: bool) {
+    if premultiplied {
+        render_scanlines_aa_solid(ras, ren, color.premultiply());
+    } else {
+        render_scanlines_aa_solid(ras, ren, color);
+    }
+}
+
+/// Average scaling of a transform
+fn transform_scale(t: &Transform) -> f64 {
+    let x = (t.sx * t.sx + t.shy * t.shy).sqrt();
+    let y = (t.shx * t.shx + t.sy * t.sy).sqrt();
+    (x + y) / 2.0
+}
+
+/// Paint of a fill or stroke
+#[derive(Debug,Copy,Clone,PartialEq)]
+enum Paint {
+    None,
+    Color(Rgba8),
+    CurrentColor,
+}
+
+/// Inherited presentation properties
+#[derive(Debug,Copy,Clone)]
+struct Style {
+    fill: Paint,
+    fill_rule: FillingRule,
+    stroke: Paint,
+    color: Rgba8,
+    fill_opacity: f64,
+    stroke_opacity: f64,
+    /// Opacity of the element itself, not inherited
+    opacity: f64,
+    stroke_width: f64,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    miter_limit: f64,
+    display: bool,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: Paint::Color(Rgba8::black()),
+            fill_rule: FillingRule::NonZero,
+            stroke: Paint::None,
+            color: Rgba8::black(),
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            opacity: 1.0,
+            stroke_width: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::MiterRevert,
+            miter_limit: 4.0,
+            display: true,
+        }
+    }
+}
+
+impl Style {
+    /// Resolve a paint to a color
+    fn resolve(&self, paint: Paint, opacity: f64) -> Option<Rgba8> {
+        let mut c = match paint {
+            Paint::None => return None,
+            Paint::Color(c) => c,
+            Paint::CurrentColor => self.color,
+        };
+        c.a = (f64::from(c.a) * opacity).round() as u8;
+        Some(c)
+    }
+}
+
+/// Collects shapes while walking the document
+struct Builder {
+    /// Size of the current viewport
+    size: (f64, f64),
+    items: Vec<Item>,
+    unsupported: Vec<SvgUnsupported>,
+}
+
+impl Builder {
+    fn report_attr(&mut self, attr: &Attribute) {
+        self.unsupported.push(SvgUnsupported::Attribute {
+            name: attr.name.clone(),
+            value: attr.value.clone(),
+            offset: attr.offset,
+        });
+    }
+    /// Length attribute, reported and `default` if not understood
+    fn length(&mut self, el: &Element, name: &str, default: f64) -> f64 {
+        match el.attr(name) {
+            None => default,
+            Some(attr) => parse_length(&attr.value).unwrap_or_else(|| {
+                self.report_attr(attr);
+                default
+            }),
+        }
+    }
+    /// Viewport size and transform of an `svg` element
+    ///
+    /// Size defaults to the `viewBox` size, or the `parent` viewport size
+    ///   for nested elements
+    fn viewport(&mut self, el: &Element, parent: Option<(f64,f64)>) -> (f64, f64, Transform) {
+        let view_box = el.attr("viewBox").and_then(|attr| {
+            match parse_numbers(&attr.value) {
+                Some(ref v) if v.len() == 4 && v[2] > 0.0 && v[3] > 0.0 =>
+                    Some([v[0], v[1], v[2], v[3]]),
+                _ => {
+                    self.report_attr(attr);
+                    None
+                }
+            }
+        });
+        let (dw, dh) = match (parent, view_box) {
+            (Some(size), _) => size,
+            (None, Some(vb)) => (vb[2], vb[3]),
+            (None, None) => (0.0, 0.0),
+        };
+        let width = self.size(el, "width", dw);
+        let height = self.size(el, "height", dh);
+        let mut mtx = Transform::new();
+        if let Some([vx, vy, vw, vh]) = view_box {
+            let par = el.attr("preserveAspectRatio")
+                .map(|a| a.value.as_str())
+                .unwrap_or("xMidYMid meet");
+            let (align, slice) = match parse_aspect_ratio(par) {
+                Some(v) => v,
+                None => {
+                    let attr = el.attr("preserveAspectRatio").unwrap();
+                    self.report_attr(attr);
+                    (Some((0.5, 0.5)), false)
+                }
+            };
+            let (mut sx, mut sy) = (width / vw, height / vh);
+            let (mut tx, mut ty) = (0.0, 0.0);
+            if let Some((ax, ay)) = align {
+                let s = if slice { sx.max(sy) } else { sx.min(sy) };
+                sx = s;
+                sy = s;
+                tx = (width  - vw * s) * ax;
+                ty = (height - vh * s) * ay;
+            }
+            mtx = Transform::new_translate(-vx, -vy);
+            mtx.scale(sx, sy);
+            mtx.translate(tx, ty);
+        }
+        if parent.is_some() {
+            let x = self.length(el, "x", 0.0);
+            let y = self.length(el, "y", 0.0);
+            mtx.translate(x, y);
+        }
+        (width, height, mtx)
+    }
+    /// Viewport width or height, percentages are relative to the default
+    fn size(&mut self, el: &Element, name: &str, default: f64) -> f64 {
+        match el.attr(name) {
+            Some(attr) if attr.value.trim().ends_with('%') => {
+                match parse_numbers(attr.value.trim().trim_end_matches('%')) {
+                    Some(ref v) if v.len() == 1 => default * v[0] / 100.0,
+                    _ => {
+                        self.report_attr(attr);
+                        default
+                    }
+                }
+            },
+            _ => self.length(el, name, default),
+        }
+    }
+    /// Local transform of an element
+    fn transform(&mut self, el: &Element) -> Transform {
+        match el.attr("transform") {
+            None => Transform::new(),
+            Some(attr) => parse_transform(&attr.value).unwrap_or_else(|| {
+                self.report_attr(attr);
+                Transform::new()
+            }),
+        }
+    }
+    /// Style of an element, from presentation attributes then `style`
+    fn style(&mut self, el: &Element, parent: &Style) -> Style {
+        let mut style = Style { opacity: 1.0, ..*parent };
+        for attr in el.attrs.iter().filter(|a| ! element_attr(&el.name, &a.name)) {
+            self.property(&mut style, attr);
+        }
+        if let Some(attr) = el.attr("style") {
+            for decl in attr.value.split(';') {
+                let mut kv = decl.splitn(2, ':');
+                let name = kv.next().unwrap_or("").trim();
+                let value = kv.next().unwrap_or("").trim();
+                if name.is_empty() {
+                    continue;
+                }
+                let decl = Attribute {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                    offset: attr.offset,
+                };
+                self.property(&mut style, &decl);
+            }
+        }
+        style
+    }
+    /// Apply a presentation property, reporting values not understood
+    fn property(&mut self, style: &mut Style, attr: &Attribute) {
+        let v = attr.value.trim();
+        let ok = match attr.name.as_str() {
+            "fill" | "stroke" => {
+                let paint = parse_paint(v);
+                // Paint servers are not supported, use the fallback or none
+                let fallback = if v.starts_with("url(") {
+                    let fallback = &v[v.find(')').map(|i| i + 1).unwrap_or(v.len())..];
+                    Some(parse_paint(fallback).unwrap_or(Paint::None))
+                } else {
+                    None
+                };
+                if let Some(p) = paint.or(fallback) {
+                    if attr.name == "fill" {
+                        style.fill = p;
+                    } else {
+                        style.stroke = p;
+                    }
+                }
+                paint.is_some()
+            },
+            "color" => match parse_paint(v) {
+                Some(Paint::Color(c)) => { style.color = c; true },
+                _ => false,
+            },
+            "stroke-width" => parse_length(v).map(|w| style.stroke_width = w).is_some(),
+            "opacity" => parse_opacity(v).map(|o| style.opacity = o).is_some(),
+            "fill-opacity" => parse_opacity(v).map(|o| style.fill_opacity = o).is_some(),
+            "stroke-opacity" => parse_opacity(v).map(|o| style.stroke_opacity = o).is_some(),
+            "stroke-miterlimit" => v.parse::<f64>().ok()
+                .filter(|&m| m >= 1.0)
+                .map(|m| style.miter_limit = m).is_some(),
+            "stroke-linecap" => {
+                let cap = match v {
+                    "butt" => Some(LineCap::Butt),
+                    "round" => Some(LineCap::Round),
+                    "square" => Some(LineCap::Square),
+                    _ => None,
+                };
+                cap.map(|c| style.line_cap = c).is_some()
+            },
+            "stroke-linejoin" => {
+                let join = match v {
+                    "miter" => Some(LineJoin::MiterRevert),
+                    "round" => Some(LineJoin::Round),
+                    "bevel" => Some(LineJoin::Bevel),
+                    _ => None,
+                };
+                join.map(|j| style.line_join = j).is_some()
+            },
+            "display" => { style.display = v != "none"; true },
+            "fill-rule" => {
+                let rule = match v {
+                    "nonzero" => Some(FillingRule::NonZero),
+                    "evenodd" => Some(FillingRule::EvenOdd),
+                    _ => None,
+                };
+                rule.map(|r| style.fill_rule = r).is_some()
+            },
+            "stroke-dasharray" => v == "none",
+            "clip-path" | "mask" | "filter" => v == "none",
+            _ => false,
+        };
+        if ! ok {
+            self.report_attr(attr);
+        }
+    }
+    /// Add items from `add`, collected into a layer if `opacity` is below one
+    fn layer<F>(&mut self, opacity: f64, add: F) -> Result<(), SvgError>
+        where F: FnOnce(&mut Self) -> Result<(), SvgError>
+    {
+        if opacity >= 1.0 {
+            return add(self);
+        }
+        let outer = std::mem::take(&mut self.items);
+        let out = add(self);
+        let items = std::mem::replace(&mut self.items, outer);
+        self.items.push(Item::Layer { opacity, items });
+        out
+    }
+    /// Walk the children of a container element
+    fn children(&mut self, el: &Element, style: &Style, mtx: &Transform) -> Result<(), SvgError> {
+        for child in &el.children {
+            self.element(child, style, mtx)?;
+        }
+        Ok(())
+    }
+    /// Add an element and its children
+    fn element(&mut self, el: &Element, parent: &Style, mtx: &Transform) -> Result<(), SvgError> {
+        match el.name.as_str() {
+            "title" | "desc" | "metadata" | "defs" => return Ok(()),
+            "svg" | "g" | "path" | "rect" | "circle" | "ellipse" |
+            "line" | "polyline" | "polygon" => {},
+            _ => {
+                self.unsupported.push(SvgUnsupported::Element {
+                    name: el.name.clone(), offset: el.offset
+                });
+                return Ok(());
+            }
+        }
+        let style = self.style(el, parent);
+        if ! style.display {
+            return Ok(());
+        }
+        let mtx = self.transform(el).mul_transform(mtx);
+        let path = match el.name.as_str() {
+            "g" => return self.layer(style.opacity, |b| b.children(el, &style, &mtx)),
+            "svg" => {
+                let parent = self.size;
+                let (width, height, viewport) = self.viewport(el, Some(parent));
+                self.size = (width, height);
+                let mtx = viewport.mul_transform(&mtx);
+                let out = self.layer(style.opacity, |b| b.children(el, &style, &mtx));
+                self.size = parent;
+                return out;
+            },
+            "path" => match el.attr("d") {
+                Some(attr) => parse_svg_path(&attr.value)
+                    .map_err(|error| SvgError::PathData {
+                        offset: attr.offset + error.offset(), error
+                    })?,
+                None => return Ok(()),
+            },
+            "rect" => self.rect(el),
+            "circle" => {
+                let r = self.length(el, "r", 0.0);
+                self.ellipse(el, r, r)
+            },
+            "ellipse" => {
+                let rx = self.length(el, "rx", 0.0);
+                let ry = self.length(el, "ry", 0.0);
+                self.ellipse(el, rx, ry)
+            },
+            "line" => {
+                let mut path = Path::new();
+                path.move_to(self.length(el, "x1", 0.0), self.length(el, "y1", 0.0));
+                path.line_to(self.length(el, "x2", 0.0), self.length(el, "y2", 0.0));
+                path
+            },
+            _ => match el.attr("points") {
+                // polyline and polygon
+                Some(attr) if ! attr.value.trim().is_empty() => {
+                    let mut path = parse_svg_path(&format!("M{}", attr.value))
+                        .map_err(|error| SvgError::PathData {
+                            offset: attr.offset + error.offset() - 1, error
+                        })?;
+                    if el.name == "polygon" {
+                        path.close_polygon();
+                    }
+                    path
+                },
+                _ => return Ok(()),
+            },
+        };
+        if path.vertices.is_empty() {
+            return Ok(());
+        }
+        let fill = if el.name == "line" { Paint::None } else { style.fill };
+        let stroke = if style.stroke_width > 0.0 { style.stroke } else { Paint::None };
+        // Opacity of a single paint is applied to its color, a fill and a
+        //   stroke are drawn together into a layer
+        let layered = fill != Paint::None && stroke != Paint::None;
+        let opacity = if layered { 1.0 } else { style.opacity };
+        let shape = Shape {
+            path,
+            fill: style.resolve(fill, style.fill_opacity * opacity),
+            stroke: style.resolve(stroke, style.stroke_opacity * opacity),
+            fill_rule: style.fill_rule,
+            transform: mtx,
+            stroke_width: style.stroke_width,
+            line_cap: style.line_cap,
+            line_join: style.line_join,
+            miter_limit: style.miter_limit,
+        };
+        if layered {
+            self.layer(style.opacity, |b| { b.items.push(Item::Shape(shape)); Ok(()) })
+        } else {
+            self.items.push(Item::Shape(shape));
+            Ok(())
+        }
+    }
+    /// Rectangle, with optionally rounded corners
+    fn rect(&mut self, el: &Element) -> Path {
+        let x = self.length(el, "x", 0.0);
+        let y = self.length(el, "y", 0.0);
+        let w = self.length(el, "width", 0.0);
+        let h = self.length(el, "height", 0.0);
+        let rx = el.attr("rx").map(|_| self.length(el, "rx", 0.0));
+        let ry = el.attr("ry").map(|_| self.length(el, "ry", 0.0));
+        let (rx, ry) = match (rx, ry) {
+            (Some(rx), Some(ry)) => (rx, ry),
+            (Some(r), None) | (None, Some(r)) => (r, r),
+            (None, None) => (0.0, 0.0),
+        };
+        let rx = rx.max(0.0).min(w / 2.0);
+        let ry = ry.max(0.0).min(h / 2.0);
+
+        let mut path = Path::new();
+        if w <= 0.0 || h <= 0.0 {
+            return path;
+        }
+        if rx > 0.0 && ry > 0.0 {
+            path.move_to(x + rx, y);
+            path.line_to(x + w - rx, y);
+            path.arc_to(rx, ry, 0.0, false, true, x + w, y + ry);
+            path.line_to(x + w, y + h - ry);
+            path.arc_to(rx, ry, 0.0, false, true, x + w - rx, y + h);
+            path.line_to(x + rx, y + h);
+            path.arc_to(rx, ry, 0.0, false, true, x, y + h - ry);
+            path.line_to(x, y + ry);
+            path.arc_to(rx, ry, 0.0, false, true, x + rx, y);
+        } else {
+            path.move_to(x, y);
+            path.line_to(x + w, y);
+            path.line_to(x + w, y + h);
+            path.line_to(x, y + h);
+        }
+        path.close_polygon();
+        path
+    }
+    /// Ellipse from four quarter arcs
+    fn ellipse(&mut self, el: &Element, rx: f64, ry: f64) -> Path {
+        let cx = self.length(el, "cx", 0.0);
+        let cy = self.length(el, "cy", 0.0);
+        let mut path = Path::new();
+        if rx <= 0.0 || ry <= 0.0 {
+            return path;
+        }
+        path.move_to(cx + rx, cy);
+        path.arc_to(rx, ry, 0.0, false, true, cx, cy + ry);
+        path.arc_to(rx, ry, 0.0, false, true, cx - rx, cy);
+        path.arc_to(rx, ry, 0.0, false, true, cx, cy - ry);
+        path.arc_to(rx, ry, 0.0, false, true, cx + rx, cy);
+        path.close_polygon();
+        path
+    }
+}
+
+/// Attributes of element `el` that are not presentation properties, either
+///   read by the element itself or without effect on rendering
+fn element_attr(el: &str, name: &str) -> bool {
+    match name {
+        "id" | "class" | "style" | "transform" | "lang" | "tabindex" => true,
+        _ if name.contains(':') || name.starts_with("data-") || name.starts_with("aria-") => true,
+        _ => match el {
+            "svg" => matches!(name, "x" | "y" | "width" | "height" | "viewBox" |
+                              "preserveAspectRatio" | "version" | "baseProfile" | "xmlns"),
+            "rect" => matches!(name, "x" | "y" | "width" | "height" | "rx" | "ry"),
+            "circle" => matches!(name, "cx" | "cy" | "r"),
+            "ellipse" => matches!(name, "cx" | "cy" | "rx" | "ry"),
+            "line" => matches!(name, "x1" | "y1" | "x2" | "y2"),
+            "polyline" | "polygon" => name == "points",
+            "path" => matches!(name, "d" | "pathLength"),
+            _ => false,
+        },
+    }
+}
+
+/// Numbers separated by whitespace and/or commas
+fn parse_numbers(s: &str) -> Option<Vec<f64>> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|v| ! v.is_empty())
+        .map(|v| v.parse::<f64>().ok())
+        .collect()
+}
+
+/// Length in pixels, with an optional absolute unit
+fn parse_length(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let units = [("px", 1.0), ("pt", 96.0 / 72.0), ("pc", 16.0),
+                 ("mm", 96.0 / 25.4), ("cm", 96.0 / 2.54), ("in", 96.0)];
+    for &(unit, scale) in units.iter() {
+        if let Some(v) = s.strip_suffix(unit) {
+            return v.trim().parse::<f64>().ok().map(|v| v * scale);
+        }
+    }
+    s.parse::<f64>().ok()
+}
+
+/// Opacity, clamped to [0,1]
+fn parse_opacity(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok().map(|v| v.clamp(0.0, 1.0))
+}
+
+/// Alignment and slice flag of a `preserveAspectRatio` value
+///
+/// Alignment is `None` for non-uniform scaling
+fn parse_aspect_ratio(s: &str) -> Option<(Option<(f64,f64)>, bool)> {
+    let mut words = s.split_whitespace().filter(|&w| w != "defer");
+    let align = words.next()?;
+    let slice = match words.next() {
+        None | Some("meet") => false,
+        Some("slice") => true,
+        _ => return None,
+    };
+    if align == "none" {
+        return Some((None, slice));
+    }
+    let pos = |s: &str| match s {
+        "Min" => Some(0.0),
+        "Mid" => Some(0.5),
+        "Max" => Some(1.0),
+        _ => None,
+    };
+    let align = align.strip_prefix('x')?;
+    let y = align.get(3..)?.strip_prefix('Y')?;
+    Some((Some((pos(align.get(..3)?)?, pos(y)?)), slice))
+}
+
+/// List of transform functions, applied right to left
+fn parse_transform(s: &str) -> Option<Transform> {
+    let mut mtx = Transform::new();
+    let mut rest = s.trim();
+    while ! rest.is_empty() {
+        let open = rest.find('(')?;
+        let close = rest.find(')')?;
+        if close < open {
+            return None;
+        }
+        let name = rest[..open].trim();
+        let v = parse_numbers(&rest[open+1..close])?;
+        let t = match (name, v.len()) {
+            ("matrix", 6) => Transform { sx: v[0], shy: v[1], shx: v[2],
+                                         sy: v[3], tx: v[4], ty: v[5] },
+            ("translate", 1) => Transform::new_translate(v[0], 0.0),
+            ("translate", 2) => Transform::new_translate(v[0], v[1]),
+            ("scale", 1) => Transform::new_scale(v[0], v[0]),
+            ("scale", 2) => Transform::new_scale(v[0], v[1]),
+            ("rotate", 1) => Transform::new_rotate(v[0].to_radians()),
+            ("rotate", 3) => {
+                let mut t = Transform::new_translate(-v[1], -v[2]);
+                t.rotate(v[0].to_radians());
+                t.translate(v[1], v[2]);
+                t
+            },
+            ("skewX", 1) => Transform { shx: v[0].to_radians().tan(), ..Transform::new() },
+            ("skewY", 1) => Transform { shy: v[0].to_radians().tan(), ..Transform::new() },
+            _ => return None,
+        };
+        mtx = t.mul_transform(&mtx);
+        rest = rest[close+1..].trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+    }
+    Some(mtx)
+}
+
+/// Color or paint server
+///
+/// Returns `None` for values not understood, including references
+fn parse_paint(s: &str) -> Option<Paint> {
+    let s = s.trim();
+    match s {
+        "none" | "transparent" => return Some(Paint::None),
+        "currentColor" => return Some(Paint::CurrentColor),
+        _ => {},
+    }
+    if let Some(hex) = s.strip_prefix('#') {
+        let v = u32::from_str_radix(hex, 16).ok()?;
+        let c = match hex.len() {
+            3 => {
+                let (r, g, b) = ((v >> 8) & 0xf, (v >> 4) & 0xf, v & 0xf);
+                Rgba8::new((r * 17) as u8, (g * 17) as u8, (b * 17) as u8, 255)
+            },
+            6 => Rgba8::new((v >> 16) as u8, (v >> 8) as u8, v as u8, 255),
+            _ => return None,
+        };
+        return Some(Paint::Color(c));
+    }
+    if s.ends_with(')') {
+        let open = s.find('(')?;
+        let name = s[..open].trim();
+        let args : Vec<_> = s[open+1..s.len()-1].split(',').map(|a| a.trim()).collect();
+        let channel = |a: &str| -> Option<u8> {
+            let v = match a.strip_suffix('%') {
+                Some(p) => p.trim().parse::<f64>().ok()? * 2.55,
+                None => a.parse::<f64>().ok()?,
+            };
+            Some(v.round().clamp(0.0, 255.0) as u8)
+        };
+        let alpha = match (name, args.len()) {
+            ("rgb", 3) => 255,
+            ("rgba", 4) => (parse_opacity(args[3])? * 255.0).round() as u8,
+            _ => return None,
+        };
+        let c = Rgba8::new(channel(args[0])?, channel(args[1])?, channel(args[2])?, alpha);
+        return Some(Paint::Color(c));
+    }
+    let (r, g, b) = match s {
+        "black"   => (0, 0, 0),
+        "silver"  => (192, 192, 192),
+        "gray" | "grey" => (128, 128, 128),
+        "white"   => (255, 255, 255),
+        "maroon"  => (128, 0, 0),
+        "red"     => (255, 0, 0),
+        "purple"  => (128, 0, 128),
+        "fuchsia" | "magenta" => (255, 0, 255),
+        "green"   => (0, 128, 0),
+        "lime"    => (0, 255, 0),
+        "olive"   => (128, 128, 0),
+        "yellow"  => (255, 255, 0),
+        "navy"    => (0, 0, 128),
+        "blue"    => (0, 0, 255),
+        "teal"    => (0, 128, 128),
+        "aqua" | "cyan" => (0, 255, 255),
+        "orange"  => (255, 165, 0),
+        _ => return None,
+    };
+    Some(Paint::Color(Rgba8::new(r, g, b, 255)))
+}
+
+/// XML Attribute
+#[derive(Debug,Clone)]
+struct Attribute {
+    name: String,
+    value: String,
+    /// Byte offset of the value
+    offset: usize,
+}
+
+/// XML Element, text content is discarded
+#[derive(Debug,Clone)]
+struct Element {
+    /// Name without a namespace prefix
+    name: String,
+    attrs: Vec<Attribute>,
+    children: Vec<Element>,
+    /// Byte offset of the start tag
+    offset: usize,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&Attribute> {
+        self.attrs.iter().find(|a| a.name == name)
+    }
+}
+
+/// Minimal, non-validating XML Parser
+///
+/// Handles elements, attributes, comments, processing instructions,
+///   CDATA sections, a document type declaration and the predefined and
+///   numeric character references
+struct XmlParser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { s, pos: 0 }
+    }
+    fn err(&self, message: &str) -> SvgError {
+        SvgError::Xml { offset: self.pos, message: message.to_string() }
+    }
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+    /// Skip past the next `end`
+    fn skip_past(&mut self, end: &str, message: &str) -> Result<(), SvgError> {
+        match self.rest().find(end) {
+            Some(i) => { self.pos += i + end.len(); Ok(()) },
+            None => Err(self.err(message)),
+        }
+    }
+    /// Skip comments, processing instructions and doctype
+    ///
+    /// Returns true if anything was skipped
+    fn misc(&mut self) -> Result<bool, SvgError> {
+        if self.rest().starts_with("<!--") {
+            self.skip_past("-->", "unterminated comment")?;
+        } else if self.rest().starts_with("<?") {
+            self.skip_past("?>", "unterminated processing instruction")?;
+        } else if self.rest().starts_with("<![CDATA[") {
+            self.skip_past("]]>", "unterminated CDATA section")?;
+        } else if self.rest().starts_with("<!") {
+            // Document type, possibly with an internal subset
+            let mut depth = 0;
+            for (i, c) in self.rest().char_indices() {
+                match c {
+                    '[' => depth += 1,
+                    ']' => depth -= 1,
+                    '>' if depth == 0 => {
+                        self.pos += i + 1;
+                        return Ok(true);
+                    },
+                    _ => {},
+                }
+            }
+            return Err(self.err("unterminated declaration"));
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+    fn document(&mut self) -> Result<Element, SvgError> {
+        loop {
+            self.skip_ws();
+            if ! self.misc()? {
+                break;
+            }
+        }
+        if ! self.rest().starts_with('<') {
+            return Err(self.err("expected root element"));
+        }
+        let root = self.element()?;
+        loop {
+            self.skip_ws();
+            if self.rest().is_empty() {
+                break;
+            }
+            if ! self.misc()? {
+                return Err(self.err("unexpected content after root element"));
+            }
+        }
+        Ok(root)
+    }
+    fn name(&mut self) -> Result<&'a str, SvgError> {
+        let rest = self.rest();
+        let n = rest.find(|c: char| c.is_whitespace() || "/>=<\"'".contains(c))
+            .unwrap_or(rest.len());
+        if n == 0 {
+            return Err(self.err("expected name"));
+        }
+        self.pos += n;
+        Ok(&rest[..n])
+    }
+    fn expect(&mut self, c: char) -> Result<(), SvgError> {
+        if ! self.rest().starts_with(c) {
+            return Err(self.err(&format!("expected {:?}", c)));
+        }
+        self.pos += c.len_utf8();
+        Ok(())
+    }
+    fn element(&mut self) -> Result<Element, SvgError> {
+        let offset = self.pos;
+        self.expect('<')?;
+        let tag = self.name()?;
+        let name = tag.rsplit(':').next().unwrap_or(tag).to_string();
+        let mut el = Element { name, offset, attrs: vec![], children: vec![] };
+        loop {
+            self.skip_ws();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(el);
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let name = self.name()?.to_string();
+            self.skip_ws();
+            self.expect('=')?;
+            self.skip_ws();
+            let quote = match self.rest().chars().next() {
+                Some(q) if q == '"' || q == '\'' => q,
+                _ => return Err(self.err("expected quoted attribute value")),
+            };
+            self.pos += 1;
+            let offset = self.pos;
+            let n = self.rest().find(quote)
+                .ok_or_else(|| self.err("unterminated attribute value"))?;
+            let value = decode_entities(&self.rest()[..n]);
+            self.pos += n + 1;
+            el.attrs.push(Attribute { name, value, offset });
+        }
+        loop {
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                if self.name()? != tag {
+                    return Err(self.err("mismatched end tag"));
+                }
+                self.skip_ws();
+                self.expect('>')?;
+                return Ok(el);
+            }
+            if self.misc()? {
+                continue;
+            }
+            if self.rest().starts_with('<') {
+                el.children.push(self.element()?);
+                continue;
+            }
+            // Text content is not rendered
+            match self.rest().find('<') {
+                Some(i) => self.pos += i,
+                None => return Err(self.err("unterminated element")),
+            }
+        }
+    }
+}
+
+/// Replace character and entity references
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let end = match rest.find(';') {
+            Some(end) => end,
+            None => break,
+        };
+        let c = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            r if r.starts_with("#x") => u32::from_str_radix(&r[2..], 16).ok()
+                .and_then(std::char::from_u32),
+            r if r.starts_with('#') => r[1..].parse::<u32>().ok()
+                .and_then(std::char::from_u32),
+            _ => None,
+        };
+        match c {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end+1..];
+            },
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_xml_test() {
+        let doc = r#"<?xml version="1.0"?>
+            <!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "x.dtd" [ <!ENTITY e "x"> ]>
+            <!-- comment -->
+            <svg:svg a='1 &amp; 2' b="&#65;&#x42;&lt;">
+              text <![CDATA[ <not> ]]>
+              <g><svg:path d="M0 0"/></g>
+            </svg:svg>
+            "#;
+        let root = XmlParser::new(doc).document().unwrap();
+        assert_eq!(root.name, "svg");
+        assert_eq!(root.attr("a").unwrap().value, "1 & 2");
+        assert_eq!(root.attr("b").unwrap().value, "AB<");
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].children[0].name, "path");
+
+        let e = XmlParser::new("<svg><g></svg>").document().unwrap_err();
+        assert_eq!(e, SvgError::Xml { offset: 13, message: "mismatched end tag".to_string() });
+        let e = XmlParser::new("<svg a=1/>").document().unwrap_err();
+        assert_eq!(e, SvgError::Xml { offset: 7, message: "expected quoted attribute value".to_string() });
+        assert!(XmlParser::new("<svg>").document().is_err());
+        assert!(XmlParser::new("<svg/><g/>").document().is_err());
+    }
+    #[test]
+    fn svg_values_test() {
+        assert_eq!(parse_paint("#f80"), Some(Paint::Color(Rgba8::new(255,136,0,255))));
+        assert_eq!(parse_paint("#102030"), Some(Paint::Color(Rgba8::new(16,32,48,255))));
+        assert_eq!(parse_paint("rgb(10, 20,100%)"), Some(Paint::Color(Rgba8::new(10,20,255,255))));
+        assert_eq!(parse_paint("rgba(10,20,30,0.5)"), Some(Paint::Color(Rgba8::new(10,20,30,128))));
+        assert_eq!(parse_paint("navy"), Some(Paint::Color(Rgba8::new(0,0,128,255))));
+        assert_eq!(parse_paint("none"), Some(Paint::None));
+        assert_eq!(parse_paint("url(#grad)"), None);
+        assert_eq!(parse_paint("#12"), None);
+
+        assert_eq!(parse_length("12"), Some(12.0));
+        assert_eq!(parse_length("12px"), Some(12.0));
+        assert_eq!(parse_length("1in"), Some(96.0));
+        assert_eq!(parse_length("1em"), None);
+
+        let t = parse_transform("translate(10,20) scale(2)").unwrap();
+        assert_eq!(t.transform(1.0, 1.0), (12.0, 22.0));
+        let t = parse_transform("rotate(90 10 10)").unwrap();
+        let (x, y) = t.transform(20.0, 10.0);
+        assert!((x - 10.0).abs() < 1e-9 && (y - 20.0).abs() < 1e-9);
+        let t = parse_transform("matrix(1 0 0 1 5 6), skewX(45)").unwrap();
+        let (x, y) = t.transform(0.0, 1.0);
+        assert!((x - 6.0).abs() < 1e-9 && (y - 7.0).abs() < 1e-9);
+        assert!(parse_transform("scale(1 2 3)").is_none());
+
+        assert_eq!(parse_aspect_ratio("xMinYMax slice"), Some((Some((0.0, 1.0)), true)));
+        assert_eq!(parse_aspect_ratio("none"), Some((None, false)));
+        assert_eq!(parse_aspect_ratio("xMinYBottom"), None);
+        assert_eq!(parse_aspect_ratio("xMéYMin"), None);
+        assert_eq!(parse_aspect_ratio("xMinYMé"), None);
+        assert_eq!(parse_aspect_ratio("x€Y€"), None);
+    }
+    #[test]
+    fn svg_document_test() {
+        let text = r#"<svg width="200" height="100" viewBox="0 0 20 20">
+              <title>Icon</title>
+              <g fill="url(#a)" style="stroke: red; stroke-width: 2">
+                <rect width="10" height="10"/>
+                <text>Hello</text>
+              </g>
+              <path d="M 0 0 L 1"/>
+            </svg>"#;
+        let err = SvgDocument::parse(text).unwrap_err();
+        let offset = text.find("M 0 0 L 1").unwrap() + 9;
+        assert_eq!(err, SvgError::PathData {
+            offset, error: SvgPathError::ExpectedNumber { offset: 9 }
+        });
+
+        let text = text.replace(r#"<path d="M 0 0 L 1"/>"#, "");
+        let doc = SvgDocument::parse(&text).unwrap();
+        assert_eq!((doc.width(), doc.height()), (200.0, 100.0));
+        assert_eq!(doc.unsupported(), &[
+            SvgUnsupported::Attribute { name: "fill".to_string(),
+                                        value: "url(#a)".to_string(),
+                                        offset: text.find("url").unwrap() },
+            SvgUnsupported::Element { name: "text".to_string(),
+                                      offset: text.find("<text").unwrap() },
+        ]);
+        assert_eq!(doc.items.len(), 1);
+        let shape = match &doc.items[0] {
+            Item::Shape(shape) => shape,
+            item => panic!("expected shape, got {:?}", item),
+        };
+        // Unsupported paint server without a fallback is none
+        assert_eq!(shape.fill, None);
+        assert_eq!(shape.stroke, Some(Rgba8::new(255,0,0,255)));
+        assert_eq!(shape.stroke_width, 2.0);
+        // viewBox is scaled by 5 and centered horizontally
+        assert_eq!(shape.transform.transform(10.0, 10.0), (100.0, 50.0));
+
+        let doc = SvgDocument::parse("<html/>");
+        assert_eq!(doc.unwrap_err(), SvgError::NotSvg { name: "html".to_string() });
+    }
+    #[test]
+    fn svg_unknown_attribute_test() {
+        // Unknown properties are reported, element and document attributes are not
+        let text = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="x" version="1.1">
+              <path id="p" data-x="1" d="M0 0 L 1 1" marker-end="url(#m)" style="font-size: 3"/>
+              <rect x="1" y="2" width="3" height="4" rx="1" cx="2"/>
+            </svg>"#;
+        let doc = SvgDocument::parse(text).unwrap();
+        assert_eq!(doc.unsupported(), &[
+            SvgUnsupported::Attribute { name: "marker-end".to_string(),
+                                        value: "url(#m)".to_string(),
+                                        offset: text.find("url").unwrap() },
+            SvgUnsupported::Attribute { name: "font-size".to_string(),
+                                        value: "3".to_string(),
+                                        offset: text.find("font").unwrap() },
+            SvgUnsupported::Attribute { name: "cx".to_string(),
+                                        value: "2".to_string(),
+                                        offset: text.rfind("\"2\"").unwrap() + 1 },
+        ]);
+        assert_eq!(doc.items.len(), 2);
+    }
+}
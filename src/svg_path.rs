@@ -0,0 +1,401 @@
+//! SVG Path Data
+//!
+//! Parse the `d` attribute of an SVG `<path>` element into a [`Path`]
+//!
+//! All commands are supported in absolute (upper case) and relative
+//!   (lower case) forms: moveto (M), lineto (L, H, V), cubic Bezier (C, S),
+//!   quadratic Bezier (Q, T), elliptical arc (A) and closepath (Z).
+//!   Curves are stored as control points, use [`ConvCurve`] to flatten
+//!   them before rendering.  Elliptical arcs are converted to cubic curves.
+//!
+//! # Example
+//!
+//!     let path = agg::parse_svg_path("M10,10 h80 v80 h-80 z").unwrap();
+//!     assert_eq!(path.vertices.len(), 5);
+//!
+//!     // Errors report the byte offset of the problem
+//!     let err = agg::parse_svg_path("M10,10 L20,x").unwrap_err();
+//!     assert_eq!(err.offset(), 11);
+//!
+//! [`Path`]: ../paths/struct.Path.html
+//! [`ConvCurve`]: ../curves/struct.ConvCurve.html
+
+use crate::paths::Path;
+
+use std::fmt;
+
+/// Error parsing SVG Path Data
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum SvgPathError {
+    /// Character is neither a command nor an argument
+    UnexpectedCharacter { offset: usize, c: char },
+    /// Command argument is missing or is not a number
+    ExpectedNumber { offset: usize },
+    /// Arc flag is missing or is not `0` or `1`
+    ExpectedFlag { offset: usize },
+    /// Path data must begin with a moveto command
+    MissingMoveTo { offset: usize },
+}
+
+impl SvgPathError {
+    /// Byte offset into the path data where the error occurred
+    pub fn offset(&self) -> usize {
+        match *self {
+            SvgPathError::UnexpectedCharacter { offset, .. } => offset,
+            SvgPathError::ExpectedNumber { offset } => offset,
+            SvgPathError::ExpectedFlag { offset } => offset,
+            SvgPathError::MissingMoveTo { offset } => offset,
+        }
+    }
+}
+
+impl fmt::Display for SvgPathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SvgPathError::UnexpectedCharacter { offset, c } =>
+                write!(f, "unexpected character {:?} at byte {}", c, offset),
+            SvgPathError::ExpectedNumber { offset } =>
+                write!(f, "expected number at byte {}", offset),
+            SvgPathError::ExpectedFlag { offset } =>
+                write!(f, "expected flag (0 or 1) at byte {}", offset),
+            SvgPathError::MissingMoveTo { offset } =>
+                write!(f, "path data must begin with a moveto at byte {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for SvgPathError {}
+
+/// Tokenizer over the path data
+struct Tokens<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { s, pos: 0 }
+    }
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).cloned()
+    }
+    fn at_end(&self) -> bool {
+        self.pos >= self.s.len()
+    }
+    fn skip_ws(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') | Some(b'\x0c') = self.peek() {
+            self.pos += 1;
+        }
+    }
+    /// Skip whitespace with an optional comma
+    fn skip_sep(&mut self) {
+        self.skip_ws();
+        if self.peek() == Some(b',') {
+            self.pos += 1;
+            self.skip_ws();
+        }
+    }
+    /// Character at the current position, for error reporting
+    fn current_char(&self) -> char {
+        self.s[self.pos..].chars().next().unwrap_or('\0')
+    }
+    /// Return if the next token looks like the start of a number
+    fn has_number(&self) -> bool {
+        match self.peek() {
+            Some(c) => c.is_ascii_digit() || c == b'.' || c == b'-' || c == b'+',
+            None => false,
+        }
+    }
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if ! c.is_ascii_digit() {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+    /// Read a number followed by an optional separator
+    fn number(&mut self) -> Result<f64, SvgPathError> {
+        self.skip_ws();
+        let start = self.pos;
+        if let Some(b'-') | Some(b'+') = self.peek() {
+            self.pos += 1;
+        }
+        let mut n = self.digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            n += self.digits();
+        }
+        if n == 0 {
+            self.pos = start;
+            return Err(SvgPathError::ExpectedNumber { offset: start });
+        }
+        // Exponent, only if followed by digits
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            let mark = self.pos;
+            self.pos += 1;
+            if let Some(b'-') | Some(b'+') = self.peek() {
+                self.pos += 1;
+            }
+            if self.digits() == 0 {
+                self.pos = mark;
+            }
+        }
+        let v = self.s[start..self.pos].parse::<f64>()
+            .map_err(|_| SvgPathError::ExpectedNumber { offset: start })?;
+        self.skip_sep();
+        Ok(v)
+    }
+    /// Read an arc flag followed by an optional separator
+    ///
+    /// Flags are a single character and need not be separated
+    fn flag(&mut self) -> Result<bool, SvgPathError> {
+        self.skip_ws();
+        let v = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(SvgPathError::ExpectedFlag { offset: self.pos }),
+        };
+        self.pos += 1;
+        self.skip_sep();
+        Ok(v)
+    }
+    fn point(&mut self) -> Result<(f64,f64), SvgPathError> {
+        Ok((self.number()?, self.number()?))
+    }
+}
+
+/// Previous segment, for the control point of smooth curves
+#[derive(Debug,Copy,Clone)]
+enum Previous {
+    Other,
+    Cubic(f64, f64),
+    Quad(f64, f64),
+}
+
+/// Parse SVG Path Data into a Path
+///
+/// Empty path data produces an empty path
+///
+/// See [SVG Path Data](https://www.w3.org/TR/SVG/paths.html#PathData)
+pub fn parse_svg_path(d: &str) -> Result<Path, SvgPathError> {
+    let mut path = Path::new();
+    let mut t = Tokens::new(d);
+
+    // Current point and start of the current sub-path
+    let (mut x, mut y) = (0.0, 0.0);
+    let (mut x0, mut y0) = (0.0, 0.0);
+    let mut prev = Previous::Other;
+    // A closepath without a following moveto starts a new sub-path
+    //   at the start of the previous one
+    let mut reopen = false;
+    let mut cmd : Option<u8> = None;
+
+    t.skip_ws();
+    while ! t.at_end() {
+        let offset = t.pos;
+        let c = t.peek().unwrap_or(0);
+        if c.is_ascii_alphabetic() {
+            t.pos += 1;
+            t.skip_ws();
+            if cmd.is_none() && c != b'M' && c != b'm' {
+                return Err(SvgPathError::MissingMoveTo { offset });
+            }
+            cmd = Some(c);
+        } else {
+            // Implicit repetition of the previous command
+            cmd = match cmd {
+                None =>
+                    return Err(SvgPathError::MissingMoveTo { offset }),
+                Some(b'Z') | Some(b'z') =>
+                    return Err(SvgPathError::UnexpectedCharacter { offset, c: t.current_char() }),
+                Some(b'M') => Some(b'L'),
+                Some(b'm') => Some(b'l'),
+                other => other,
+            };
+            if ! t.has_number() {
+                return Err(SvgPathError::UnexpectedCharacter { offset, c: t.current_char() });
+            }
+        }
+        let c = cmd.unwrap_or(0);
+        let rel = c.is_ascii_lowercase();
+        let (dx, dy) = if rel { (x, y) } else { (0.0, 0.0) };
+        let c = c.to_ascii_uppercase();
+
+        if c != b'M' && c != b'Z' && reopen {
+            path.move_to(x, y);
+        }
+        reopen = false;
+
+        match c {
+            b'M' => {
+                let (px, py) = t.point()?;
+                x = px + dx;
+                y = py + dy;
+                x0 = x;
+                y0 = y;
+                path.move_to(x, y);
+                prev = Previous::Other;
+            },
+            b'L' => {
+                let (px, py) = t.point()?;
+                x = px + dx;
+                y = py + dy;
+                path.line_to(x, y);
+                prev = Previous::Other;
+            },
+            b'H' => {
+                x = t.number()? + dx;
+                path.line_to(x, y);
+                prev = Previous::Other;
+            },
+            b'V' => {
+                y = t.number()? + dy;
+                path.line_to(x, y);
+                prev = Previous::Other;
+            },
+            b'C' | b'S' => {
+                let (x1, y1) = if c == b'C' {
+                    let (px, py) = t.point()?;
+                    (px + dx, py + dy)
+                } else if let Previous::Cubic(cx, cy) = prev {
+                    (x + x - cx, y + y - cy)
+                } else {
+                    (x, y)
+                };
+                let (x2, y2) = t.point()?;
+                let (px, py) = t.point()?;
+                let (x2, y2) = (x2 + dx, y2 + dy);
+                x = px + dx;
+                y = py + dy;
+                path.curve4(x1, y1, x2, y2, x, y);
+                prev = Previous::Cubic(x2, y2);
+            },
+            b'Q' | b'T' => {
+                let (x1, y1) = if c == b'Q' {
+                    let (px, py) = t.point()?;
+                    (px + dx, py + dy)
+                } else if let Previous::Quad(cx, cy) = prev {
+                    (x + x - cx, y + y - cy)
+                } else {
+                    (x, y)
+                };
+                let (px, py) = t.point()?;
+                x = px + dx;
+                y = py + dy;
+                path.curve3(x1, y1, x, y);
+                prev = Previous::Quad(x1, y1);
+            },
+            b'A' => {
+                let rx = t.number()?;
+                let ry = t.number()?;
+                let angle = t.number()?;
+                let large_arc = t.flag()?;
+                let sweep = t.flag()?;
+                let (px, py) = t.point()?;
+                x = px + dx;
+                y = py + dy;
+                path.arc_to(rx, ry, angle.to_radians(), large_arc, sweep, x, y);
+                prev = Previous::Other;
+            },
+            b'Z' => {
+                path.close_polygon();
+                x = x0;
+                y = y0;
+                reopen = true;
+                prev = Previous::Other;
+            },
+            _ => return Err(SvgPathError::UnexpectedCharacter {
+                offset, c: d[offset..].chars().next().unwrap_or('\0')
+            }),
+        }
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::PathCommand;
+
+    fn xyc(path: &Path) -> Vec<(f64,f64,PathCommand)> {
+        path.vertices.iter().map(|v| (v.x, v.y, v.cmd)).collect()
+    }
+
+    #[test]
+    fn svg_path_lines_test() {
+        use crate::paths::PathCommand::*;
+        let p = parse_svg_path("M10,10 L 20 10 h10 v10 H10 V15 z").unwrap();
+        assert_eq!(xyc(&p), vec![(10.0, 10.0, MoveTo),
+                                 (20.0, 10.0, LineTo),
+                                 (30.0, 10.0, LineTo),
+                                 (30.0, 20.0, LineTo),
+                                 (10.0, 20.0, LineTo),
+                                 (10.0, 15.0, LineTo),
+                                 (10.0, 15.0, Close)]);
+        // Implicit lineto after moveto, compact numbers
+        let p = parse_svg_path("m1-2 3.5.5 1e1,0Z l1,1").unwrap();
+        assert_eq!(xyc(&p), vec![(1.0, -2.0, MoveTo),
+                                 (4.5, -1.5, LineTo),
+                                 (14.5, -1.5, LineTo),
+                                 (14.5, -1.5, Close),
+                                 (1.0, -2.0, MoveTo),
+                                 (2.0, -1.0, LineTo)]);
+        assert!(parse_svg_path("").unwrap().vertices.is_empty());
+        assert!(parse_svg_path("  \n").unwrap().vertices.is_empty());
+    }
+    #[test]
+    fn svg_path_curves_test() {
+        use crate::paths::PathCommand::*;
+        let p = parse_svg_path("M0 0 C0 10 10 10 10 0 s10 -10 10 0 Q 25 10 30 0 t 10 0").unwrap();
+        assert_eq!(xyc(&p), vec![(0.0, 0.0, MoveTo),
+                                 (0.0, 10.0, Curve4),
+                                 (10.0, 10.0, Curve4),
+                                 (10.0, 0.0, Curve4),
+                                 (10.0, -10.0, Curve4),
+                                 (20.0, -10.0, Curve4),
+                                 (20.0, 0.0, Curve4),
+                                 (25.0, 10.0, Curve3),
+                                 (30.0, 0.0, Curve3),
+                                 (35.0, -10.0, Curve3),
+                                 (40.0, 0.0, Curve3)]);
+        // Smooth curve without a matching previous curve uses current point
+        let p = parse_svg_path("M0 0 Q 5 5 10 0 S 20 10 20 0").unwrap();
+        assert_eq!(xyc(&p)[3], (10.0, 0.0, Curve4));
+    }
+    #[test]
+    fn svg_path_arc_test() {
+        use crate::paths::PathCommand::*;
+        // Compact flags
+        let p = parse_svg_path("M0 0a10 10 0 0120 0").unwrap();
+        let v = xyc(&p);
+        assert_eq!(v.len(), 7);
+        assert!(v[1..].iter().all(|v| v.2 == Curve4));
+        assert_eq!(v[6], (20.0, 0.0, Curve4));
+        // Zero radius is a line
+        let p = parse_svg_path("M0 0 A0 10 0 0 1 20 0").unwrap();
+        assert_eq!(xyc(&p)[1], (20.0, 0.0, LineTo));
+    }
+    #[test]
+    fn svg_path_error_test() {
+        assert_eq!(parse_svg_path("L 10 10").unwrap_err(),
+                   SvgPathError::MissingMoveTo { offset: 0 });
+        assert_eq!(parse_svg_path("10 10").unwrap_err(),
+                   SvgPathError::MissingMoveTo { offset: 0 });
+        assert_eq!(parse_svg_path("M 10 10 L 20").unwrap_err(),
+                   SvgPathError::ExpectedNumber { offset: 12 });
+        assert_eq!(parse_svg_path("M 10 10 X 20").unwrap_err(),
+                   SvgPathError::UnexpectedCharacter { offset: 8, c: 'X' });
+        assert_eq!(parse_svg_path("M 10 10 # 20").unwrap_err(),
+                   SvgPathError::UnexpectedCharacter { offset: 8, c: '#' });
+        assert_eq!(parse_svg_path("M 0 0 A 1 1 0 2 0 1 1").unwrap_err(),
+                   SvgPathError::ExpectedFlag { offset: 14 });
+        assert_eq!(parse_svg_path("M 0 0 Z 1").unwrap_err(),
+                   SvgPathError::UnexpectedCharacter { offset: 8, c: '1' });
+        let e = parse_svg_path("M 10 10 L 20,.").unwrap_err();
+        assert_eq!(e.offset(), 13);
+        assert_eq!(e.to_string(), "expected number at byte 13");
+    }
+}
@@ -115,14 +115,14 @@ impl Mul<Transform> for Transform {
 
 /// Path Transform
 #[derive(Debug,Default)]
-pub struct ConvTransform {
+pub struct ConvTransform<T: VertexSource = Path> {
     /// Source Path to Transform
-    pub source: Path,
+    pub source: T,
     /// Transform to apply
     pub trans: Transform,
 }
 
-impl VertexSource for ConvTransform {
+impl<T> VertexSource for ConvTransform<T> where T: VertexSource {
     /// Apply the Transform
     fn xconvert(&self) -> Vec<Vertex<f64>> {
         self.transform()
@@ -130,9 +130,9 @@ impl VertexSource for ConvTransform {
 }
 
 
-impl ConvTransform {
+impl<T> ConvTransform<T> where T: VertexSource {
     /// Create a new Path Transform
-    pub fn new(source: Path, trans: Transform) -> Self {
+    pub fn new(source: T, trans: Transform) -> Self {
         Self { source, trans }
     }
     /// Transform the Path
@@ -0,0 +1,175 @@
+
+extern crate agg;
+use agg::Render;
+use agg::Pixel;
+
+use std::fs;
+
+fn parse_lion() -> (Vec<agg::Path>, Vec<agg::Rgba8>){
+    let txt = fs::read_to_string("tests/lion.txt").unwrap();
+    let mut paths = vec![];
+    let mut colors = vec![];
+    let mut path = agg::Path::new();
+    let mut color = agg::Rgba8::black();
+    let mut cmd = agg::PathCommand::Stop;
+
+    for line in txt.lines() {
+        let v : Vec<_> = line.split_whitespace().collect();
+        if v.len() == 1 {
+            let n = 0;
+            let hex = v[0];
+            let r = u8::from_str_radix(&hex[n..n+2],16).unwrap();
+            let g = u8::from_str_radix(&hex[n+2..n+4],16).unwrap();
+            let b = u8::from_str_radix(&hex[n+4..n+6],16).unwrap();
+            if ! path.vertices.is_empty() {
+                path.close_polygon();
+                paths.push(path);
+                colors.push(color);
+            }
+            path = agg::Path::new();
+            color =  agg::Rgba8::new(r,g,b,255);
+        } else {
+            for val in v {
+                if val == "M" {
+                    cmd = agg::PathCommand::MoveTo;
+                } else if val == "L" {
+                    cmd = agg::PathCommand::LineTo;
+                } else {
+                    let pts : Vec<_> = val.split(",")
+                        .map(|x| x.parse::<f64>().unwrap())
+                        .collect();
+
+                    match cmd {
+                        agg::PathCommand::LineTo =>
+                            path.line_to(pts[0], pts[1]),
+                        agg::PathCommand::MoveTo => {
+                            path.close_polygon();
+                            path.move_to(pts[0], pts[1]);
+                        }
+                        _ => unreachable!("oh no !!!"),
+                    }
+                }
+            }
+        }
+    }
+    if ! path.vertices.is_empty() {
+        colors.push(color);
+        path.close_polygon();
+        paths.push(path);
+    }
+    assert_eq!(paths.len(), colors.len());
+    paths.iter_mut().for_each(|p| p.arrange_orientations(agg::PathOrientation::Clockwise));
+    (paths, colors)
+}
+
+/// Lion paths centered in an image of `w` x `h`
+fn lion_paths(w: usize, h: usize) -> (Vec<agg::ConvTransform>, Vec<agg::Rgba8>) {
+    let (paths, colors) = parse_lion();
+
+    let p = paths[0].vertices[0];
+    let mut r = agg::Rectangle::new(p.x,p.y,p.x,p.y);
+    for p in &paths {
+        if let Some(rp) = agg::bounding_rect(p) {
+            r.expand_rect(&rp);
+        }
+    }
+    let g_base_dx = (r.x2() - r.x1())/2.0;
+    let g_base_dy = (r.y2() - r.y1())/2.0;
+    let mut mtx = agg::Transform::new();
+    mtx.translate(-g_base_dx, -g_base_dy);
+    mtx.translate((w/2) as f64, (h/2) as f64);
+    let t : Vec<_> = paths.into_iter()
+        .map(|p| agg::ConvTransform::new(p, mtx))
+        .collect();
+    (t, colors)
+}
+
+/// Render the anti-aliased lion, centered
+fn lion<T: agg::Pixel>(ren_base: &mut agg::RenderingBase<T>) {
+    let (t, colors) = lion_paths(ren_base.pixf.width(), ren_base.pixf.height());
+    let mut ras = agg::RasterizerScanline::new();
+    let mut ren = agg::RenderingScanlineAASolid::with_base(ren_base);
+    agg::render_all_paths(&mut ras, &mut ren, &t, &colors);
+}
+
+/// Render a gray mask of overlapping ellipses
+///
+/// Positions, sizes and values come from a fixed linear congruential generator
+fn mask<T: agg::Pixel>(pixf: T) -> T {
+    let (w,h) = (pixf.width() as f64, pixf.height() as f64);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear(agg::Rgba8::black());
+    let mut ras = agg::RasterizerScanline::new();
+    let mut seed = 12345u32;
+    let mut rand = |n: u32| {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        (seed >> 16) % n
+    };
+    for _ in 0 .. 20 {
+        let x = f64::from(rand(w as u32));
+        let y = f64::from(rand(h as u32));
+        let rx = f64::from(rand(100)) + 20.0;
+        let ry = f64::from(rand(100)) + 20.0;
+        let v = rand(256) as u8;
+        ras.reset();
+        ras.add_path(&agg::Ellipse::new(x, y, rx, ry, 100));
+        let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+        ren.color(agg::Rgba8::new(v, v, v, 128 + rand(128) as u8));
+        agg::render_scanlines(&mut ras, &mut ren);
+    }
+    ren_base.pixf
+}
+
+#[test]
+fn alpha_mask2() {
+    let (w,h) = (400,400);
+    let alpha = mask(agg::Pixfmt::<agg::Gray8>::new(w,h));
+
+    let mut pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    pixf.fill(agg::Rgb8::white());
+    let mix = agg::AlphaMaskAdaptor::new(pixf, agg::AlphaMaskU8::new(alpha));
+    let mut ren_base = agg::RenderingBase::new(mix);
+    lion(&mut ren_base);
+
+    ren_base.to_file("tests/tmp/alpha_mask2.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/alpha_mask2.png", "images/alpha_mask2.png").unwrap());
+}
+
+#[test]
+fn alpha_mask2_rgb_channel() {
+    let (w,h) = (400,400);
+    // Mask in the green channel of an Rgb image, same values as a gray mask
+    let alpha = mask(agg::Pixfmt::<agg::Rgb8>::new(w,h));
+    let mut pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    pixf.fill(agg::Rgb8::white());
+    let mix = agg::AlphaMaskAdaptor::new(pixf, agg::AlphaMaskNoClipU8::with_channel(alpha, 1));
+    let mut ren_base = agg::RenderingBase::new(mix);
+    lion(&mut ren_base);
+
+    ren_base.to_file("tests/tmp/alpha_mask2_rgb.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/alpha_mask2_rgb.png", "images/alpha_mask2.png").unwrap());
+}
+
+#[test]
+fn alpha_mask2_scanline() {
+    let (w,h) = (400,400);
+    let alpha = mask(agg::Pixfmt::<agg::Gray8>::new(w,h));
+
+    // Masking the scanline covers matches masking the pixel format
+    let mut pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    pixf.fill(agg::Rgb8::white());
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    let mut sl = agg::ScanlineU8Am::new(agg::AlphaMaskU8::new(alpha));
+    let (t, colors) = lion_paths(w, h);
+    let mut ras = agg::RasterizerScanline::new();
+    let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+    for (path, &color) in t.iter().zip(colors.iter()) {
+        ras.reset();
+        ras.add_path(path);
+        ren.color(color);
+        agg::render_scanlines_am(&mut ras, &mut ren, &mut sl);
+    }
+
+    ren_base.to_file("tests/tmp/alpha_mask2_scanline.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/alpha_mask2_scanline.png", "images/alpha_mask2.png").unwrap());
+}
@@ -0,0 +1,102 @@
+
+extern crate agg;
+use agg::Render;
+use agg::Pixel;
+use agg::Source;
+
+/// Sprite of a translucent circle on a transparent background
+fn sprite() -> agg::Pixfmt<agg::Rgba8> {
+    let mut pixf = agg::Pixfmt::<agg::Rgba8>::new(60, 60);
+    pixf.fill(agg::Rgba8::new(0, 0, 0, 0));
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    let mut ras = agg::RasterizerScanline::new();
+    ras.add_path(&agg::Ellipse::new(30.0, 30.0, 28.0, 28.0, 64));
+    let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+    ren.color(agg::Rgba8::new(200, 30, 30, 200));
+    agg::render_scanlines(&mut ras, &mut ren);
+    ras.reset();
+    ras.add_path(&agg::Ellipse::new(30.0, 30.0, 12.0, 12.0, 64));
+    ren.color(agg::Rgba8::new(250, 220, 40, 255));
+    agg::render_scanlines(&mut ras, &mut ren);
+    ren_base.pixf
+}
+
+#[test]
+fn blit() {
+    let (w,h) = (300,200);
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::white() );
+    for y in (0 .. h as i64).step_by(20) {
+        ren_base.blend_hline(0, y, w as i64 - 1, agg::Rgba8::new(40, 40, 160, 255), 255);
+    }
+
+    let sprite = sprite();
+    // Copy replaces pixels, including the transparent background
+    ren_base.copy_from(&sprite, None, 10, 10);
+    // Blend with the sprite alpha and a global alpha
+    ren_base.blend_from(&sprite, None, 90, 10, 255);
+    ren_base.blend_from(&sprite, None, 170, 10, 128);
+    // Only the bottom right quarter
+    ren_base.blend_from(&sprite, Some(agg::Rectangle::new(30, 30, 59, 59)), 220, 100, 255);
+    // Clipped at the image edges
+    ren_base.blend_from(&sprite, None, -30, 150, 255);
+    ren_base.blend_from(&sprite, None, 270, -30, 255);
+    // Entirely outside
+    ren_base.blend_from(&sprite, None, 400, 400, 255);
+
+    assert_eq!(ren_base.pixf.get((11, 11)), agg::Rgba8::new(0, 0, 0, 255));
+    assert_eq!(ren_base.pixf.get((120, 40)), agg::Rgba8::new(250, 220, 40, 255));
+    assert_eq!(ren_base.pixf.get((255, 135)), agg::Rgba8::new(250, 220, 40, 255));
+    assert_eq!(ren_base.pixf.get((245, 125)), agg::Rgba8::white());
+
+    ren_base.to_file("tests/tmp/blit.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/blit.png", "images/blit.png").unwrap());
+}
+
+#[test]
+fn blit_premultiplied() {
+    // Half transparent red
+    let mut sprite = agg::Pixfmt::<agg::Rgba8pre>::new(2, 2);
+    sprite.fill(agg::Rgba8pre::new(128, 0, 0, 128));
+
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(4, 4));
+    ren_base.clear(agg::Rgba8::white());
+    ren_base.blend_from(&sprite, None, 0, 0, 255);
+    ren_base.copy_from(&sprite, None, 2, 2);
+    assert_eq!(ren_base.pixf.get((1, 1)), agg::Rgba8::new(255, 127, 127, 255));
+    assert_eq!(ren_base.pixf.get((3, 3)), agg::Rgba8::new(255, 0, 0, 255));
+
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgba8>::new(2, 2));
+    ren_base.copy_from(&sprite, None, 0, 0);
+    assert_eq!(ren_base.pixf.get((0, 0)), agg::Rgba8::new(255, 0, 0, 128));
+
+    // Straight alpha onto premultiplied, and back
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgba8pre>::new(2, 2));
+    let mut straight = agg::Pixfmt::<agg::Rgba8>::new(2, 2);
+    straight.fill(agg::Rgba8::new(255, 0, 0, 128));
+    ren_base.copy_from(&straight, None, 0, 0);
+    assert!(ren_base.pixels().all(|c| c == agg::Rgba8pre::new(128, 0, 0, 128)));
+    ren_base.copy_from(&sprite, None, 0, 0);
+    assert!(ren_base.pixels().all(|c| c == agg::Rgba8pre::new(128, 0, 0, 128)));
+}
+
+#[test]
+fn blit_16bit() {
+    let c = agg::Rgba16::new(0x1234, 0x5678, 0x9abc, 0xffff);
+    let mut src = agg::Pixfmt::<agg::Rgba16>::new(2, 2);
+    src.fill(c);
+
+    // Same format is copied unchanged
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgba16>::new(4, 4));
+    ren_base.copy_from(&src, None, 0, 0);
+    assert_eq!(ren_base.pixf.raw((1, 1)), c);
+    // Opaque blends keep the full precision
+    ren_base.blend_from(&src, None, 2, 2, 255);
+    assert_eq!(ren_base.pixf.raw((3, 3)), c);
+
+    // Other 16-bit formats are converted without dropping to 8 bits
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb16>::new(2, 2));
+    ren_base.copy_from(&src, None, 0, 0);
+    assert_eq!(ren_base.pixf.raw((0, 0)), agg::Rgb16::new(0x1234, 0x5678, 0x9abc));
+}
@@ -0,0 +1,33 @@
+
+extern crate agg;
+
+mod common;
+use common::lion;
+
+#[test]
+fn channel_order_bgr() {
+    let ren_base = lion(agg::Pixfmt::<agg::Bgr8>::new(400,400));
+    ren_base.to_file("tests/tmp/channel_order_bgr.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/channel_order_bgr.png", "images/lion_cw_aa.png").unwrap());
+}
+
+#[test]
+fn channel_order_bgra() {
+    let ren_base = lion(agg::Pixfmt::<agg::Bgra8>::new(400,400));
+    ren_base.to_file("tests/tmp/channel_order_bgra.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/channel_order_bgra.png", "images/lion_cw_aa.png").unwrap());
+}
+
+#[test]
+fn channel_order_argb() {
+    let ren_base = lion(agg::Pixfmt::<agg::Argb8>::new(400,400));
+    ren_base.to_file("tests/tmp/channel_order_argb.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/channel_order_argb.png", "images/lion_cw_aa.png").unwrap());
+}
+
+#[test]
+fn channel_order_abgr() {
+    let ren_base = lion(agg::Pixfmt::<agg::Abgr8>::new(400,400));
+    ren_base.to_file("tests/tmp/channel_order_abgr.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/channel_order_abgr.png", "images/lion_cw_aa.png").unwrap());
+}
@@ -0,0 +1,64 @@
+
+extern crate agg;
+use agg::Render;
+use agg::Source;
+
+#[test]
+fn clip_box() {
+    let (w,h) = (300,200);
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::white() );
+
+    // Frame around the plot area, drawn partially off-canvas
+    let gray = agg::Rgba8::new(160, 160, 160, 255);
+    ren_base.blend_bar(-20, -20, 320, 29, gray, 255);
+    ren_base.copy_hline(-50, 170, 400, agg::Rgba8::black());
+    ren_base.copy_vline(39, -10, 250, agg::Rgba8::black());
+    ren_base.copy_pixel(-1, 5, agg::Rgba8::black());
+
+    // Restrict drawing to the plot area
+    assert!(ren_base.clip_box(40, 30, 279, 169));
+
+    let mut ras = agg::RasterizerScanline::new();
+    ras.add_path(&agg::Ellipse::new(260.0, 40.0, 90.0, 70.0, 128));
+    let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+    ren.color(agg::Rgba8::new(200, 30, 30, 160));
+    agg::render_scanlines(&mut ras, &mut ren);
+
+    let mut path = agg::Path::new();
+    path.move_to(-40.0, 220.0);
+    for i in 1 .. 8 {
+        let x = -40.0 + 60.0 * f64::from(i);
+        let y = if i % 2 == 0 { 220.0 } else { -20.0 };
+        path.line_to(x, y);
+    }
+    let mut stroke = agg::Stroke::new(path);
+    stroke.width(6.0);
+    ras.reset();
+    ras.add_path(&stroke);
+    ren.color(agg::Rgba8::new(30, 30, 200, 255));
+    agg::render_scanlines(&mut ras, &mut ren);
+
+    ren_base.blend_bar(250, 140, 400, 300, agg::Rgba8::new(30, 160, 30, 255), 128);
+    ren_base.blend_pixel(279, 169, agg::Rgba8::black(), 255);
+    ren_base.blend_pixel(280, 170, agg::Rgba8::new(255, 0, 0, 255), 255);
+
+    // Nothing is drawn outside of the plot area
+    assert_eq!(ren_base.pixf.get((20, 100)), agg::Rgba8::white());
+    assert_eq!(ren_base.pixf.get((290, 180)), agg::Rgba8::white());
+    assert_eq!(ren_base.pixf.get((280, 170)), agg::Rgba8::black());
+    assert_eq!(ren_base.pixf.get((290, 10)), gray);
+    assert_eq!(ren_base.pixf.get((279, 169)), agg::Rgba8::black());
+
+    // Nothing is drawn at all
+    ren_base.reset_clipping(false);
+    ren_base.copy_bar(0, 0, 299, 199, agg::Rgba8::black());
+    assert_eq!(ren_base.pixf.get((20, 100)), agg::Rgba8::white());
+
+    ren_base.reset_clipping(true);
+    assert!(ren_base.inbox(0, 0) && ren_base.inbox(299, 199));
+
+    ren_base.to_file("tests/tmp/clip_box.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/clip_box.png", "images/clip_box.png").unwrap());
+}
@@ -0,0 +1,58 @@
+
+extern crate agg;
+use agg::Render;
+use agg::Pixel;
+
+/// Fill a circle with a solid color using the current compositing operation
+fn circle(ren_base: &mut agg::RenderingBase<agg::PixfmtCustomBlendRgba>,
+          ras: &mut agg::RasterizerScanline,
+          x: f64, y: f64, r: f64, color: agg::Rgba8) {
+    ras.reset();
+    ras.add_path(&agg::Ellipse::new(x, y, r, r, 64));
+    let mut ren = agg::RenderingScanlineAASolid::with_base(ren_base);
+    ren.color(color);
+    agg::render_scanlines(ras, &mut ren);
+}
+
+#[test]
+fn comp_op() {
+    let ops = [
+        agg::CompOp::Clear,      agg::CompOp::Src,        agg::CompOp::Dst,
+        agg::CompOp::SrcOver,    agg::CompOp::DstOver,    agg::CompOp::SrcIn,
+        agg::CompOp::DstIn,      agg::CompOp::SrcOut,     agg::CompOp::DstOut,
+        agg::CompOp::SrcAtop,    agg::CompOp::DstAtop,    agg::CompOp::Xor,
+        agg::CompOp::Plus,       agg::CompOp::Multiply,   agg::CompOp::Screen,
+        agg::CompOp::Overlay,    agg::CompOp::Darken,     agg::CompOp::Lighten,
+        agg::CompOp::ColorDodge, agg::CompOp::ColorBurn,  agg::CompOp::HardLight,
+        agg::CompOp::SoftLight,  agg::CompOp::Difference, agg::CompOp::Exclusion,
+    ];
+    let (w,h) = (600,400);
+
+    // Transparent image, each tile is composited independently
+    let pixf = agg::PixfmtCustomBlendRgba::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(0, 0, 0, 0) );
+
+    let mut ras = agg::RasterizerScanline::new();
+    let dst = agg::Rgba8::new(30, 110, 220, 220);
+    let src = agg::Rgba8::new(240, 120, 40, 200);
+
+    for (i, &op) in ops.iter().enumerate() {
+        let x = (i % 6) as f64 * 100.0;
+        let y = (i / 6) as f64 * 100.0;
+        ren_base.pixf.set_comp_op(agg::CompOp::SrcOver);
+        circle(&mut ren_base, &mut ras, x + 40.0, y + 40.0, 30.0, dst);
+        ren_base.pixf.set_comp_op(op);
+        assert_eq!(ren_base.pixf.comp_op(), op);
+        circle(&mut ren_base, &mut ras, x + 60.0, y + 60.0, 30.0, src);
+    }
+
+    // Place everything over a white background
+    ren_base.pixf.set_comp_op(agg::CompOp::DstOver);
+    for y in 0 .. h as i64 {
+        ren_base.blend_hline(0, y, w as i64 - 1, agg::Rgba8::white(), 255);
+    }
+
+    ren_base.pixf.drop_alpha().to_file("tests/tmp/comp_op.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/comp_op.png", "images/comp_op.png").unwrap());
+}
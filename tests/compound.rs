@@ -0,0 +1,229 @@
+
+extern crate agg;
+use agg::Source;
+use agg::SpanGenerator;
+
+const N : usize = 12;
+
+/// Add wedges of a circle, each with its own style, to a compound rasterizer
+fn wedges(ras: &mut agg::RasterizerCompound, cx: f64, cy: f64, r: f64) {
+    let pt = |i: usize| {
+        let a = 2.0 * std::f64::consts::PI * i as f64 / N as f64;
+        (cx + r * a.cos(), cy + r * a.sin())
+    };
+    for i in 0 .. N {
+        let (x1, y1) = pt(i);
+        let (x2, y2) = pt(i + 1);
+        ras.styles(i as i64, -1);
+        ras.move_to(cx, cy);
+        ras.line_to(x1, y1);
+        ras.line_to(x2, y2);
+        ras.close_polygon();
+    }
+}
+
+/// Same wedges as paths
+fn wedge_paths(cx: f64, cy: f64, r: f64) -> Vec<agg::Path> {
+    let pt = |i: usize| {
+        let a = 2.0 * std::f64::consts::PI * i as f64 / N as f64;
+        (cx + r * a.cos(), cy + r * a.sin())
+    };
+    (0 .. N).map(|i| {
+        let mut path = agg::Path::new();
+        let (x1, y1) = pt(i);
+        let (x2, y2) = pt(i + 1);
+        path.move_to(cx, cy);
+        path.line_to(x1, y1);
+        path.line_to(x2, y2);
+        path.close_polygon();
+        path
+    }).collect()
+}
+
+/// Solid colors, with styles after the colors a radial gradient
+struct Styles {
+    colors: Vec<agg::Rgba8>,
+    gradient: agg::SpanGradient<agg::GradientRadialD>,
+}
+
+impl agg::StyleHandler for Styles {
+    fn is_solid(&self, style: usize) -> bool {
+        style < self.colors.len()
+    }
+    fn color(&self, style: usize) -> agg::Rgba8 {
+        self.colors[style]
+    }
+    fn generate_span(&mut self, x: i64, y: i64, len: usize, _style: usize, span: &mut [agg::Rgba8]) {
+        self.gradient.generate(x, y, len, span);
+    }
+}
+
+#[test]
+fn compound_seams() {
+    let c = agg::Rgba8::new(40, 80, 200, 255);
+    let inside = |x: usize, y: usize| {
+        let (dx, dy) = (x as f64 - 50.0, y as f64 - 50.0);
+        dx * dx + dy * dy < 40.0 * 40.0
+    };
+    // Shared edges may differ from the color by rounding
+    let seam = |p: agg::Rgba8| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).abs();
+        d(p.r, c.r) > 1 || d(p.g, c.g) > 1 || d(p.b, c.b) > 1
+    };
+
+    // One path at a time, shared edges show the background
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(100,100));
+    ren_base.clear(agg::Rgba8::white());
+    let mut ras = agg::RasterizerScanline::new();
+    let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+    agg::render_all_paths(&mut ras, &mut ren, &wedge_paths(50.0, 50.0, 45.0), &[c; N]);
+    let seams = (0 .. 100).flat_map(|y| (0 .. 100).map(move |x| (x,y)))
+        .filter(|&(x,y)| inside(x,y) && seam(ren_base.pixf.get((x,y))))
+        .count();
+    assert!(seams > 10, "{}", seams);
+
+    // All styles at once, no seams
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(100,100));
+    ren_base.clear(agg::Rgba8::white());
+    let mut ras = agg::RasterizerCompound::new();
+    wedges(&mut ras, 50.0, 50.0, 45.0);
+    assert_eq!(ras.min_style(), Some(0));
+    assert_eq!(ras.max_style(), Some(N - 1));
+    agg::render_scanlines_compound(&mut ras, &mut ren_base, &mut [c; N][..]);
+    for y in 0 .. 100 {
+        for x in 0 .. 100 {
+            if inside(x,y) {
+                assert!(! seam(ren_base.pixf.get((x,y))), "seam at {} {}", x, y);
+            }
+        }
+    }
+}
+
+#[test]
+fn compound() {
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(300,200));
+    ren_base.clear(agg::Rgba8::white());
+
+    let mut lut = agg::GradientLut::new();
+    lut.add_color(0.0, agg::Rgba8::new(250, 220, 40, 255));
+    lut.add_color(1.0, agg::Rgba8::new(200, 30, 30, 255));
+    lut.build_lut();
+    let mut mtx = agg::Transform::new_translate(150.0, 100.0);
+    mtx.invert();
+    let gradient = agg::SpanGradient::new(mtx, agg::GradientRadialD{}, lut.colors(), 35.0, 60.0);
+    let colors = (0 .. N)
+        .map(|i| agg::Rgba8::from_wavelength_gamma(380.0 + 300.0 * i as f64 / N as f64, 0.8))
+        .collect();
+    let mut styles = Styles { colors, gradient };
+
+    let mut ras = agg::RasterizerCompound::new();
+    wedges(&mut ras, 150.0, 100.0, 90.0);
+    // Gradient ring over the wedges
+    ras.styles(N as i64, -1);
+    ras.add_path(&agg::Ellipse::new(150.0, 100.0, 60.0, 60.0, 64));
+    ras.styles(-1, N as i64);
+    ras.add_path(&agg::Ellipse::new(150.0, 100.0, 35.0, 35.0, 64));
+    ras.set_layer_order(agg::LayerOrder::Inverse);
+    assert_eq!(ras.layer_order(), agg::LayerOrder::Inverse);
+
+    agg::render_scanlines_compound(&mut ras, &mut ren_base, &mut styles);
+
+    ren_base.to_file("tests/tmp/compound.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/compound.png", "images/compound.png").unwrap());
+}
+
+#[test]
+fn compound_reuse() {
+    let square = |x: f64| {
+        let mut path = agg::Path::new();
+        path.move_to(x, 10.0);
+        path.line_to(x + 20.0, 10.0);
+        path.line_to(x + 20.0, 30.0);
+        path.line_to(x, 30.0);
+        path.close_polygon();
+        path
+    };
+    let mut colors = [agg::Rgba8::new(200, 30, 30, 255); 6];
+    colors[5] = agg::Rgba8::new(30, 30, 200, 255);
+    let new_base = || {
+        let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(60,40));
+        ren_base.clear(agg::Rgba8::white());
+        ren_base
+    };
+
+    let mut ras = agg::RasterizerCompound::new();
+    let mut ren_base = new_base();
+    ras.styles(0, -1);
+    ras.add_path(&square(5.5));
+    agg::render_scanlines_compound(&mut ras, &mut ren_base, &mut colors[..]);
+
+    // Second frame with the same rasterizer, styles set before adding paths
+    let mut ren_base = new_base();
+    ras.styles(0, -1);
+    ras.add_path(&square(5.5));
+    ras.styles(5, -1);
+    ras.add_path(&square(25.5));
+    agg::render_scanlines_compound(&mut ras, &mut ren_base, &mut colors[..]);
+
+    let mut expected = new_base();
+    let mut ras = agg::RasterizerCompound::new();
+    ras.styles(0, -1);
+    ras.add_path(&square(5.5));
+    ras.styles(5, -1);
+    ras.add_path(&square(25.5));
+    agg::render_scanlines_compound(&mut ras, &mut expected, &mut colors[..]);
+
+    assert!(ren_base.pixels().eq(expected.pixels()));
+    assert_eq!(ren_base.pixf.get((15, 20)), colors[0]);
+    assert_eq!(ren_base.pixf.get((35, 20)), colors[5]);
+
+    // Edges without styles after a reset are not drawn
+    ras.reset();
+    ras.add_path(&square(5.5));
+    let mut ren_base = new_base();
+    agg::render_scanlines_compound(&mut ras, &mut ren_base, &mut colors[..]);
+    assert_eq!(ren_base.pixf.get((15, 20)), agg::Rgba8::white());
+}
+
+#[test]
+fn compound_layers() {
+    let rect = |x1: f64, y1: f64, x2: f64, y2: f64| {
+        let mut path = agg::Path::new();
+        path.move_to(x1, y1);
+        path.line_to(x2, y1);
+        path.line_to(x2, y2);
+        path.line_to(x1, y2);
+        path.close_polygon();
+        path
+    };
+    // Opaque layer, with a translucent, partially covering layer over it
+    let bottom = rect(5.0, 5.0, 55.0, 35.0);
+    let top = rect(10.3, 8.6, 40.7, 30.2);
+    let mut colors = [agg::Rgba8::new(30, 60, 200, 128), agg::Rgba8::new(200, 30, 30, 255)];
+
+    let mut expected = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(60,40));
+    expected.clear(agg::Rgba8::white());
+    let mut ras = agg::RasterizerScanline::new();
+    let mut ren = agg::RenderingScanlineAASolid::with_base(&mut expected);
+    agg::render_all_paths(&mut ras, &mut ren, &[bottom.clone(), top.clone()],
+                           &[colors[1], colors[0]]);
+
+    // Lower styles are drawn last
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(60,40));
+    ren_base.clear(agg::Rgba8::white());
+    let mut ras = agg::RasterizerCompound::new();
+    ras.styles(1, -1);
+    ras.add_path(&bottom);
+    ras.styles(0, -1);
+    ras.add_path(&top);
+    agg::render_scanlines_compound(&mut ras, &mut ren_base, &mut colors[..]);
+
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).abs();
+    for y in 0 .. 40 {
+        for x in 0 .. 60 {
+            let (p, q) = (ren_base.pixf.get((x,y)), expected.pixf.get((x,y)));
+            assert!(d(p.r, q.r) <= 2 && d(p.g, q.g) <= 2 && d(p.b, q.b) <= 2,
+                    "{} {}: {:?} {:?}", x, y, p, q);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+
+extern crate agg;
+use agg::Render;
+
+#[test]
+fn curves() {
+    let (w,h) = (300,200);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+
+    // Filled shape with quadratic and smooth cubic curves
+    let mut path = agg::Path::new();
+    path.move_to(20.0, 100.0);
+    path.curve3(70.0, 10.0, 120.0, 100.0);
+    path.curve4(140.0, 130.0, 100.0, 180.0, 70.0, 180.0);
+    path.curve4_smooth(20.0, 130.0, 20.0, 100.0);
+    path.close_polygon();
+    let curve = agg::ConvCurve::new(path);
+    ras.add_path(&curve);
+    {
+        let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+        ren.color(agg::Rgba8::new(200,60,40,255));
+        agg::render_scanlines(&mut ras, &mut ren);
+    }
+
+    // Stroked curves, adaptive and incremental
+    let methods = [agg::CurveApproximationMethod::Div,
+                   agg::CurveApproximationMethod::Inc];
+    for (i, method) in methods.iter().enumerate() {
+        let dy = 90.0 * i as f64;
+        let mut path = agg::Path::new();
+        path.move_to(160.0, 80.0 + dy);
+        path.curve4(160.0, 10.0 + dy, 280.0, 100.0 + dy, 280.0, 20.0 + dy);
+        path.curve3_smooth(200.0, 60.0 + dy);
+        let mut curve = agg::ConvCurve::new(path);
+        curve.approximation_method(*method);
+        let mut stroke = agg::Stroke::new(curve);
+        stroke.width(3.0);
+        stroke.line_join(agg::LineJoin::Round);
+        ras.reset();
+        ras.add_path(&stroke);
+        let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+        ren.color(agg::Rgba8::new(0,0,0,255));
+        agg::render_scanlines(&mut ras, &mut ren);
+    }
+
+    ren_base.to_file("tests/tmp/curves.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/curves.png", "images/curves.png").unwrap());
+}
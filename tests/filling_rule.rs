@@ -0,0 +1,75 @@
+
+extern crate agg;
+use agg::Render;
+
+/// Five pointed star, self-intersecting, and a donut with both
+///   circles drawn in the same direction
+fn shapes(dx: f64) -> agg::Path {
+    let mut path = agg::Path::new();
+    let (cx, cy, r) = (dx + 50.0, 55.0, 45.0);
+    for i in 0 .. 5 {
+        let a = std::f64::consts::PI * (-0.5 + 0.8 * f64::from(i));
+        let (x, y) = (cx + r * a.cos(), cy + r * a.sin());
+        if i == 0 {
+            path.move_to(x, y);
+        } else {
+            path.line_to(x, y);
+        }
+    }
+    path.close_polygon();
+    let (cy, n) = (150.0, 64);
+    for &r in [40.0, 20.0].iter() {
+        for i in 0 .. n {
+            let a = 2.0 * std::f64::consts::PI * f64::from(i) / f64::from(n);
+            let (x, y) = (cx + r * a.cos(), cy + r * a.sin());
+            if i == 0 {
+                path.move_to(x, y);
+            } else {
+                path.line_to(x, y);
+            }
+        }
+        path.close_polygon();
+    }
+    path
+}
+
+/// Render with render_scanlines, render_scanlines_aa_solid and
+///   render_scanlines_bin_solid, left to right
+fn draw(rule: agg::FillingRule, filename: &str) {
+    let (w,h) = (300,200);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+    assert_eq!(ras.filling_rule(), agg::FillingRule::NonZero);
+    ras.set_filling_rule(rule);
+    assert_eq!(ras.filling_rule(), rule);
+
+    let color = agg::Rgba8::new(40, 90, 160, 255);
+    ras.add_path(&shapes(0.0));
+    {
+        let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+        ren.color(color);
+        agg::render_scanlines(&mut ras, &mut ren);
+    }
+    ras.add_path(&shapes(100.0));
+    agg::render_scanlines_aa_solid(&mut ras, &mut ren_base, color);
+    ras.add_path(&shapes(200.0));
+    agg::render_scanlines_bin_solid(&mut ras, &mut ren_base, color);
+
+    let (out, reference) = (format!("tests/tmp/{}", filename), format!("images/{}", filename));
+    ren_base.to_file(&out).unwrap();
+    assert!(agg::ppm::img_diff(&out, &reference).unwrap());
+}
+
+#[test]
+fn filling_rule_non_zero() {
+    draw(agg::FillingRule::NonZero, "filling_rule_non_zero.png");
+}
+
+#[test]
+fn filling_rule_even_odd() {
+    draw(agg::FillingRule::EvenOdd, "filling_rule_even_odd.png");
+}
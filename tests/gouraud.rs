@@ -0,0 +1,89 @@
+
+extern crate agg;
+
+/// Triangle vertices from the original AGG gouraud demo
+const X: [f64; 3] = [57.0, 369.0, 143.0];
+const Y: [f64; 3] = [60.0, 170.0, 310.0];
+
+/// Dilation to hide seams between triangles
+const D: f64 = 0.175;
+
+/// Center and three outer points, reflections of the center across each edge
+fn points() -> ((f64,f64), [(f64,f64);3]) {
+    let xc = (X[0] + X[1] + X[2]) / 3.0;
+    let yc = (Y[0] + Y[1] + Y[2]) / 3.0;
+    let outer = |i: usize, j: usize| {
+        let mx = (X[i] + X[j]) / 2.0;
+        let my = (Y[i] + Y[j]) / 2.0;
+        (mx - (xc - mx), my - (yc - my))
+    };
+    ((xc, yc), [outer(0,1), outer(1,2), outer(2,0)])
+}
+
+#[test]
+fn gouraud() {
+    let (w,h) = (400,320);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+
+    let colors = [agg::Rgba8::new(255, 0, 0, 255),
+                  agg::Rgba8::new(0, 255, 0, 255),
+                  agg::Rgba8::new(0, 0, 255, 255)];
+    let white = agg::Rgba8::new(255, 255, 255, 255);
+    let black = agg::Rgba8::new(0, 0, 0, 255);
+
+    let ((xc, yc), outer) = points();
+    let mut span = agg::SpanGouraudRgba::new(white, white, white,
+                                             0.0, 0.0, 1.0, 0.0, 0.0, 1.0, D);
+    for i in 0 .. 3 {
+        let j = (i + 1) % 3;
+        // Inner triangle, shading to white at the center
+        span.colors(colors[i], colors[j], white);
+        span.triangle(X[i], Y[i], X[j], Y[j], xc, yc, D);
+        ras.reset();
+        ras.add_path(&span);
+        agg::render_scanlines_aa(&mut ras, &mut ren_base, &mut span);
+
+        // Outer triangle, shading to black at the outer point
+        span.colors(colors[i], colors[j], black);
+        span.triangle(X[i], Y[i], X[j], Y[j], outer[i].0, outer[i].1, D);
+        ras.reset();
+        ras.add_path(&span);
+        agg::render_scanlines_aa(&mut ras, &mut ren_base, &mut span);
+    }
+
+    ren_base.to_file("tests/tmp/gouraud.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/gouraud.png", "images/gouraud.png").unwrap());
+}
+
+#[test]
+fn gouraud_gray() {
+    let (w,h) = (400,320);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+
+    let values = [agg::Gray8::new(0), agg::Gray8::new(96), agg::Gray8::new(192)];
+    let center = agg::Gray8::new(255);
+
+    let ((xc, yc), _) = points();
+    for i in 0 .. 3 {
+        let j = (i + 1) % 3;
+        let mut span = agg::SpanGouraudGray::new(values[i], values[j], center,
+                                                 X[i], Y[i], X[j], Y[j], xc, yc,
+                                                 D);
+        ras.reset();
+        ras.add_path(&span);
+        agg::render_scanlines_aa(&mut ras, &mut ren_base, &mut span);
+    }
+
+    ren_base.to_file("tests/tmp/gouraud_gray.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/gouraud_gray.png", "images/gouraud_gray.png").unwrap());
+}
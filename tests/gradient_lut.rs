@@ -0,0 +1,69 @@
+
+extern crate agg;
+
+#[test]
+fn gradient_lut() {
+    let (w,h) = (300,200);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+
+    // Checkerboard background to show transparency
+    for i in 0 .. 15 {
+        for j in 0 .. 10 {
+            if (i + j) % 2 == 0 {
+                let (x, y) = (f64::from(i) * 20.0, f64::from(j) * 20.0);
+                let mut path = agg::Path::new();
+                path.move_to(x, y);
+                path.line_to(x + 20.0, y);
+                path.line_to(x + 20.0, y + 20.0);
+                path.line_to(x, y + 20.0);
+                path.close_polygon();
+                ras.reset();
+                ras.add_path(&path);
+                agg::render_scanlines_aa_solid(&mut ras, &mut ren_base,
+                                               agg::Rgba8::new(200, 200, 200, 255));
+            }
+        }
+    }
+
+    // Linear, multiple stops fading to transparent
+    let mut lut = agg::GradientLut::new();
+    lut.add_color(0.0, agg::Rgba8::new(220, 40, 40, 255));
+    lut.add_color(0.3, agg::Rgba8::new(240, 200, 40, 255));
+    lut.add_color(0.6, agg::Rgba8::new(40, 160, 60, 200));
+    lut.add_color(1.0, agg::Rgba8::new(40, 60, 220, 0));
+    lut.build_lut();
+
+    let mut mtx = agg::Transform::new_translate(20.0, 0.0);
+    mtx.invert();
+    let span = agg::SpanGradient::new(mtx, agg::GradientX{}, lut.colors(), 0.0, 260.0);
+    let mut ren = agg::RenderingScanlineAA::new(&mut ren_base, span);
+    let mut rect = agg::RoundedRect::new(20.0, 20.0, 280.0, 80.0, 10.0);
+    rect.calc();
+    ras.reset();
+    ras.add_path(&rect);
+    agg::render_scanlines(&mut ras, &mut ren);
+
+    // Radial highlight, opaque center to transparent edge
+    let mut lut = agg::GradientLut::new();
+    lut.add_color(0.0, agg::Rgba8::new(255, 255, 255, 255));
+    lut.add_color(0.2, agg::Rgba8::new(60, 120, 230, 255));
+    lut.add_color(1.0, agg::Rgba8::new(20, 40, 120, 0));
+    lut.build_lut();
+
+    let mut mtx = agg::Transform::new_translate(150.0, 140.0);
+    mtx.invert();
+    let span = agg::SpanGradient::new(mtx, agg::GradientRadialD{}, lut.colors(), 0.0, 55.0);
+    let mut ren = agg::RenderingScanlineAA::new(&mut ren_base, span);
+    let circle = agg::Ellipse::new(150.0, 140.0, 55.0, 55.0, 64);
+    ras.reset();
+    ras.add_path(&circle);
+    agg::render_scanlines(&mut ras, &mut ren);
+
+    ren_base.to_file("tests/tmp/gradient_lut.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/gradient_lut.png", "images/gradient_lut.png").unwrap());
+}
@@ -0,0 +1,65 @@
+
+extern crate agg;
+
+fn lut() -> agg::GradientLut {
+    let mut lut = agg::GradientLut::new();
+    lut.add_color(0.0, agg::Rgba8::new(30, 60, 160, 255));
+    lut.add_color(1.0, agg::Rgba8::new(250, 210, 40, 255));
+    lut.build_lut();
+    lut
+}
+
+/// Fill a bar with a gradient starting 20 pixels into the bar
+fn bar<T,G>(ren_base: &mut agg::RenderingBase<T>, ras: &mut agg::RasterizerScanline,
+            gradient: G, y: f64)
+    where T: agg::Pixel, G: agg::Gradient
+{
+    let mut mtx = agg::Transform::new_translate(40.0, y);
+    mtx.invert();
+    let span = agg::SpanGradient::new(mtx, gradient, lut().colors(), 0.0, 40.0);
+    let mut ren = agg::RenderingScanlineAA::new(ren_base, span);
+    let mut rect = agg::RoundedRect::new(20.0, y, 280.0, y + 30.0, 6.0);
+    rect.calc();
+    ras.reset();
+    ras.add_path(&rect);
+    agg::render_scanlines(ras, &mut ren);
+}
+
+/// Fill a circle with a radial gradient
+fn circle<T,G>(ren_base: &mut agg::RenderingBase<T>, ras: &mut agg::RasterizerScanline,
+               gradient: G, x: f64)
+    where T: agg::Pixel, G: agg::Gradient
+{
+    let (y, r) = (160.0, 45.0);
+    let mut mtx = agg::Transform::new_translate(x, y);
+    mtx.invert();
+    let span = agg::SpanGradient::new(mtx, gradient, lut().colors(), 0.0, 15.0);
+    let mut ren = agg::RenderingScanlineAA::new(ren_base, span);
+    let path = agg::Ellipse::new(x, y, r, r, 64);
+    ras.reset();
+    ras.add_path(&path);
+    agg::render_scanlines(ras, &mut ren);
+}
+
+#[test]
+fn gradient_spread() {
+    let (w,h) = (300,220);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+
+    // Pad, Repeat and Reflect
+    bar(&mut ren_base, &mut ras, agg::GradientX{}, 10.0);
+    bar(&mut ren_base, &mut ras, agg::GradientRepeat::new(agg::GradientX{}), 45.0);
+    bar(&mut ren_base, &mut ras, agg::GradientReflect::new(agg::GradientX{}), 80.0);
+
+    circle(&mut ren_base, &mut ras, agg::GradientRadialD{}, 55.0);
+    circle(&mut ren_base, &mut ras, agg::GradientRepeat::new(agg::GradientRadialD{}), 150.0);
+    circle(&mut ren_base, &mut ras, agg::GradientReflect::new(agg::GradientRadialD{}), 245.0);
+
+    ren_base.to_file("tests/tmp/gradient_spread.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/gradient_spread.png", "images/gradient_spread.png").unwrap());
+}
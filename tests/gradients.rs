@@ -0,0 +1,52 @@
+
+extern crate agg;
+
+fn colors() -> Vec<agg::Rgb8> {
+    (0 .. 256).map(|i| {
+        let a = i as u8;
+        agg::Rgb8::new(agg::math::lerp_u8(30, 250, a),
+                       agg::math::lerp_u8(60, 210, a),
+                       agg::math::lerp_u8(160, 40, a))
+    }).collect()
+}
+
+/// Fill a tile with a gradient centered in the tile
+fn tile<T,G>(ren_base: &mut agg::RenderingBase<T>, ras: &mut agg::RasterizerScanline,
+             gradient: G, x: f64, y: f64)
+    where T: agg::Pixel, G: agg::Gradient
+{
+    let mut mtx = agg::Transform::new_translate(x + 50.0, y + 50.0);
+    mtx.invert();
+    let span = agg::SpanGradient::new(mtx, gradient, &colors(), 0.0, 45.0);
+    let mut ren = agg::RenderingScanlineAA::new(ren_base, span);
+    let mut path = agg::RoundedRect::new(x + 5.0, y + 5.0, x + 95.0, y + 95.0, 8.0);
+    path.calc();
+    ras.reset();
+    ras.add_path(&path);
+    agg::render_scanlines(ras, &mut ren);
+}
+
+#[test]
+fn gradients() {
+    let (w,h) = (300,300);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+
+    tile(&mut ren_base, &mut ras, agg::GradientX{},         0.0,   0.0);
+    tile(&mut ren_base, &mut ras, agg::GradientY{},       100.0,   0.0);
+    tile(&mut ren_base, &mut ras, agg::GradientRadial{},  200.0,   0.0);
+    tile(&mut ren_base, &mut ras, agg::GradientRadialD{},   0.0, 100.0);
+    tile(&mut ren_base, &mut ras, agg::GradientRadialFocus::new(45.0, 15.0, -15.0),
+         100.0, 100.0);
+    tile(&mut ren_base, &mut ras, agg::GradientDiamond{}, 200.0, 100.0);
+    tile(&mut ren_base, &mut ras, agg::GradientXY{},        0.0, 200.0);
+    tile(&mut ren_base, &mut ras, agg::GradientSqrtXY{},  100.0, 200.0);
+    tile(&mut ren_base, &mut ras, agg::GradientConic{},   200.0, 200.0);
+
+    ren_base.to_file("tests/tmp/gradients.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/gradients.png", "images/gradients.png").unwrap());
+}
@@ -0,0 +1,93 @@
+
+extern crate agg;
+
+/// Small image of colored squares with a diagonal line
+fn source() -> agg::Pixfmt<agg::Rgb8> {
+    let mut pix = agg::Pixfmt::<agg::Rgb8>::new(8,8);
+    let colors = [agg::Rgba8::new(200, 40, 60, 255),
+                  agg::Rgba8::new(40, 80, 200, 255),
+                  agg::Rgba8::new(250, 210, 40, 255),
+                  agg::Rgba8::new(255, 255, 255, 255)];
+    for y in 0 .. 8 {
+        for x in 0 .. 8 {
+            let c = if x == y { agg::Rgba8::black() } else { colors[x / 4 + 2 * (y / 4)] };
+            pix.copy_pixel(x, y, c);
+        }
+    }
+    pix
+}
+
+/// Fill a tile with the image scaled to the tile
+fn tile<T,S>(ren_base: &mut agg::RenderingBase<T>, ras: &mut agg::RasterizerScanline,
+             span: &mut S, x: f64, y: f64)
+    where T: agg::Pixel, S: agg::SpanGenerator
+{
+    let mut path = agg::Path::new();
+    path.move_to(x + 4.0, y + 4.0);
+    path.line_to(x + 76.0, y + 4.0);
+    path.line_to(x + 76.0, y + 76.0);
+    path.line_to(x + 4.0, y + 76.0);
+    path.close_polygon();
+    ras.reset();
+    ras.add_path(&path);
+    agg::render_scanlines_aa(ras, ren_base, span);
+}
+
+/// Transform from the tile to the image
+fn transform(x: f64, y: f64) -> agg::Transform {
+    let mut mtx = agg::Transform::new_scale(9.0, 9.0);
+    mtx.translate(x + 4.0, y + 4.0);
+    mtx.invert();
+    mtx
+}
+
+#[test]
+fn image_filters() {
+    let (w,h) = (480,240);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+    let img = source();
+    let bg = agg::Rgba8::new(255, 255, 255, 255);
+
+    let src = agg::ImageAccessorClip::new(&img, bg);
+    let mut span = agg::SpanImageFilterRgbaNn::new(src, transform(0.0, 0.0));
+    tile(&mut ren_base, &mut ras, &mut span, 0.0, 0.0);
+
+    let src = agg::ImageAccessorClip::new(&img, bg);
+    let mut span = agg::SpanImageFilterRgbaBilinear::new(src, transform(80.0, 0.0));
+    tile(&mut ren_base, &mut ras, &mut span, 80.0, 0.0);
+
+    let luts = vec![
+        agg::ImageFilterLut::new(&agg::ImageFilterBilinear{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterBicubic{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterSpline16{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterSpline36{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterHanning{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterHamming{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterHermite{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterKaiser::default(), true),
+        agg::ImageFilterLut::new(&agg::ImageFilterQuadric{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterCatrom{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterGaussian{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterBessel{}, true),
+        agg::ImageFilterLut::new(&agg::ImageFilterMitchell::default(), true),
+        agg::ImageFilterLut::new(&agg::ImageFilterSinc::new(4.0), true),
+        agg::ImageFilterLut::new(&agg::ImageFilterLanczos::new(4.0), true),
+        agg::ImageFilterLut::new(&agg::ImageFilterBlackman::new(4.0), true),
+    ];
+    for (i, lut) in luts.into_iter().enumerate() {
+        let i = i + 2;
+        let x = (i % 6) as f64 * 80.0;
+        let y = (i / 6) as f64 * 80.0;
+        let src = agg::ImageAccessorClip::new(&img, bg);
+        let mut span = agg::SpanImageFilterRgba::new(src, transform(x, y), lut);
+        tile(&mut ren_base, &mut ras, &mut span, x, y);
+    }
+
+    ren_base.to_file("tests/tmp/image_filters.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/image_filters.png", "images/image_filters.png").unwrap());
+}
@@ -0,0 +1,84 @@
+
+extern crate agg;
+use agg::Pixel;
+
+/// Checkerboard with a circle in the center
+fn source() -> agg::Pixfmt<agg::Rgb8> {
+    let colors = [agg::Rgba8::new(40, 80, 200, 255),
+                  agg::Rgba8::new(250, 210, 40, 255)];
+    let mut pix = agg::Pixfmt::<agg::Rgb8>::new(300,300);
+    for y in 0 .. 300 {
+        for x in 0 .. 300 {
+            pix.copy_pixel(x, y, colors[(x / 30 + y / 30) % 2]);
+        }
+    }
+    let mut ren_base = agg::RenderingBase::new(pix);
+    let mut ras = agg::RasterizerScanline::new();
+    ras.add_path(&agg::Ellipse::new(150.0, 150.0, 90.0, 90.0, 128));
+    agg::render_scanlines_aa_solid(&mut ras, &mut ren_base, agg::Rgba8::new(200, 40, 60, 255));
+    ren_base.pixf
+}
+
+#[test]
+fn image_transforms() {
+    let (w,h) = (400,400);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let img = source();
+
+    // Rotate and scale the image about its center, then move it to the
+    //   center of the output
+    let mut mtx = agg::Transform::new_translate(-150.0, -150.0);
+    mtx.rotate(30.0_f64.to_radians());
+    mtx.scale(1.1, 1.1);
+    mtx.translate(200.0, 200.0);
+    mtx.invert();
+
+    let src = agg::ImageAccessorClip::new(&img, agg::Rgba8::new(0, 102, 0, 128));
+    let lut = agg::ImageFilterLut::new(&agg::ImageFilterSpline36{}, true);
+    let mut span = agg::SpanImageFilterRgba::new(src, mtx, lut);
+
+    let mut ras = agg::RasterizerScanline::new();
+    ras.add_path(&agg::Ellipse::new(200.0, 200.0, 190.0, 190.0, 128));
+    agg::render_scanlines_aa(&mut ras, &mut ren_base, &mut span);
+
+    ren_base.to_file("tests/tmp/image_transforms.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/image_transforms.png", "images/image_transforms.png").unwrap());
+}
+
+/// Fill a circle over a white background with an image span
+fn render_image<S: agg::SpanGenerator>(span: &mut S) -> agg::RenderingBase<agg::Pixfmt<agg::Rgb8>> {
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(40,40));
+    ren_base.clear(agg::Rgba8::white());
+    let mut ras = agg::RasterizerScanline::new();
+    ras.add_path(&agg::Ellipse::new(20.0, 20.0, 20.0, 20.0, 64));
+    agg::render_scanlines_aa(&mut ras, &mut ren_base, span);
+    ren_base
+}
+
+#[test]
+fn image_transparent_border() {
+    // Opaque red image on a transparent background, scaled up
+    let mut img = agg::Pixfmt::<agg::Rgba8>::new(2,2);
+    img.fill(agg::Rgba8::new(255, 0, 0, 255));
+    let mut mtx = agg::Transform::new_scale(10.0, 10.0);
+    mtx.translate(10.0, 10.0);
+    mtx.invert();
+    let src = || agg::ImageAccessorClip::new(&img, agg::Rgba8::new(0, 0, 0, 0));
+
+    let bilinear = render_image(&mut agg::SpanImageFilterRgbaBilinear::new(src(), mtx));
+    let lut = agg::ImageFilterLut::new(&agg::ImageFilterBilinear{}, true);
+    let filtered = render_image(&mut agg::SpanImageFilterRgba::new(src(), mtx, lut));
+
+    for ren_base in &[bilinear, filtered] {
+        // Edges fade from red to white without darkening
+        let p = ren_base.pixel(10, 20);
+        assert!(p.g > 0 && p.g < 255, "{:?}", p);
+        assert!(ren_base.pixels().all(|p| p.r == 255 && p.g == p.b), "{:?}",
+                ren_base.pixels().find(|p| p.r != 255 || p.g != p.b));
+        assert_eq!(ren_base.pixel(20, 20), agg::Rgb8::new(255, 0, 0));
+    }
+}
@@ -0,0 +1,88 @@
+
+extern crate agg;
+use agg::Render;
+
+const VIEWPORTS : [(i64,i64,i64,i64); 3] = [(10, 10, 140, 90), (160, 10, 289, 90), (40, 110, 259, 189)];
+
+/// Draw a gradient ellipse and a zig-zag line with `ren`
+fn scene<R: Render>(ren: &mut R, ras: &mut agg::RasterizerScanline) {
+    ras.reset();
+    let mut path = agg::Path::new();
+    path.move_to(-20.0, 200.0);
+    for i in 1 .. 8 {
+        let x = -20.0 + 50.0 * f64::from(i);
+        let y = if i % 2 == 0 { 200.0 } else { 0.0 };
+        path.line_to(x, y);
+    }
+    let mut stroke = agg::Stroke::new(path);
+    stroke.width(8.0);
+    ras.add_path(&stroke);
+    ren.color(agg::Rgba8::new(30, 30, 200, 200));
+    agg::render_scanlines(ras, ren);
+}
+
+fn ellipse<T: agg::Pixel>(ren_base: &mut agg::RenderingBase<T>,
+                          ras: &mut agg::RasterizerScanline,
+                          mclip: bool) {
+    let mut lut = agg::GradientLut::new();
+    lut.add_color(0.0, agg::Rgba8::new(240, 200, 40, 255));
+    lut.add_color(1.0, agg::Rgba8::new(200, 30, 30, 255));
+    lut.build_lut();
+    let mut mtx = agg::Transform::new_translate(150.0, 100.0);
+    mtx.invert();
+    let span = agg::SpanGradient::new(mtx, agg::GradientRadialD{}, lut.colors(), 0.0, 120.0);
+    let ren = agg::RenderingScanlineAA::new(ren_base, span);
+    ras.reset();
+    ras.add_path(&agg::Ellipse::new(150.0, 100.0, 140.0, 80.0, 128));
+    if mclip {
+        let mut ren = agg::RendererMClip::new(ren);
+        for &(x1,y1,x2,y2) in &VIEWPORTS {
+            ren.add_clip_box(x1, y1, x2, y2);
+        }
+        agg::render_scanlines(ras, &mut ren);
+    } else {
+        let mut ren = ren;
+        agg::render_scanlines(ras, &mut ren);
+    }
+}
+
+fn new_base() -> agg::RenderingBase<agg::Pixfmt<agg::Rgb8>> {
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(300, 200));
+    ren_base.clear( agg::Rgba8::new(230, 230, 230, 255) );
+    ren_base
+}
+
+#[test]
+fn mclip() {
+    let mut ras = agg::RasterizerScanline::new();
+
+    // Rasterize once, clipped to each viewport
+    let mut ren_base = new_base();
+    ellipse(&mut ren_base, &mut ras, true);
+    let mut ren = agg::RendererMClip::new(agg::RenderingScanlineAASolid::with_base(&mut ren_base));
+    for &(x1,y1,x2,y2) in VIEWPORTS.iter() {
+        ren.add_clip_box(x2, y2, x1, y1);
+    }
+    assert_eq!(ren.clip_boxes().len(), 3);
+    scene(&mut ren, &mut ras);
+
+    // Nothing drawn without clip boxes and no visibility
+    ren.reset_clipping(false);
+    ren.color(agg::Rgba8::black());
+    ras.reset();
+    ras.add_path(&agg::Ellipse::new(150.0, 100.0, 200.0, 200.0, 64));
+    agg::render_scanlines(&mut ras, &mut ren);
+
+    // Same as rasterizing once for each viewport
+    let mut expected = new_base();
+    for &(x1,y1,x2,y2) in VIEWPORTS.iter() {
+        expected.clip_box(x1, y1, x2, y2);
+        ellipse(&mut expected, &mut ras, false);
+        let mut ren = agg::RenderingScanlineAASolid::with_base(&mut expected);
+        scene(&mut ren, &mut ras);
+    }
+    assert!(ren_base.pixels().eq(expected.pixels()));
+
+    ren_base.to_file("tests/tmp/mclip.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/mclip.png", "images/mclip.png").unwrap());
+}
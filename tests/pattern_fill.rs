@@ -0,0 +1,75 @@
+
+extern crate agg;
+use agg::Render;
+
+/// Pattern tile, a triangle in one corner and a line on the right edge
+///   so wrapping and edge extension are visible
+fn pattern() -> agg::Pixfmt<agg::Rgb8> {
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(32,32);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(250, 230, 180, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+    ras.move_to(2.0, 2.0);
+    ras.line_to(24.0, 4.0);
+    ras.line_to(6.0, 20.0);
+    let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+    ren.color(agg::Rgba8::new(160, 40, 60, 255));
+    agg::render_scanlines(&mut ras, &mut ren);
+    ren_base.pixf.copy_vline(31, 0, 32, agg::Rgba8::new(40, 80, 200, 255));
+    ren_base.pixf
+}
+
+/// Fill a rounded rectangle in a 200x200 panel at (x,y)
+fn panel<T,S>(ren_base: &mut agg::RenderingBase<T>, ras: &mut agg::RasterizerScanline,
+              span: &mut S, x: f64, y: f64)
+    where T: agg::Pixel, S: agg::SpanGenerator
+{
+    let mut path = agg::RoundedRect::new(x + 10.0, y + 10.0, x + 190.0, y + 190.0, 20.0);
+    path.calc();
+    ras.reset();
+    ras.add_path(&path);
+    agg::render_scanlines_aa(ras, ren_base, span);
+}
+
+#[test]
+fn pattern_fill() {
+    let (w,h) = (400,400);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+    let img = pattern();
+
+    // Repeat in x and y
+    let src = agg::ImageAccessorWrap::new(&img, agg::WrapMode::Repeat, agg::WrapMode::Repeat);
+    let mut span = agg::SpanImageFilterRgbaNn::new(src, agg::Transform::new());
+    panel(&mut ren_base, &mut ras, &mut span, 0.0, 0.0);
+
+    // Reflect in x and y
+    let src = agg::ImageAccessorWrap::new(&img, agg::WrapMode::Reflect, agg::WrapMode::Reflect);
+    let mut span = agg::SpanImageFilterRgbaNn::new(src, agg::Transform::new());
+    panel(&mut ren_base, &mut ras, &mut span, 200.0, 0.0);
+
+    // Repeat in x, Reflect in y, rotated and scaled
+    let mut mtx = agg::Transform::new_rotate(20.0_f64.to_radians());
+    mtx.scale(0.75, 0.75);
+    mtx.translate(0.0, 200.0);
+    mtx.invert();
+    let src = agg::ImageAccessorWrap::new(&img, agg::WrapMode::Repeat, agg::WrapMode::Reflect);
+    let mut span = agg::SpanImageFilterRgbaBilinear::new(src, mtx);
+    panel(&mut ren_base, &mut ras, &mut span, 0.0, 200.0);
+
+    // Edges extended, pattern placed in the center
+    let mut mtx = agg::Transform::new_scale(2.0, 2.0);
+    mtx.translate(268.0, 268.0);
+    mtx.invert();
+    let src = agg::ImageAccessorClone::new(&img);
+    let mut span = agg::SpanImageFilterRgbaBilinear::new(src, mtx);
+    panel(&mut ren_base, &mut ras, &mut span, 200.0, 200.0);
+
+    ren_base.to_file("tests/tmp/pattern_fill.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/pattern_fill.png", "images/pattern_fill.png").unwrap());
+}
@@ -0,0 +1,86 @@
+
+extern crate agg;
+use agg::{Pixel,Source};
+
+/// Horizontal gradient with 16-bit colors
+struct Gradient16 {
+    x1: f64,
+    x2: f64,
+    c1: agg::Rgba16,
+    c2: agg::Rgba16,
+}
+
+impl agg::SpanGenerator for Gradient16 {
+    type Color = agg::Rgba16;
+    fn generate(&mut self, x: i64, _y: i64, len: usize, span: &mut [agg::Rgba16]) {
+        let lerp = |a: u16, b: u16, k: f64| (f64::from(a) + (f64::from(b) - f64::from(a)) * k).round() as u16;
+        for (i, s) in span.iter_mut().take(len).enumerate() {
+            let k = ((x as f64 + i as f64 + 0.5 - self.x1) / (self.x2 - self.x1)).clamp(0.0, 1.0);
+            *s = agg::Rgba16::new(lerp(self.c1.r, self.c2.r, k),
+                                  lerp(self.c1.g, self.c2.g, k),
+                                  lerp(self.c1.b, self.c2.b, k),
+                                  lerp(self.c1.a, self.c2.a, k));
+        }
+    }
+}
+
+/// Fill the rectangle (x1,y1) - (x2,y2) with a gradient from `c1` to `c2`
+fn fill<T: Pixel>(ren_base: &mut agg::RenderingBase<T>, ras: &mut agg::RasterizerScanline,
+                  (x1,y1): (f64,f64), (x2,y2): (f64,f64), c1: agg::Rgba16, c2: agg::Rgba16) {
+    let mut path = agg::Path::new();
+    path.move_to(x1, y1);
+    path.line_to(x2, y1);
+    path.line_to(x2, y2);
+    path.line_to(x1, y2);
+    path.close_polygon();
+    ras.reset();
+    ras.add_path(&path);
+    let mut span = Gradient16 { x1, x2, c1, c2 };
+    agg::render_scanlines_aa(ras, ren_base, &mut span);
+}
+
+#[test]
+fn pixfmt_rgb16() {
+    let (w,h) = (1024,100);
+    let pixf = agg::Pixfmt::<agg::Rgb16>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::white() );
+
+    let mut ras = agg::RasterizerScanline::new();
+    // A shallow gradient, a single 8-bit step over a third of the image
+    fill(&mut ren_base, &mut ras, (0.0, 0.0), (1024.0, 50.0),
+         agg::Rgba16::new(30000, 30000, 30000, 65535),
+         agg::Rgba16::new(30770, 30300, 30100, 65535));
+    // A full gradient, partially transparent
+    fill(&mut ren_base, &mut ras, (0.0, 50.0), (1024.0, 100.0),
+         agg::Rgba16::new(0, 0, 65535, 65535),
+         agg::Rgba16::new(65535, 0, 0, 32768));
+
+    // Every column of the shallow gradient is distinct in 16-bit
+    let reds : Vec<_> = (0 .. w).map(|x| ren_base.pixf.raw((x, 10)).r).collect();
+    assert!(reds.windows(2).filter(|v| v[1] > v[0]).count() > 700);
+    let reds8 : Vec<_> = (0 .. w).map(|x| ren_base.pixf.get((x, 10)).r).collect();
+    assert!(reds8.windows(2).filter(|v| v[1] > v[0]).count() < 5);
+
+    ren_base.to_file("tests/tmp/pixfmt_rgb16.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/pixfmt_rgb16.png", "images/pixfmt_rgb16.png").unwrap());
+}
+
+#[test]
+fn pixfmt_gray16() {
+    let (w,h) = (256,64);
+    let pixf = agg::Pixfmt::<agg::Gray16>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::black() );
+
+    let mut ras = agg::RasterizerScanline::new();
+    ras.add_path(&agg::Ellipse::new(128.0, 32.0, 120.0, 28.0, 128));
+    let mut span = Gradient16 { x1: 8.0, x2: 248.0,
+                                c1: agg::Rgba16::new(0, 0, 0, 65535),
+                                c2: agg::Rgba16::white() };
+    agg::render_scanlines_aa(&mut ras, &mut ren_base, &mut span);
+    assert_eq!(ren_base.pixf.raw((128, 32)), agg::Gray16::new(32904));
+
+    ren_base.to_file("tests/tmp/pixfmt_gray16.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/pixfmt_gray16.png", "images/pixfmt_gray16.png").unwrap());
+}
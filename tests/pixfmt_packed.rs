@@ -0,0 +1,19 @@
+
+extern crate agg;
+
+mod common;
+use common::lion;
+
+#[test]
+fn pixfmt_rgb565() {
+    let ren_base = lion(agg::Pixfmt::<agg::Rgb565>::new(400,400));
+    ren_base.to_file("tests/tmp/pixfmt_rgb565.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/pixfmt_rgb565.png", "images/pixfmt_rgb565.png").unwrap());
+}
+
+#[test]
+fn pixfmt_rgb555() {
+    let ren_base = lion(agg::Pixfmt::<agg::Rgb555>::new(400,400));
+    ren_base.to_file("tests/tmp/pixfmt_rgb555.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/pixfmt_rgb555.png", "images/pixfmt_rgb555.png").unwrap());
+}
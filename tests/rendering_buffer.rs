@@ -0,0 +1,34 @@
+
+extern crate agg;
+
+mod common;
+use common::lion;
+
+#[test]
+fn rendering_buffer_attach() {
+    let (w,h) = (400,400);
+    // Rows are padded and stored bottom-up, with a border around the image
+    let stride = (w + 16) * 3;
+    let mut data = vec![0u8; stride * (h + 8)];
+    {
+        let start = 4 * stride + 8 * 3;
+        let end = start + (h - 1) * stride + w * 3;
+        let pixf = agg::Pixfmt::<agg::Rgb8,_>::attach(&mut data[start..end], w, h, -(stride as isize));
+        let ren_base = lion(pixf);
+        ren_base.to_file("tests/tmp/rendering_buffer_attach.png").unwrap();
+        assert!(agg::ppm::img_diff("tests/tmp/rendering_buffer_attach.png", "images/lion_cw_aa.png").unwrap());
+
+        // Top row of the image is at the end of the data
+        let rbuf = ren_base.pixf.rendering_buffer();
+        assert_eq!(rbuf.stride(), -(stride as isize));
+        assert_eq!(rbuf.row(0), &rbuf.as_bytes()[(h-1)*stride ..]);
+    }
+    // Border is untouched
+    assert!(data[.. 4 * stride + 8 * 3].iter().all(|&v| v == 0));
+    for y in 4 .. h + 4 {
+        let row = &data[y * stride .. (y + 1) * stride];
+        assert!(row[.. 8 * 3].iter().all(|&v| v == 0));
+        assert!(row[(w + 8) * 3 ..].iter().all(|&v| v == 0));
+    }
+    assert!(data[(h + 4) * stride ..].iter().all(|&v| v == 0));
+}
@@ -0,0 +1,57 @@
+
+extern crate agg;
+use agg::Render;
+
+/// Horizontal gradient mask from transparent to opaque
+fn mask(w: usize, h: usize) -> agg::AlphaMaskU8<agg::Pixfmt<agg::Gray8>> {
+    let mut alpha = agg::Pixfmt::<agg::Gray8>::new(w, h);
+    for x in 0 .. w {
+        let v = (255 * x / (w - 1)) as u8;
+        alpha.copy_vline(x, 0, h, agg::Gray8::new(v));
+    }
+    agg::AlphaMaskU8::new(alpha)
+}
+
+#[test]
+fn scanline_am() {
+    let (w,h) = (300,300);
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::white() );
+
+    let mut ras = agg::RasterizerScanline::new();
+    let mut sl = agg::ScanlineU8Am::new(mask(w,h));
+
+    // Aliased
+    ras.add_path(&agg::Ellipse::new(150.0, 50.0, 140.0, 40.0, 100));
+    let mut ren = agg::RenderingScanlineBinSolid::with_base(&mut ren_base);
+    ren.color(agg::Rgba8::new(200, 0, 0, 255));
+    agg::render_scanlines_am(&mut ras, &mut ren, &mut sl);
+
+    // Anti-aliased
+    ras.reset();
+    ras.add_path(&agg::Ellipse::new(150.0, 150.0, 140.0, 40.0, 100));
+    let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+    ren.color(agg::Rgba8::new(0, 120, 0, 255));
+    agg::render_scanlines_am(&mut ras, &mut ren, &mut sl);
+
+    // Colors from a Span Generator
+    let colors : Vec<_> = (0 .. 256)
+        .map(|i| agg::Rgba8::new(0, i as u8, 255 - i as u8, 255))
+        .collect();
+    let mut mtx = agg::Transform::new_translate(150.0, 250.0);
+    mtx.invert();
+    let span = agg::SpanGradient::new(mtx, agg::GradientY{}, &colors, -40.0, 40.0);
+    let mut ren = agg::RenderingScanlineAA::new(&mut ren_base, span);
+    ras.reset();
+    ras.add_path(&agg::Ellipse::new(150.0, 250.0, 140.0, 40.0, 100));
+    agg::render_scanlines_am(&mut ras, &mut ren, &mut sl);
+
+    // Mask is transparent at the left and opaque at the right
+    assert_eq!(ren_base.pixel(20, 50), agg::Rgb8::new(251, 238, 238));
+    assert_eq!(ren_base.pixel(150, 50), agg::Rgb8::new(228, 128, 128));
+    assert_eq!(ren_base.pixel(280, 50), agg::Rgb8::new(204, 17, 17));
+
+    ren_base.to_file("tests/tmp/scanline_am.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/scanline_am.png", "images/scanline_am.png").unwrap());
+}
@@ -0,0 +1,123 @@
+
+extern crate agg;
+use agg::Render;
+use agg::Pixel;
+use agg::Source;
+
+/// Fill a path with a solid color
+fn fill<T: agg::Pixel, VS: agg::VertexSource>(ren_base: &mut agg::RenderingBase<T>,
+                                             ras: &mut agg::RasterizerScanline,
+                                             path: &VS, color: agg::Rgba8) {
+    ras.reset();
+    ras.add_path(path);
+    let mut ren = agg::RenderingScanlineAASolid::with_base(ren_base);
+    ren.color(color);
+    agg::render_scanlines(ras, &mut ren);
+}
+
+/// Zig-zag line, like a chart series
+fn series(x: f64, y: f64) -> agg::Path {
+    let mut path = agg::Path::new();
+    path.move_to(x, y);
+    for i in 1 .. 8 {
+        let dy = if i % 2 == 0 { 0.0 } else { -60.0 };
+        path.line_to(x + i as f64 * 20.0, y + dy);
+    }
+    path
+}
+
+#[test]
+fn simple_blur() {
+    let (w,h) = (400,200);
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::white() );
+    let mut ras = agg::RasterizerScanline::new();
+
+    // Drop shadow, stack blur
+    let mut stroke = agg::Stroke::new(series(30.0, 140.0));
+    stroke.width(8.0);
+    let shadow = agg::ConvTransform::new(stroke, agg::Transform::new_translate(6.0, 6.0));
+    fill(&mut ren_base, &mut ras, &shadow, agg::Rgba8::new(0, 0, 0, 128));
+    ren_base.pixf.stack_blur_region(6, 6, 0, 0, 199, 199);
+    let mut stroke = agg::Stroke::new(series(30.0, 140.0));
+    stroke.width(8.0);
+    fill(&mut ren_base, &mut ras, &stroke, agg::Rgba8::new(40, 90, 200, 255));
+
+    // Glow, recursive blur
+    let mut stroke = agg::Stroke::new(series(230.0, 140.0));
+    stroke.width(16.0);
+    fill(&mut ren_base, &mut ras, &stroke, agg::Rgba8::new(255, 200, 0, 255));
+    ren_base.pixf.recursive_blur_region(8.0, 8.0, 200, 0, 399, 199);
+    let mut stroke = agg::Stroke::new(series(230.0, 140.0));
+    stroke.width(3.0);
+    fill(&mut ren_base, &mut ras, &stroke, agg::Rgba8::new(120, 40, 0, 255));
+
+    // Regions do not bleed into each other
+    assert_eq!(ren_base.pixf.get((200, 100)), agg::Rgba8::white());
+
+    ren_base.to_file("tests/tmp/simple_blur.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/simple_blur.png", "images/simple_blur.png").unwrap());
+}
+
+#[test]
+fn simple_blur_gray() {
+    // Independent radii, only blur along x
+    let mut pix = agg::Pixfmt::<agg::Gray8>::new(20, 20);
+    pix.fill(agg::Gray8::new(0));
+    pix.copy_pixel(10, 10, agg::Gray8::new(255));
+    let mut a = agg::Pixfmt::<agg::Gray8>::new(20, 20);
+    a.fill(agg::Gray8::new(0));
+    a.copy_pixel(10, 10, agg::Gray8::new(255));
+    pix.stack_blur(4, 0);
+    a.recursive_blur(4.0, 0.0);
+    for y in 0 .. 20 {
+        for x in 0 .. 20 {
+            if y != 10 {
+                assert_eq!(pix.raw((x, y)).value, 0);
+                assert_eq!(a.raw((x, y)).value, 0);
+            }
+        }
+    }
+    assert!(pix.raw((13, 10)).value > 0);
+    assert!(a.raw((13, 10)).value > 0);
+}
+
+#[test]
+fn simple_blur_formats() {
+    // 16-bit and floating point formats keep their precision
+    let mut pix = agg::Pixfmt::<agg::Gray16>::new(5, 1);
+    pix.fill(agg::Gray16::new_with_alpha(0, 65535));
+    pix.copy_pixel(2, 0, agg::Gray16::new_with_alpha(65535, 65535));
+    pix.stack_blur(1, 0);
+    let v : Vec<_> = (0 .. 5).map(|x| pix.raw((x, 0)).value).collect();
+    assert_eq!(v, vec![0, 16383, 32767, 16383, 0]);
+
+    let mut pix = agg::Pixfmt::<agg::Rgba32>::new(5, 1);
+    pix.fill(agg::Rgba32::new(0.0, 0.0, 0.0, 1.0));
+    pix.copy_pixel(2, 0, agg::Rgba32::new(1.0, 1.0, 1.0, 1.0));
+    pix.stack_blur(1, 0);
+    let v : Vec<_> = (0 .. 5).map(|x| pix.raw((x, 0)).r).collect();
+    assert_eq!(v, vec![0.0, 0.25, 0.5, 0.25, 0.0]);
+
+    // Packed formats blur their 8-bit components
+    let mut pix = agg::Pixfmt::<agg::Rgb565>::new(5, 1);
+    pix.fill(agg::Rgb8::black());
+    pix.copy_pixel(2, 0, agg::Rgb8::white());
+    pix.stack_blur(1, 0);
+    assert_eq!(pix.raw((1, 0)), agg::Rgb565::new(63, 63, 63));
+    assert_eq!(pix.raw((2, 0)), agg::Rgb565::new(127, 127, 127));
+
+    // sRGB blurs in linear light, black and white average to about 188
+    let mut pix = agg::Pixfmt::<agg::Srgba8>::new(8, 1);
+    for x in 0 .. 8 {
+        let c = if x % 2 == 0 { agg::Srgba8::new(0, 0, 0, 255) } else { agg::Srgba8::new(255, 255, 255, 255) };
+        pix.copy_pixel(x, 0, c);
+    }
+    pix.stack_blur(1, 0);
+    for x in 1 .. 7 {
+        let c = pix.raw((x, 0)).into_slice();
+        assert!((186 ..= 189).contains(&c[0]), "{:?}", c);
+        assert_eq!(c[3], 255);
+    }
+}
@@ -0,0 +1,47 @@
+
+extern crate agg;
+
+/// Checkerboard of two colors, defined outside of the library
+struct Checker {
+    size: i64,
+    colors: [agg::Rgba8; 2],
+}
+
+impl agg::SpanGenerator for Checker {
+    type Color = agg::Rgba8;
+    fn generate(&mut self, x: i64, y: i64, len: usize, span: &mut [agg::Rgba8]) {
+        for (i, c) in span.iter_mut().enumerate().take(len) {
+            let cx = (x + i as i64).div_euclid(self.size);
+            let cy = y.div_euclid(self.size);
+            *c = self.colors[((cx + cy) & 1) as usize];
+        }
+    }
+}
+
+#[test]
+fn span_generator() {
+    let (w,h) = (200,200);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+    let colors = [agg::Rgba8::new(30, 60, 160, 255),
+                  agg::Rgba8::new(250, 210, 40, 255)];
+
+    // Through the Renderer
+    ras.add_path(&agg::Ellipse::new(60.0, 60.0, 50.0, 50.0, 64));
+    let checker = Checker { size: 10, colors };
+    let mut ren = agg::RenderingScanlineAA::new(&mut ren_base, checker);
+    agg::render_scanlines(&mut ras, &mut ren);
+
+    // Directly from the Rasterizer
+    ras.reset();
+    ras.add_path(&agg::Ellipse::new(140.0, 140.0, 50.0, 50.0, 64));
+    let mut checker = Checker { size: 5, colors };
+    agg::render_scanlines_aa(&mut ras, &mut ren_base, &mut checker);
+
+    ren_base.to_file("tests/tmp/span_generator.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/span_generator.png", "images/span_generator.png").unwrap());
+}
@@ -0,0 +1,61 @@
+
+extern crate agg;
+use agg::Render;
+use agg::Pixel;
+
+/// Stroke a line from `p1` to `p2` of `width`
+fn line<T>(ren_base: &mut agg::RenderingBase<T>, ras: &mut agg::RasterizerScanline,
+           p1: (f64,f64), p2: (f64,f64), width: f64, color: agg::Rgba8)
+    where T: agg::Pixel
+{
+    let mut path = agg::Path::new();
+    path.move_to(p1.0, p1.1);
+    path.line_to(p2.0, p2.1);
+    let mut stroke = agg::Stroke::new(path);
+    stroke.width(width);
+    ras.reset();
+    ras.add_path(&stroke);
+    let mut ren = agg::RenderingScanlineAASolid::with_base(ren_base);
+    ren.color(color);
+    agg::render_scanlines(ras, &mut ren);
+}
+
+#[test]
+fn srgb_blend() {
+    let (w,h) = (400,200);
+    let pixf = agg::Pixfmt::<agg::Srgba8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::white() );
+    for y in 0 .. h as i64 {
+        ren_base.blend_hline(200, y, 399, agg::Rgba8::black(), 255);
+    }
+
+    let mut ras = agg::RasterizerScanline::new();
+
+    // Thin lines, slightly slanted, in black on white and white on black
+    for i in 0 .. 20 {
+        let k = i as f64;
+        let width = 0.1 + k * 0.1;
+        let x = 10.0 + k * 9.0;
+        line(&mut ren_base, &mut ras, (x, 10.0), (x + 6.0, 110.0), width, agg::Rgba8::black());
+        line(&mut ren_base, &mut ras, (x + 200.0, 10.0), (x + 206.0, 110.0), width, agg::Rgba8::white());
+    }
+
+    // Translucent overlapping circles
+    let colors = [agg::Rgba8::new(255, 0, 0, 160),
+                  agg::Rgba8::new(0, 255, 0, 160),
+                  agg::Rgba8::new(0, 0, 255, 160)];
+    for (i, &c) in colors.iter().enumerate() {
+        for &x0 in &[60.0, 260.0] {
+            let x = x0 + i as f64 * 35.0;
+            ras.reset();
+            ras.add_path(&agg::Ellipse::new(x, 155.0, 35.0, 35.0, 64));
+            let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+            ren.color(c);
+            agg::render_scanlines(&mut ras, &mut ren);
+        }
+    }
+
+    ren_base.pixf.drop_alpha().to_file("tests/tmp/srgb_blend.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/srgb_blend.png", "images/srgb_blend.png").unwrap());
+}
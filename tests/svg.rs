@@ -0,0 +1,50 @@
+
+extern crate agg;
+
+#[test]
+fn svg() {
+    let doc = agg::SvgDocument::from_file("tests/icon.svg").unwrap();
+    assert_eq!((doc.width(), doc.height()), (240.0, 160.0));
+
+    // Unsupported parts are reported, not rendered
+    let unsupported : Vec<_> = doc.unsupported().iter().map(|u| match u {
+        agg::SvgUnsupported::Element { name, .. } => name.clone(),
+        agg::SvgUnsupported::Attribute { name, value, .. } => format!("{}={}", name, value),
+    }).collect();
+    assert_eq!(unsupported, vec!["text", "fill=url(#pattern)"]);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(doc.width() as usize, doc.height() as usize);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+    doc.render(&mut ren_base);
+
+    ren_base.to_file("tests/tmp/svg.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/svg.png", "images/svg.png").unwrap());
+}
+
+#[test]
+fn svg_group_opacity() {
+    // Overlapping children of a translucent group do not show through
+    //   each other, neither do the fill and stroke of a translucent shape
+    let svg = "<svg xmlns='http://www.w3.org/2000/svg' width='40' height='20'>
+                 <g opacity='0.5'>
+                   <rect x='0' y='0' width='14' height='20' fill='red'/>
+                   <rect x='6' y='0' width='14' height='20' fill='blue'/>
+                 </g>
+                 <rect x='24' y='4' width='12' height='12' fill='red' stroke='blue'
+                       stroke-width='4' opacity='0.5'/>
+               </svg>";
+    let doc = agg::SvgDocument::parse(svg).unwrap();
+    let mut ren_base = agg::RenderingBase::new(agg::Pixfmt::<agg::Rgb8>::new(40, 20));
+    ren_base.clear(agg::Rgba8::white());
+    doc.render(&mut ren_base);
+
+    let red = agg::Rgb8::new(255, 127, 127);
+    let blue = agg::Rgb8::new(127, 127, 255);
+    assert_eq!(ren_base.pixel(3, 10), red);
+    assert_eq!(ren_base.pixel(10, 10), blue);
+    assert_eq!(ren_base.pixel(17, 10), blue);
+    assert_eq!(ren_base.pixel(30, 10), red);
+    assert_eq!(ren_base.pixel(24, 10), blue);
+    assert_eq!(ren_base.pixel(38, 10), agg::Rgb8::white());
+}
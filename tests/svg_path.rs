@@ -0,0 +1,37 @@
+
+extern crate agg;
+use agg::Render;
+
+#[test]
+fn svg_path() {
+    let (w,h) = (300,200);
+
+    let pixf = agg::Pixfmt::<agg::Rgb8>::new(w,h);
+    let mut ren_base = agg::RenderingBase::new(pixf);
+    ren_base.clear( agg::Rgba8::new(255, 255, 255, 255) );
+
+    let mut ras = agg::RasterizerScanline::new();
+
+    // Heart, cubic curves with relative and smooth commands
+    let heart = "M 70,170 C 20,130 10,90 30,60 s 40,-20 40,10 \
+                 c 0,-30 20,-40 40,-10 S 120,130 70,170 Z";
+    // Arcs and quadratic curves
+    let arcs = "M160,100 a40,40 0 1,1 80,0 a20,40 0 0 0 -80,0z \
+                M170 170 q 25 -40 50 0 t 50 0";
+    let colors = [agg::Rgba8::new(200,40,60,255),
+                  agg::Rgba8::new(40,80,200,255)];
+    for (d, color) in [heart, arcs].iter().zip(colors.iter()) {
+        let path = agg::parse_svg_path(d).unwrap();
+        let curve = agg::ConvCurve::new(path);
+        let mut stroke = agg::Stroke::new(curve);
+        stroke.width(4.0);
+        ras.reset();
+        ras.add_path(&stroke);
+        let mut ren = agg::RenderingScanlineAASolid::with_base(&mut ren_base);
+        ren.color(*color);
+        agg::render_scanlines(&mut ras, &mut ren);
+    }
+
+    ren_base.to_file("tests/tmp/svg_path.png").unwrap();
+    assert!(agg::ppm::img_diff("tests/tmp/svg_path.png", "images/svg_path.png").unwrap());
+}
@@ -23,12 +23,12 @@ fn t04_solar_spectrum_alpha() {
         span[i] = agg::Rgb8::from_wavelength_gamma(380.0 + 400.0 * i as f64 / w as f64, 0.8);
     }
 
-    let mut mix = agg::AlphaMaskAdaptor::new(pix, alpha);
+    let mut mix = agg::AlphaMaskAdaptor::new(pix, agg::AlphaMaskNoClipU8::new(alpha));
 
     for i in 0 .. h {
-        mix.blend_color_hspan(0, i, w, &span, 0);
+        mix.blend_color_hspan(0, i as i64, w as i64, &span, &[], 255);
     }
-    mix.rgb.to_file("tests/tmp/agg_test_04.png").unwrap();
+    mix.pixf.to_file("tests/tmp/agg_test_04.png").unwrap();
 
     assert_eq!(agg::ppm::img_diff("tests/tmp/agg_test_04.png", "images/agg_test_04.png").unwrap(), true);
 
@@ -24,12 +24,12 @@ fn t05_solar_spectrum_alpha() {
     }
 
 
-    let mut mix = agg::AlphaMaskAdaptor::new(pix, alpha);
+    let mut mix = agg::AlphaMaskAdaptor::new(pix, agg::AlphaMaskNoClipU8::new(alpha));
 
     for i in 0 .. h {
-        mix.blend_color_hspan(0, i, w, &span, 0);
+        mix.blend_color_hspan(0, i as i64, w as i64, &span, &[], 255);
     }
-    mix.rgb.to_file("tests/tmp/agg_test_05.png").unwrap();
+    mix.pixf.to_file("tests/tmp/agg_test_05.png").unwrap();
 
     assert!(agg::ppm::img_diff("tests/tmp/agg_test_05.png", "images/agg_test_05.png").unwrap(), true);
 